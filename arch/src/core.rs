@@ -5,7 +5,14 @@ pub mod operand;
 pub mod interrupt;
 pub mod memory_cell;
 pub mod stack_trace;
+pub mod crash_report;
+#[cfg(feature = "alloc")]
+pub mod core_dump;
+#[cfg(feature = "alloc")]
+pub mod executable;
 pub mod specification;
+pub mod model;
+pub mod symbols;
 
 pub use operand::Operand;
 pub use interrupt::Interrupt;
@@ -14,3 +21,12 @@ pub use memory_cell::MemoryCell;
 pub use specification::{Specification, StaticSpecification};
 pub use registers::Registers;
 pub use stack_trace::StackTrace;
+pub use crash_report::CrashReport;
+#[cfg(feature = "alloc")]
+pub use core_dump::CoreDump;
+#[cfg(feature = "alloc")]
+pub use executable::Executable;
+pub use model::{CpuModel, Extension, ExtensionSet};
+pub use symbols::{Symbol, SymbolLookup};
+#[cfg(feature = "alloc")]
+pub use symbols::SymbolTable;
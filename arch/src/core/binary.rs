@@ -1,16 +1,46 @@
 use core::fmt::{Display, Formatter};
 
-pub struct ExtractedBinaryData<'a>(pub &'a [u8]);
+pub const INSTRUCTION_SIZE: usize = 15;
 
-impl Display for ExtractedBinaryData<'_> {
+#[derive(Debug, Clone, Copy)]
+pub struct ExtractedBinaryData {
+    pub bytes: [u8; INSTRUCTION_SIZE],
+    pub length: usize
+}
+
+impl ExtractedBinaryData {
+    #[must_use]
+    pub fn new(bytes: [u8; INSTRUCTION_SIZE]) -> Self {
+        Self {
+            bytes,
+            length: INSTRUCTION_SIZE
+        }
+    }
+
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            bytes: [0u8; INSTRUCTION_SIZE],
+            length: 0
+        }
+    }
+
+    #[must_use]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes[..self.length]
+    }
+}
+
+impl Display for ExtractedBinaryData {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        let second_to_last = self.0.len() - 1;
-        let bytes = &self.0[..second_to_last];
+        let Some((last, bytes)) = self.as_slice().split_last() else {
+            return Ok(());
+        };
 
         for byte in bytes {
             write!(f, "{byte:0>2x} ")?;
         }
 
-        write!(f, "{:0>2x}", self.0.last().unwrap())
+        write!(f, "{last:0>2x}")
     }
 }
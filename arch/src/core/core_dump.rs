@@ -0,0 +1,298 @@
+// Everything left of a machine after an unhandled interrupt, saved so it can be inspected later
+// without running the program again. The file is little endian throughout:
+//
+//     magic "VXCORE", version u16
+//     interrupt u32, model name (u8 length + bytes), memory size u64, cycles u64
+//     pc u32, sp u32, sr u8, r0..r14 u32, core id u32, core count u32, ptbr u32, pfar u32
+//     system stack (u32 count + words)
+//     devices (u32 count), each a port range (u32 start, u32 end) and its state (u32 length + text)
+//     memory pages (u32 count), each an address u32 and PAGE_SIZE bytes
+//
+// Only pages with something other than zeroes in them are stored, which keeps dumps of machines
+// with large amounts of unused memory small. The specification block is part of memory.
+//
+// A dump holds a single core, the one that raised the interrupt. On a `Machine` the registers,
+// stacks and MMU state of the other cores are not saved, only how many there were.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::ops::Range;
+use crate::core::{CpuModel, Interrupt, Registers};
+use crate::core::registers::StatusRegister;
+use crate::models::find_model;
+use crate::CPU;
+
+pub const CORE_DUMP_MAGIC: &[u8; 6] = b"VXCORE";
+pub const CORE_DUMP_VERSION: u16 = 1;
+pub const PAGE_SIZE: usize = 4096;
+
+#[derive(Debug, Clone)]
+pub struct DeviceState {
+    pub ports: Range<u32>,
+    // The device's debug representation at the time of the dump
+    pub state: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreDump {
+    pub interrupt: Interrupt,
+    pub model: &'static CpuModel,
+    pub memory_size: usize,
+    pub cycles: u64,
+    pub program_counter: u32,
+    pub stack_pointer: u32,
+    pub status_register: StatusRegister,
+    pub registers: Registers,
+    pub core_id: u32,
+    pub core_count: u32,
+    // PTBR with its paging enable bit
+    pub page_table_base: u32,
+    pub fault_address: u32,
+    pub system_stack: Vec<u32>,
+    pub devices: Vec<DeviceState>,
+    pub pages: Vec<(u32, Vec<u8>)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoreDumpError {
+    NotACoreDump,
+    UnsupportedVersion(u16),
+    Truncated,
+    UnknownModel(String),
+    UnknownInterrupt(u32),
+    Invalid(&'static str),
+}
+
+impl CoreDump {
+    #[must_use]
+    pub fn capture(interrupt: Interrupt, cpu: &CPU) -> Self {
+        let devices = cpu.io.devices()
+            .map(|(ports, device)| DeviceState { ports: ports.clone(), state: format!("{device:?}") })
+            .collect();
+
+        // Memory is at most 4 GiB, so page addresses always fit
+        #[allow(clippy::cast_possible_truncation)]
+        let pages = cpu.memory.chunks(PAGE_SIZE).enumerate()
+            .filter(|(_, page)| page.iter().any(|byte| *byte != 0))
+            .map(|(index, page)| ((index * PAGE_SIZE) as u32, page.to_vec()))
+            .collect();
+
+        Self {
+            interrupt,
+            model: cpu.model,
+            memory_size: cpu.memory.len(),
+            cycles: cpu.cycles,
+            program_counter: cpu.program_counter,
+            stack_pointer: cpu.stack_pointer,
+            status_register: cpu.status_register,
+            registers: cpu.registers.clone(),
+            core_id: cpu.core_id,
+            core_count: cpu.core_count,
+            page_table_base: cpu.mmu.page_table_base(),
+            fault_address: cpu.mmu.fault_address(),
+            system_stack: cpu.system_stack.clone(),
+            devices,
+            pages,
+        }
+    }
+
+    // A CPU in the state the dump was taken in, without any devices attached
+    #[must_use]
+    pub fn restore(&self) -> CPU<'static> {
+        let mut cpu = CPU::new_with_model(self.memory_size, self.model);
+        for (address, page) in &self.pages {
+            let start = *address as usize;
+            cpu.memory[start..start + page.len()].copy_from_slice(page);
+        }
+
+        cpu.cycles = self.cycles;
+        cpu.program_counter = self.program_counter;
+        cpu.stack_pointer = self.stack_pointer;
+        cpu.status_register = self.status_register;
+        cpu.registers = self.registers.clone();
+        cpu.core_id = self.core_id;
+        cpu.core_count = self.core_count;
+        cpu.mmu.set_page_table_base(self.page_table_base);
+        cpu.mmu.set_fault_address(self.fault_address);
+        cpu.system_stack.clone_from(&self.system_stack);
+        cpu
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(CORE_DUMP_MAGIC);
+        bytes.extend_from_slice(&CORE_DUMP_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&u32::from(self.interrupt).to_le_bytes());
+        let name = self.model.short_name.as_bytes();
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name);
+        bytes.extend_from_slice(&(self.memory_size as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.cycles.to_le_bytes());
+
+        let registers = &self.registers;
+        push_words(&mut bytes, &[self.program_counter, self.stack_pointer]);
+        bytes.push(self.status_register.into());
+        push_words(&mut bytes, &[
+            registers.r0, registers.r1, registers.r2, registers.r3, registers.r4,
+            registers.r5, registers.r6, registers.r7, registers.r8, registers.r9,
+            registers.r10, registers.r11, registers.r12, registers.r13, registers.r14,
+            self.core_id, self.core_count, self.page_table_base, self.fault_address,
+        ]);
+
+        push_length(&mut bytes, self.system_stack.len());
+        push_words(&mut bytes, &self.system_stack);
+
+        push_length(&mut bytes, self.devices.len());
+        for device in &self.devices {
+            push_words(&mut bytes, &[device.ports.start, device.ports.end]);
+            push_length(&mut bytes, device.state.len());
+            bytes.extend_from_slice(device.state.as_bytes());
+        }
+
+        push_length(&mut bytes, self.pages.len());
+        for (address, page) in &self.pages {
+            bytes.extend_from_slice(&address.to_le_bytes());
+            bytes.extend_from_slice(page);
+            // The last page of an odd-sized memory is padded out
+            bytes.resize(bytes.len() + PAGE_SIZE - page.len(), 0);
+        }
+
+        bytes
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, CoreDumpError> {
+        let mut reader = Reader { bytes };
+        if reader.take(CORE_DUMP_MAGIC.len()).ok() != Some(CORE_DUMP_MAGIC.as_slice()) {
+            return Err(CoreDumpError::NotACoreDump);
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != CORE_DUMP_VERSION {
+            return Err(CoreDumpError::UnsupportedVersion(version));
+        }
+
+        let code = reader.word()?;
+        let interrupt = Interrupt::from_code(code).ok_or(CoreDumpError::UnknownInterrupt(code))?;
+        let name_length = reader.array::<1>()?[0] as usize;
+        let name = core::str::from_utf8(reader.take(name_length)?)
+            .map_err(|_| CoreDumpError::Invalid("model name is not UTF-8"))?;
+        let model = find_model(name).ok_or_else(|| CoreDumpError::UnknownModel(name.into()))?;
+        let memory_size = u64::from_le_bytes(reader.array()?);
+        if memory_size > u64::from(u32::MAX) + 1 {
+            return Err(CoreDumpError::Invalid("memory is larger than the address space"));
+        }
+        let memory_size = usize::try_from(memory_size)
+            .map_err(|_| CoreDumpError::Invalid("memory does not fit on this host"))?;
+        let cycles = u64::from_le_bytes(reader.array()?);
+
+        let program_counter = reader.word()?;
+        let stack_pointer = reader.word()?;
+        let status_register = StatusRegister::from(reader.array::<1>()?[0]);
+        let mut words = [0; 19];
+        for word in &mut words {
+            *word = reader.word()?;
+        }
+        let [r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12, r13, r14, core_id, core_count,
+             page_table_base, fault_address] = words;
+        let registers = Registers { r0, r1, r2, r3, r4, r5, r6, r7, r8, r9, r10, r11, r12, r13, r14 };
+
+        let mut system_stack = Vec::new();
+        for _ in 0..reader.word()? {
+            system_stack.push(reader.word()?);
+        }
+
+        let mut devices = Vec::new();
+        for _ in 0..reader.word()? {
+            let ports = reader.word()?..reader.word()?;
+            let length = reader.word()? as usize;
+            let state = core::str::from_utf8(reader.take(length)?)
+                .map_err(|_| CoreDumpError::Invalid("device state is not UTF-8"))?;
+            devices.push(DeviceState { ports, state: state.into() });
+        }
+
+        let mut pages = Vec::new();
+        for _ in 0..reader.word()? {
+            let address = reader.word()?;
+            let start = address as usize;
+            if !start.is_multiple_of(PAGE_SIZE) || start >= memory_size {
+                return Err(CoreDumpError::Invalid("page outside of memory"));
+            }
+            let page = reader.take(PAGE_SIZE)?;
+            pages.push((address, page[..PAGE_SIZE.min(memory_size - start)].to_vec()));
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(CoreDumpError::Invalid("trailing data"));
+        }
+
+        Ok(Self {
+            interrupt,
+            model,
+            memory_size,
+            cycles,
+            program_counter,
+            stack_pointer,
+            status_register,
+            registers,
+            core_id,
+            core_count,
+            page_table_base,
+            fault_address,
+            system_stack,
+            devices,
+            pages,
+        })
+    }
+}
+
+fn push_words(bytes: &mut Vec<u8>, words: &[u32]) {
+    for word in words {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+}
+
+fn push_length(bytes: &mut Vec<u8>, length: usize) {
+    #[allow(clippy::cast_possible_truncation)]
+    bytes.extend_from_slice(&(length as u32).to_le_bytes());
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], CoreDumpError> {
+        if self.bytes.len() < length {
+            return Err(CoreDumpError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], CoreDumpError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn word(&mut self) -> Result<u32, CoreDumpError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+}
+
+impl Display for CoreDumpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CoreDumpError::NotACoreDump => write!(f, "not a core dump"),
+            CoreDumpError::UnsupportedVersion(version) => write!(f, "unsupported core dump version {version}"),
+            CoreDumpError::Truncated => write!(f, "core dump is truncated"),
+            CoreDumpError::UnknownModel(name) => write!(f, "unknown CPU model `{name}`"),
+            CoreDumpError::UnknownInterrupt(code) => write!(f, "unknown interrupt {code:0>2x}"),
+            CoreDumpError::Invalid(reason) => write!(f, "invalid core dump: {reason}"),
+        }
+    }
+}
@@ -0,0 +1,184 @@
+// The same state a stack trace shows, written as JSON for tools that have to parse it. Formats
+// straight into the output like StackTrace does, so reports can be produced without allocating.
+
+use core::fmt::{Display, Formatter, Write};
+use crate::core::Interrupt;
+use crate::core::registers::StatusRegister;
+use crate::core::stack_trace::{fault_reason, Disassembly};
+use crate::core::symbols::SymbolLookup;
+use crate::CPU;
+use crate::cpu::{AddressTranslation, Decoder};
+use crate::cpu::mmu::Access;
+
+pub const DEFAULT_MEMORY_WINDOW: u32 = 64;
+
+pub struct CrashReport<'a, 'b> {
+    cpu: &'a CPU<'b>,
+    interrupt: Interrupt,
+    symbols: Option<&'a dyn SymbolLookup>,
+    memory_window: u32
+}
+
+impl<'a, 'b> CrashReport<'a, 'b> {
+    #[must_use]
+    pub fn new(interrupt: Interrupt, cpu: &'a CPU<'b>) -> Self {
+        Self {
+            cpu,
+            interrupt,
+            symbols: None,
+            memory_window: DEFAULT_MEMORY_WINDOW
+        }
+    }
+
+    #[must_use]
+    pub fn with_symbols(mut self, symbols: &'a dyn SymbolLookup) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    // Bytes of memory to include, centered on the program counter
+    #[must_use]
+    pub fn with_memory_window(mut self, size: u32) -> Self {
+        self.memory_window = size;
+        self
+    }
+
+    fn write_symbol(&self, f: &mut Formatter<'_>, address: u32) -> core::fmt::Result {
+        match self.symbols.and_then(|symbols| symbols.lookup(address)) {
+            Some(symbol) => write!(f, "{}", JsonString(symbol)),
+            None => f.write_str("null")
+        }
+    }
+
+    fn write_status_register(f: &mut Formatter<'_>, status_register: StatusRegister) -> core::fmt::Result {
+        write!(f, "{{\"raw\": {}, \"negative\": {}, \"overflow\": {}, \"double_fault\": {}, \"interrupt\": {}, \
+                   \"interrupt_disable\": {}, \"zero\": {}, \"carry\": {}}}",
+               u8::from(status_register), status_register.negative, status_register.overflow,
+               status_register.double_fault, status_register.interrupt, status_register.interrupt_disable,
+               status_register.zero, status_register.carry)
+    }
+
+    fn write_registers(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let cpu = self.cpu;
+        let registers = [
+            ("r0", cpu.registers.r0), ("r1", cpu.registers.r1), ("r2", cpu.registers.r2),
+            ("r3", cpu.registers.r3), ("r4", cpu.registers.r4), ("r5", cpu.registers.r5),
+            ("r6", cpu.registers.r6), ("r7", cpu.registers.r7), ("r8", cpu.registers.r8),
+            ("r9", cpu.registers.r9), ("r10", cpu.registers.r10), ("r11", cpu.registers.r11),
+            ("r12", cpu.registers.r12), ("r13", cpu.registers.r13), ("r14", cpu.registers.r14),
+            ("sp", cpu.stack_pointer), ("pc", cpu.program_counter),
+            ("ptbr", cpu.mmu.page_table_base()), ("pfar", cpu.mmu.fault_address()), ("cid", cpu.core_id)
+        ];
+
+        f.write_str("{")?;
+        for (i, (name, value)) in registers.iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            write!(f, "{separator}\"{name}\": {value}")?;
+        }
+        f.write_str("}")
+    }
+
+    fn write_frames(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let status_register = self.cpu.status_register;
+        let frames = self.cpu.system_stack.chunks_exact(2).rev();
+
+        f.write_str("[")?;
+        for (i, frame) in frames.enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            write!(f, "{separator}\n    {{\"pc\": {}, \"cause\": ", frame[1])?;
+            match Interrupt::frame_cause(i, status_register) {
+                Some(cause) => write!(f, "{}", JsonString(cause))?,
+                None => f.write_str("null")?
+            }
+            // Status register dump in stack frame is 8-bit
+            #[allow(clippy::cast_possible_truncation)]
+            let frame_status = StatusRegister::from(frame[0] as u8);
+            f.write_str(", \"status_register\": ")?;
+            Self::write_status_register(f, frame_status)?;
+            f.write_str(", \"symbol\": ")?;
+            self.write_symbol(f, frame[1])?;
+            f.write_str("}")?;
+        }
+
+        if self.cpu.system_stack.len() >= 2 {
+            f.write_str("\n  ")?;
+        }
+        f.write_str("]")
+    }
+
+    fn write_memory(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let start = self.cpu.program_counter.saturating_sub(self.memory_window / 2);
+
+        write!(f, "{{\"start\": {start}, \"bytes\": [")?;
+        for i in 0..self.memory_window {
+            let address = start.wrapping_add(i);
+            let separator = if i == 0 { "" } else { ", " };
+            // Unmapped or out-of-range bytes are null rather than ending the report
+            let byte = self.cpu.inspect(|| self.cpu.read_virtual::<1>(address, Access::Read));
+            match byte {
+                Ok([byte]) => write!(f, "{separator}{byte}")?,
+                Err(_) => write!(f, "{separator}null")?
+            }
+        }
+        f.write_str("]}")
+    }
+}
+
+impl Display for CrashReport<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let cpu = self.cpu;
+        let instruction = cpu.extract_instruction_infailible(cpu.program_counter);
+
+        writeln!(f, "{{")?;
+        writeln!(f, "  \"interrupt\": {{\"code\": {}, \"description\": {}}},",
+                 u32::from(self.interrupt), JsonString(self.interrupt.description()))?;
+        writeln!(f, "  \"reason\": {},", JsonString(fault_reason(cpu.status_register)))?;
+        write!(f, "  \"pc\": {},\n  \"symbol\": ", cpu.program_counter)?;
+        self.write_symbol(f, cpu.program_counter)?;
+        f.write_str(",\n  \"registers\": ")?;
+        self.write_registers(f)?;
+        f.write_str(",\n  \"status_register\": ")?;
+        Self::write_status_register(f, cpu.status_register)?;
+
+        f.write_str(",\n  \"instruction\": {\"bytes\": [")?;
+        for (i, byte) in instruction.as_slice().iter().enumerate() {
+            let separator = if i == 0 { "" } else { ", " };
+            write!(f, "{separator}{byte}")?;
+        }
+        write!(f, "], \"disassembly\": {}}}", JsonString(Disassembly(cpu)))?;
+
+        f.write_str(",\n  \"stack\": ")?;
+        self.write_frames(f)?;
+        f.write_str(",\n  \"memory\": ")?;
+        self.write_memory(f)?;
+        f.write_str("\n}")
+    }
+}
+
+// Writes any displayable value as a quoted JSON string
+struct JsonString<T: Display>(T);
+
+struct Escaper<'a, 'b>(&'a mut Formatter<'b>);
+
+impl Write for Escaper<'_, '_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for c in s.chars() {
+            match c {
+                '"' => self.0.write_str("\\\"")?,
+                '\\' => self.0.write_str("\\\\")?,
+                '\n' => self.0.write_str("\\n")?,
+                c if c.is_control() => write!(self.0, "\\u{:0>4x}", u32::from(c))?,
+                c => self.0.write_char(c)?
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: Display> Display for JsonString<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str("\"")?;
+        write!(Escaper(f), "{}", self.0)?;
+        f.write_str("\"")
+    }
+}
@@ -0,0 +1,271 @@
+// A program together with what is needed to run it: where each part goes in memory, where to
+// start and what kind of CPU it was built for. Raw ROMs leave all of that to convention. The file
+// is little endian throughout:
+//
+//     magic "VXEXEC", version u16
+//     required model name (u8 length + bytes, empty for any model), required extensions u32
+//     entry point u32
+//     segments (u32 count), each a load address u32 and its data (u32 length + bytes)
+//     sections (u32 count), each a kind u8 and its data (u32 length + bytes)
+//
+// Sections are optional extras. Symbols hold a symbol map as written by `SymbolTable::to_map`,
+// debug holds free-form UTF-8 text from the toolchain. Sections of kinds this version doesn't know
+// are skipped, so they can be added without breaking older loaders.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use crate::core::{CpuModel, Extension, ExtensionSet, SymbolTable};
+use crate::cpu::LoadSegment;
+use crate::models::find_model;
+
+pub const EXECUTABLE_MAGIC: &[u8; 6] = b"VXEXEC";
+pub const EXECUTABLE_VERSION: u16 = 1;
+
+const SECTION_SYMBOLS: u8 = 1;
+const SECTION_DEBUG: u8 = 2;
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub address: u32,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Executable {
+    // Only runs on this model when set, e.g. for firmware written against its specification block
+    pub model: Option<&'static CpuModel>,
+    pub extensions: ExtensionSet,
+    pub entry_point: u32,
+    pub segments: Vec<Segment>,
+    pub symbols: Option<SymbolTable>,
+    pub debug: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExecutableError {
+    NotAnExecutable,
+    UnsupportedVersion(u16),
+    Truncated,
+    UnknownModel(String),
+    Invalid(&'static str),
+    WrongModel { required: &'static str, actual: &'static str },
+    // The extensions the CPU lacks
+    MissingExtensions(ExtensionSet),
+}
+
+impl Executable {
+    // A single segment starting at `entry_point`, as a raw ROM would be loaded
+    #[must_use]
+    pub fn new(entry_point: u32, data: Vec<u8>) -> Self {
+        Self {
+            model: None,
+            extensions: ExtensionSet::empty(),
+            entry_point,
+            segments: alloc::vec![Segment { address: entry_point, data }],
+            symbols: None,
+            debug: None,
+        }
+    }
+
+    // Anything else is taken for a raw ROM
+    #[must_use]
+    pub fn is_executable(bytes: &[u8]) -> bool {
+        bytes.starts_with(EXECUTABLE_MAGIC)
+    }
+
+    #[must_use]
+    pub fn load_segments(&self) -> Vec<LoadSegment<'_>> {
+        self.segments.iter()
+            .map(|segment| LoadSegment { address: segment.address, data: &segment.data })
+            .collect()
+    }
+
+    pub fn check(&self, model: &'static CpuModel) -> Result<(), ExecutableError> {
+        if let Some(required) = self.model {
+            if required.short_name != model.short_name {
+                return Err(ExecutableError::WrongModel { required: required.short_name, actual: model.short_name });
+            }
+        }
+
+        let missing = self.extensions.0 & !model.extensions.0;
+        if missing != 0 {
+            return Err(ExecutableError::MissingExtensions(ExtensionSet(missing)));
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(EXECUTABLE_MAGIC);
+        bytes.extend_from_slice(&EXECUTABLE_VERSION.to_le_bytes());
+        let name = self.model.map_or("", |model| model.short_name).as_bytes();
+        #[allow(clippy::cast_possible_truncation)]
+        bytes.push(name.len() as u8);
+        bytes.extend_from_slice(name);
+        bytes.extend_from_slice(&u32::from(self.extensions).to_le_bytes());
+        bytes.extend_from_slice(&self.entry_point.to_le_bytes());
+
+        push_length(&mut bytes, self.segments.len());
+        for segment in &self.segments {
+            bytes.extend_from_slice(&segment.address.to_le_bytes());
+            push_data(&mut bytes, &segment.data);
+        }
+
+        let mut sections = Vec::new();
+        if let Some(symbols) = &self.symbols {
+            sections.push((SECTION_SYMBOLS, symbols.to_map()));
+        }
+        if let Some(debug) = &self.debug {
+            sections.push((SECTION_DEBUG, debug.clone()));
+        }
+
+        push_length(&mut bytes, sections.len());
+        for (kind, data) in sections {
+            bytes.push(kind);
+            push_data(&mut bytes, data.as_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, ExecutableError> {
+        let mut reader = Reader { bytes };
+        if reader.take(EXECUTABLE_MAGIC.len()).ok() != Some(EXECUTABLE_MAGIC.as_slice()) {
+            return Err(ExecutableError::NotAnExecutable);
+        }
+        let version = u16::from_le_bytes(reader.array()?);
+        if version != EXECUTABLE_VERSION {
+            return Err(ExecutableError::UnsupportedVersion(version));
+        }
+
+        let name_length = reader.array::<1>()?[0] as usize;
+        let name = core::str::from_utf8(reader.take(name_length)?)
+            .map_err(|_| ExecutableError::Invalid("model name is not UTF-8"))?;
+        let model = match name {
+            "" => None,
+            name => Some(find_model(name).ok_or_else(|| ExecutableError::UnknownModel(name.into()))?),
+        };
+        let extensions = ExtensionSet(reader.word()?);
+        let entry_point = reader.word()?;
+
+        let mut segments = Vec::new();
+        for _ in 0..reader.word()? {
+            let address = reader.word()?;
+            let data = reader.data()?.to_vec();
+            if address.checked_add(u32::try_from(data.len()).unwrap_or(u32::MAX)).is_none() {
+                return Err(ExecutableError::Invalid("segment past the end of the address space"));
+            }
+            segments.push(Segment { address, data });
+        }
+
+        let mut symbols = None;
+        let mut debug = None;
+        for _ in 0..reader.word()? {
+            let kind = reader.array::<1>()?[0];
+            let data = reader.data()?;
+            match kind {
+                SECTION_SYMBOLS => {
+                    let map = core::str::from_utf8(data).map_err(|_| ExecutableError::Invalid("symbols are not UTF-8"))?;
+                    symbols = Some(SymbolTable::parse_map(map).map_err(|_| ExecutableError::Invalid("bad symbol map"))?);
+                }
+                SECTION_DEBUG => {
+                    let text = core::str::from_utf8(data).map_err(|_| ExecutableError::Invalid("debug section is not UTF-8"))?;
+                    debug = Some(text.into());
+                }
+                _ => {}
+            }
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(ExecutableError::Invalid("trailing data"));
+        }
+
+        // Labels only cover the segment they are in. Ending one at the start of each segment too
+        // keeps labels outside every segment from running into the next one
+        if let Some(symbols) = &mut symbols {
+            for segment in &segments {
+                symbols.insert_end(segment.address);
+                let length = u32::try_from(segment.data.len()).unwrap_or(u32::MAX);
+                symbols.insert_end(segment.address.saturating_add(length));
+            }
+        }
+
+        Ok(Self {
+            model,
+            extensions,
+            entry_point,
+            segments,
+            symbols,
+            debug,
+        })
+    }
+}
+
+fn push_length(bytes: &mut Vec<u8>, length: usize) {
+    #[allow(clippy::cast_possible_truncation)]
+    bytes.extend_from_slice(&(length as u32).to_le_bytes());
+}
+
+fn push_data(bytes: &mut Vec<u8>, data: &[u8]) {
+    push_length(bytes, data.len());
+    bytes.extend_from_slice(data);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, length: usize) -> Result<&'a [u8], ExecutableError> {
+        if self.bytes.len() < length {
+            return Err(ExecutableError::Truncated);
+        }
+        let (taken, rest) = self.bytes.split_at(length);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ExecutableError> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn word(&mut self) -> Result<u32, ExecutableError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn data(&mut self) -> Result<&'a [u8], ExecutableError> {
+        let length = self.word()? as usize;
+        self.take(length)
+    }
+}
+
+impl Display for ExecutableError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExecutableError::NotAnExecutable => write!(f, "not a Vixen executable"),
+            ExecutableError::UnsupportedVersion(version) => write!(f, "unsupported executable version {version}"),
+            ExecutableError::Truncated => write!(f, "executable is truncated"),
+            ExecutableError::UnknownModel(name) => write!(f, "built for unknown CPU model `{name}`"),
+            ExecutableError::Invalid(reason) => write!(f, "invalid executable: {reason}"),
+            ExecutableError::WrongModel { required, actual } => {
+                write!(f, "built for the {required} CPU, not the {actual}")
+            }
+            ExecutableError::MissingExtensions(missing) => {
+                write!(f, "needs extensions the CPU lacks:")?;
+                for extension in Extension::ALL.iter().filter(|extension| missing.contains(**extension)) {
+                    write!(f, " {};", extension.name())?;
+                }
+                let unknown = Extension::ALL.iter().fold(missing.0, |bits, extension| bits & !extension.bit());
+                if unknown != 0 {
+                    write!(f, " unknown extensions {unknown:0>8x};")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
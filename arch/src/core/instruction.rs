@@ -1,13 +1,17 @@
+#[cfg(feature = "alloc")]
 use alloc::string::String;
-use crate::core::Operand;
+use core::fmt::Write;
+use crate::core::{Interrupt, Operand};
 use crate::{instructions, CPUResult, InstructionResult};
 use crate::CPU;
 
 pub mod addressing;
 pub mod operation;
+pub mod flags;
 
 pub use addressing::Addressing;
-pub use operation::Operation;
+pub use operation::{Operation, OperandRole, OperationInfo};
+pub use flags::{FlagEffect, FlagEffects};
 
 #[derive(Debug)]
 pub struct Instruction {
@@ -112,7 +116,33 @@ impl Instruction {
             Operation::Jle => instructions::jle(&mut self.operands, cpu),
             Operation::Jg => instructions::jg(&mut self.operands, cpu),
             Operation::Jp => instructions::jp(&mut self.operands, cpu),
-            Operation::Jnp => instructions::jnp(&mut self.operands, cpu)
+            Operation::Jnp => instructions::jnp(&mut self.operands, cpu),
+
+            // 0x09?? - MPE: Multiprocessing Extension
+            Operation::Cas => instructions::cas(&mut self.operands, cpu),
+            Operation::Xadd => instructions::xadd(&mut self.operands, cpu),
+            Operation::Ipi => instructions::ipi(&mut self.operands, cpu),
+
+            // 0x0A?? - CMX: Conditional Move Extension
+            Operation::Cmps => instructions::cmps(&mut self.operands, cpu),
+            Operation::Cmovz => instructions::cmovz(&mut self.operands, cpu),
+            Operation::Cmovnz => instructions::cmovnz(&mut self.operands, cpu),
+            Operation::Cmovc => instructions::cmovc(&mut self.operands, cpu),
+            Operation::Cmovnc => instructions::cmovnc(&mut self.operands, cpu),
+            Operation::Cmovo => instructions::cmovo(&mut self.operands, cpu),
+            Operation::Cmovno => instructions::cmovno(&mut self.operands, cpu),
+            Operation::Cmovs => instructions::cmovs(&mut self.operands, cpu),
+            Operation::Cmovns => instructions::cmovns(&mut self.operands, cpu),
+            Operation::Cmovle => instructions::cmovle(&mut self.operands, cpu),
+            Operation::Cmovg => instructions::cmovg(&mut self.operands, cpu),
+
+            // 0x0B?? - TAE: Trapping Arithmetic Extension
+            Operation::Addv => instructions::addv(&mut self.operands, cpu),
+            Operation::Subv => instructions::subv(&mut self.operands, cpu),
+            Operation::Mulv => instructions::mulv(&mut self.operands, cpu),
+
+            // 0x0C?? - LPE: Loop Extension
+            Operation::Loop => instructions::r#loop(&mut self.operands, cpu)
         }
     }
 }
@@ -121,6 +151,10 @@ impl DecodedInstruction {
     #[allow(clippy::cast_possible_truncation)]
     pub fn into_instruction(self, cpu: &CPU) -> CPUResult<Instruction> {
         let operation = Operation::try_from(self.operation as u16)?;
+        if !cpu.model.supports(operation) {
+            return Err(Interrupt::IllegalInstruction);
+        }
+
         let modes = [
             Addressing::try_from(self.modes[0] as u8)?,
             Addressing::try_from(self.modes[1] as u8)?,
@@ -137,11 +171,21 @@ impl DecodedInstruction {
         })
     }
 
-    #[allow(clippy::cast_possible_truncation)]
+    #[cfg(feature = "alloc")]
     #[must_use] pub fn disassemble(self, cpu: &CPU) -> String {
         let mut disassembled = String::new();
-        let operation = Operation::disassemble(self.operation as u16);
-        disassembled.push_str(&operation);
+        // Writing to a String never fails
+        let _ = self.disassemble_into(cpu, &mut disassembled);
+        disassembled
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn disassemble_into(self, cpu: &CPU, f: &mut dyn Write) -> core::fmt::Result {
+        Operation::disassemble_into(self.operation as u16, f)?;
+
+        // Operands past the operation's arity are ignored by the CPU, don't show them
+        let arity = Operation::try_from(self.operation as u16)
+            .map_or(3, |operation| operation.operands().len());
 
         let operands_with_modes = [
             (self.operands[0], self.modes[0]),
@@ -149,7 +193,7 @@ impl DecodedInstruction {
             (self.operands[2], self.modes[2])
         ];
 
-        for (i, (operand, mode)) in operands_with_modes.iter().enumerate() {
+        for (i, (operand, mode)) in operands_with_modes.iter().enumerate().take(arity) {
             #[allow(clippy::cast_possible_truncation)]
             let mode = Addressing::try_from(*mode as u8)
                 .ok()
@@ -157,14 +201,13 @@ impl DecodedInstruction {
 
             if let Some(mode) = mode {
                 if i > 0 {
-                    disassembled.push_str(", ");
+                    f.write_str(", ")?;
                 }
 
-                let operand = Operand::disassemble(*operand, cpu, mode);
-                disassembled.push_str(&operand);
+                Operand::disassemble_into(*operand, cpu, mode, f)?;
             }
         }
 
-        disassembled
+        Ok(())
     }
 }
@@ -0,0 +1,127 @@
+use crate::core::instruction::Operation;
+
+// How an operation leaves a status register flag. `Result` means zero is set when the value written
+// to operand 0 is zero and negative mirrors its bit 31. `Computed` flags follow the rule documented
+// next to the operation below.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FlagEffect {
+    Unaffected,
+    Set,
+    Cleared,
+    Result,
+    Computed,
+    // Loaded back from a stack
+    Restored
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlagEffects {
+    pub negative: FlagEffect,
+    pub overflow: FlagEffect,
+    pub interrupt_disable: FlagEffect,
+    pub zero: FlagEffect,
+    pub carry: FlagEffect
+}
+
+use FlagEffect::{Cleared, Computed, Restored, Result, Set, Unaffected};
+
+impl FlagEffects {
+    pub const NONE: FlagEffects = FlagEffects::new(Unaffected, Unaffected, Unaffected, Unaffected);
+    pub const RESTORED: FlagEffects = FlagEffects {
+        negative: Restored,
+        overflow: Restored,
+        interrupt_disable: Restored,
+        zero: Restored,
+        carry: Restored
+    };
+
+    // Arithmetic flags only, interrupt_disable is left alone
+    #[must_use]
+    pub const fn new(zero: FlagEffect, carry: FlagEffect, overflow: FlagEffect, negative: FlagEffect) -> Self {
+        Self {
+            negative,
+            overflow,
+            interrupt_disable: Unaffected,
+            zero,
+            carry
+        }
+    }
+
+    #[must_use]
+    pub const fn with_interrupt_disable(self, interrupt_disable: FlagEffect) -> Self {
+        Self {
+            interrupt_disable,
+            ..self
+        }
+    }
+}
+
+const ARITHMETIC: FlagEffects = FlagEffects::new(Result, Computed, Computed, Result);
+// Results that can neither carry nor overflow clear both flags
+const PLAIN_RESULT: FlagEffects = FlagEffects::new(Result, Cleared, Cleared, Result);
+const SHIFT: FlagEffects = FlagEffects::new(Result, Computed, Cleared, Result);
+const COMPARISON: FlagEffects = FlagEffects::new(Computed, Computed, Unaffected, Computed);
+
+impl Operation {
+    // Arms are grouped by what the operations do rather than by the effects they share
+    #[allow(clippy::match_same_arms)]
+    #[must_use]
+    pub const fn flags(self) -> FlagEffects {
+        match self {
+            // C: unsigned carry (add) or borrow (sub), V: signed overflow
+            // addc/subc add or subtract the incoming carry on top
+            Operation::Add | Operation::Sub | Operation::Addc | Operation::Subc => ARITHMETIC,
+            // C: the unsigned product does not fit, V: the signed product does not fit
+            Operation::Mul | Operation::Sqre | Operation::Cube => ARITHMETIC,
+            Operation::Div | Operation::Mod | Operation::Sqrt | Operation::Cbrt
+                | Operation::Min | Operation::Max => PLAIN_RESULT,
+            // C: the bit shifted out (bit 0 to the right, bit 31 to the left)
+            Operation::Sar | Operation::Shr | Operation::Shl | Operation::Rol | Operation::Ror => SHIFT,
+            // C: bit 31 shifted out, V: the sign bit changed
+            Operation::Sal => ARITHMETIC,
+
+            Operation::And | Operation::Or | Operation::Xor | Operation::Nor | Operation::Nand
+                | Operation::Imp | Operation::Not => PLAIN_RESULT,
+
+            // C: the counter wrapped around, V: it crossed the signed boundary
+            Operation::Inc | Operation::Dec | Operation::Loop => ARITHMETIC,
+
+            // cmp/cmps: Z: a == b, C: a >= b, N: a < b (unsigned/signed)
+            // lte: Z: a <= b, C: a > b, N: a < b
+            // gte: Z: a >= b, C: a < b, N: a < b
+            Operation::Cmp | Operation::Lte | Operation::Gte | Operation::Cmps => COMPARISON,
+            Operation::Setz | Operation::Setc | Operation::Seto => FlagEffects::NONE,
+
+            Operation::Mov | Operation::Xchg => FlagEffects::NONE,
+            Operation::Clr => FlagEffects::new(Set, Unaffected, Unaffected, Unaffected),
+            Operation::Stc => FlagEffects::new(Unaffected, Set, Unaffected, Unaffected),
+            Operation::Clc => FlagEffects::new(Unaffected, Cleared, Unaffected, Unaffected),
+            Operation::Clv => FlagEffects::new(Unaffected, Unaffected, Cleared, Unaffected),
+            Operation::Sti => FlagEffects::NONE.with_interrupt_disable(Set),
+            Operation::Cli => FlagEffects::NONE.with_interrupt_disable(Cleared),
+
+            Operation::Ret | Operation::Iret | Operation::Irets | Operation::Popf => FlagEffects::RESTORED,
+            Operation::Jmp | Operation::Jmpl | Operation::Jz | Operation::Jnz | Operation::Jc
+                | Operation::Jnc | Operation::Jo | Operation::Jno | Operation::Int | Operation::Nop
+                | Operation::Hlt | Operation::Js | Operation::Jns => FlagEffects::NONE,
+
+            Operation::Push | Operation::Pop | Operation::Pushf => FlagEffects::NONE,
+
+            Operation::Jnae | Operation::Jae | Operation::Jna | Operation::Ja | Operation::Jl
+                | Operation::Jge | Operation::Jle | Operation::Jg | Operation::Jp | Operation::Jnp => FlagEffects::NONE,
+
+            // Z: the swap happened
+            Operation::Cas => FlagEffects::new(Computed, Unaffected, Unaffected, Unaffected),
+            // Same as add on the value stored back to memory
+            Operation::Xadd => FlagEffects::new(Computed, Computed, Computed, Computed),
+            Operation::Ipi => FlagEffects::NONE,
+
+            Operation::Cmovz | Operation::Cmovnz | Operation::Cmovc | Operation::Cmovnc | Operation::Cmovo
+                | Operation::Cmovno | Operation::Cmovs | Operation::Cmovns | Operation::Cmovle
+                | Operation::Cmovg => FlagEffects::NONE,
+
+            // C: as add/sub/mul, V is always clear since signed overflow traps instead
+            Operation::Addv | Operation::Subv | Operation::Mulv => FlagEffects::new(Result, Computed, Cleared, Result)
+        }
+    }
+}
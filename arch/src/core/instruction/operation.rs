@@ -1,11 +1,13 @@
-use alloc::format;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
-use core::fmt::{Debug, Display, Formatter};
+use core::fmt::{Debug, Display, Formatter, Write};
+use crate::core::instruction::FlagEffects;
+use crate::core::model::Extension;
 use crate::{CPUResult, Interrupt};
 
 #[allow(edition_2024_expr_fragment_specifier)]
 macro_rules! isa {
-    ( $( $x: expr => $y: ident ),+ $(,)? ) => {
+    ( $( $x: expr => $y: ident ( $mnemonic: literal $( | $alias: literal )* ) [ $( $role: ident ),* ] ),+ $(,)? ) => {
         impl TryFrom<u16> for Operation {
             type Error = Interrupt;
 
@@ -28,11 +30,42 @@ macro_rules! isa {
                 }
             }
         }
+
+        impl Operation {
+            pub const ALL: &'static [Operation] = &[ $( Operation::$y ),* ];
+
+            #[must_use]
+            pub const fn mnemonic(self) -> &'static str {
+                match self {
+                    $(
+                        Operation::$y => $mnemonic,
+                    )*
+                }
+            }
+
+            #[must_use]
+            pub const fn aliases(self) -> &'static [&'static str] {
+                match self {
+                    $(
+                        Operation::$y => &[ $( $alias ),* ],
+                    )*
+                }
+            }
+
+            #[must_use]
+            pub const fn operands(self) -> &'static [OperandRole] {
+                match self {
+                    $(
+                        Operation::$y => &[ $( OperandRole::$role ),* ],
+                    )*
+                }
+            }
+        }
     };
 }
 
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Operation {
     /* 0x01?? */ Add, Sub, Mul, Div, Mod, Sqrt, Cbrt, Sqre, Cube, Min, Max, Addc, Subc, Sar, Sal,
     /* 0x02?? */ And, Or,  Xor, Nor, Nand, Imp, Not, Shl, Shr, Rol, Ror,
@@ -41,112 +74,216 @@ pub enum Operation {
     /* 0x05?? */ Mov, Xchg, Clr, Stc, Clc, Sti, Cli, Clv,
     /* 0x06?? */ Jmp, Jmpl, Ret, Jz, Jnz, Jc, Jnc, Jo, Jno, Int, Iret, Irets, Nop, Hlt, Js, Jns,
     /* 0x07?? */ Push, Pop, Pushf, Popf,
-    /* 0x08?? */ Jnae, Jae, Jna, Ja, Jl, Jge, Jle, Jg, Jp, Jnp
+    /* 0x08?? */ Jnae, Jae, Jna, Ja, Jl, Jge, Jle, Jg, Jp, Jnp,
+    /* 0x09?? */ Cas, Xadd, Ipi,
+    /* 0x0A?? */ Cmps, Cmovz, Cmovnz, Cmovc, Cmovnc, Cmovo, Cmovno, Cmovs, Cmovns, Cmovle, Cmovg,
+    /* 0x0B?? */ Addv, Subv, Mulv,
+    /* 0x0C?? */ Loop
+}
+
+// What an instruction does with each of its operands
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OperandRole {
+    Read,
+    Write,
+    ReadWrite,
+    // Only the address is used, e.g. a jump target
+    Address
+}
+
+// Everything tools need to know about an operation, gathered from the ISA table
+#[derive(Debug, Clone, Copy)]
+pub struct OperationInfo {
+    pub operation: Operation,
+    pub opcode: u16,
+    pub mnemonic: &'static str,
+    pub aliases: &'static [&'static str],
+    pub operands: &'static [OperandRole],
+    pub flags: FlagEffects,
+    pub extension: Option<Extension>
+}
+
+impl OperandRole {
+    #[must_use]
+    pub fn is_written(self) -> bool {
+        matches!(self, OperandRole::Write | OperandRole::ReadWrite)
+    }
+
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            OperandRole::Read => "read",
+            OperandRole::Write => "write",
+            OperandRole::ReadWrite => "read/write",
+            OperandRole::Address => "address"
+        }
+    }
 }
 
 impl Display for Operation {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        Display::fmt(&format!("{self:?}"), f)
+        Display::fmt(self.mnemonic(), f)
     }
 }
 
 impl Operation {
+    #[cfg(feature = "alloc")]
     #[must_use]
     pub fn disassemble(value: u16) -> String {
+        let mut disassembled = String::new();
+        // Writing to a String never fails
+        let _ = Self::disassemble_into(value, &mut disassembled);
+        disassembled
+    }
+
+    pub fn disassemble_into(value: u16, f: &mut dyn Write) -> core::fmt::Result {
         if let Ok(operation) = Operation::try_from(value) {
-            format!("{operation} ").to_lowercase()
+            write!(f, "{operation} ")
         } else {
-            String::from("<unk> ")
+            f.write_str("<unk> ")
+        }
+    }
+
+    #[must_use]
+    pub fn extension(self) -> Option<Extension> {
+        Extension::from_group(u16::from(self) >> 4)
+    }
+
+    // Case-insensitive lookup by mnemonic or alias
+    #[must_use]
+    pub fn from_mnemonic(name: &str) -> Option<Operation> {
+        Self::ALL.iter()
+            .copied()
+            .find(|operation| operation.mnemonic().eq_ignore_ascii_case(name)
+                || operation.aliases().iter().any(|alias| alias.eq_ignore_ascii_case(name)))
+    }
+
+    #[must_use]
+    pub fn info(self) -> OperationInfo {
+        OperationInfo {
+            operation: self,
+            opcode: self.into(),
+            mnemonic: self.mnemonic(),
+            aliases: self.aliases(),
+            operands: self.operands(),
+            flags: self.flags(),
+            extension: self.extension()
         }
     }
 }
 
 isa! {
     // 0x01?? - Arithmetic and Algebric Instructions
-    0x010 => Add,
-    0x011 => Sub,
-    0x012 => Mul,
-    0x013 => Div,
-    0x014 => Mod,
-    0x015 => Sqrt,
-    0x016 => Cbrt,
-    0x017 => Sqre,
-    0x018 => Cube,
-    0x019 => Min,
-    0x01A => Max,
-    0x01B => Addc,
-    0x01C => Subc,
-    0x01D => Sar,
-    0x01E => Sal,
+    0x010 => Add("add") [Write, Read, Read],
+    0x011 => Sub("sub") [Write, Read, Read],
+    0x012 => Mul("mul") [Write, Read, Read],
+    0x013 => Div("div") [Write, Read, Read],
+    0x014 => Mod("mod") [Write, Read, Read],
+    0x015 => Sqrt("sqrt" | "sqt") [Write, Read],
+    0x016 => Cbrt("cbrt" | "cbt") [Write, Read],
+    0x017 => Sqre("sqre" | "sqr") [Write, Read],
+    0x018 => Cube("cube" | "cbe") [Write, Read],
+    0x019 => Min("min") [Write, Read, Read],
+    0x01A => Max("max") [Write, Read, Read],
+    0x01B => Addc("addc" | "adc") [Write, Read, Read],
+    0x01C => Subc("subc" | "sbc") [Write, Read, Read],
+    0x01D => Sar("sar" | "asr") [ReadWrite],
+    0x01E => Sal("sal" | "asl") [ReadWrite],
 
     // 0x02?? - Logic Instructions
-    0x020 => And,
-    0x021 => Or,
-    0x022 => Xor,
-    0x023 => Nor,
-    0x024 => Nand,
-    0x025 => Imp,
-    0x026 => Not,
-    0x027 => Shl,
-    0x028 => Shr,
-    0x029 => Rol,
-    0x02A => Ror,
+    0x020 => And("and") [Write, Read, Read],
+    0x021 => Or("or") [Write, Read, Read],
+    0x022 => Xor("xor") [Write, Read, Read],
+    0x023 => Nor("nor") [Write, Read, Read],
+    0x024 => Nand("nand" | "nad") [Write, Read, Read],
+    0x025 => Imp("imp") [Write, Read, Read],
+    0x026 => Not("not") [],
+    0x027 => Shl("shl") [ReadWrite],
+    0x028 => Shr("shr") [ReadWrite],
+    0x029 => Rol("rol") [ReadWrite],
+    0x02A => Ror("ror") [ReadWrite],
 
     // 0x03?? - Counting Instructions
-    0x030 => Inc,
-    0x031 => Dec,
+    0x030 => Inc("inc") [ReadWrite],
+    0x031 => Dec("dec") [ReadWrite],
 
     // 0x04?? - Comparison Instructions
-    0x040 => Cmp,
-    0x041 => Lte,
-    0x042 => Gte,
-    0x043 => Setz,
-    0x044 => Setc,
-    0x045 => Seto,
+    0x040 => Cmp("cmp") [Read, Read],
+    0x041 => Lte("lte") [Read, Read],
+    0x042 => Gte("gte") [Read, Read],
+    0x043 => Setz("setz" | "srz") [Write],
+    0x044 => Setc("setc" | "src") [Write],
+    0x045 => Seto("seto" | "sro") [Write],
 
     // 0x05?? - Data Movement Instructions
-    0x050 => Mov,
-    0x051 => Xchg,
-    0x052 => Clr,
-    0x053 => Stc,
-    0x054 => Clc,
-    0x055 => Sti,
-    0x056 => Cli,
-    0x057 => Clv,
+    0x050 => Mov("mov") [Write, Read],
+    0x051 => Xchg("xchg" | "swp") [ReadWrite, ReadWrite],
+    0x052 => Clr("clr") [Write],
+    0x053 => Stc("stc" | "sec") [],
+    0x054 => Clc("clc") [],
+    0x055 => Sti("sti" | "sei") [],
+    0x056 => Cli("cli") [],
+    0x057 => Clv("clv") [],
 
     // 0x06?? - Control Flow Instructions
-    0x060 => Jmpl,
-    0x061 => Jmp,
-    0x062 => Ret,
-    0x063 => Jz,
-    0x064 => Jnz,
-    0x065 => Jc,
-    0x066 => Jnc,
-    0x067 => Jo,
-    0x068 => Jno,
-    0x069 => Int,
-    0x06A => Iret,
-    0x06B => Irets,
-    0x06C => Nop,
-    0x06D => Hlt,
-    0x06E => Js,
-    0x06F => Jns,
+    0x060 => Jmpl("jmpl") [Address],
+    0x061 => Jmp("jmp" | "jsr") [Address],
+    0x062 => Ret("ret") [],
+    0x063 => Jz("jz" | "beq") [Address],
+    0x064 => Jnz("jnz" | "bne") [Address],
+    0x065 => Jc("jc" | "bec") [Address],
+    0x066 => Jnc("jnc" | "bnc") [Address],
+    0x067 => Jo("jo" | "beo") [Address],
+    0x068 => Jno("jno" | "bno") [Address],
+    0x069 => Int("int") [],
+    0x06A => Iret("iret" | "irt") [],
+    0x06B => Irets("irets" | "irj") [],
+    0x06C => Nop("nop") [],
+    0x06D => Hlt("hlt" | "jam") [],
+    0x06E => Js("js" | "bpl") [Address],
+    0x06F => Jns("jns" | "bmi") [Address],
 
     // 0x07?? - Stack Instructions
-    0x070 => Push,
-    0x071 => Pop,
-    0x072 => Pushf,
-    0x073 => Popf,
+    0x070 => Push("push" | "psh") [Read],
+    0x071 => Pop("pop" | "pll") [Write],
+    0x072 => Pushf("pushf" | "php") [],
+    0x073 => Popf("popf" | "plp") [],
 
     // 0x08?? - AFE: Advanced Flow Extension
-    0x080 => Jnae,
-    0x081 => Jae,
-    0x082 => Jna,
-    0x083 => Ja,
-    0x084 => Jl,
-    0x085 => Jge,
-    0x086 => Jle,
-    0x087 => Jg,
-    0x088 => Jp,
-    0x089 => Jnp
+    0x080 => Jnae("jnae") [Read, Read, Address],
+    0x081 => Jae("jae") [Read, Read, Address],
+    0x082 => Jna("jna") [Read, Read, Address],
+    0x083 => Ja("ja") [Read, Read, Address],
+    0x084 => Jl("jl") [Read, Read, Address],
+    0x085 => Jge("jge") [Read, Read, Address],
+    0x086 => Jle("jle") [Read, Read, Address],
+    0x087 => Jg("jg") [Read, Read, Address],
+    0x088 => Jp("jp") [Read, Address],
+    0x089 => Jnp("jnp") [Read, Address],
+
+    // 0x09?? - MPE: Multiprocessing Extension
+    0x090 => Cas("cas") [ReadWrite, ReadWrite, Read],
+    0x091 => Xadd("xadd") [Write, ReadWrite, Read],
+    0x092 => Ipi("ipi") [Read],
+
+    // 0x0A?? - CMX: Conditional Move Extension
+    0x0A0 => Cmps("cmps") [Read, Read],
+    0x0A1 => Cmovz("cmovz" | "cmove") [Write, Read],
+    0x0A2 => Cmovnz("cmovnz" | "cmovne") [Write, Read],
+    0x0A3 => Cmovc("cmovc" | "cmovge" | "cmovae") [Write, Read],
+    0x0A4 => Cmovnc("cmovnc") [Write, Read],
+    0x0A5 => Cmovo("cmovo") [Write, Read],
+    0x0A6 => Cmovno("cmovno") [Write, Read],
+    0x0A7 => Cmovs("cmovs" | "cmovl" | "cmovb") [Write, Read],
+    0x0A8 => Cmovns("cmovns") [Write, Read],
+    0x0A9 => Cmovle("cmovle" | "cmovbe") [Write, Read],
+    0x0AA => Cmovg("cmovg" | "cmova") [Write, Read],
+
+    // 0x0B?? - TAE: Trapping Arithmetic Extension
+    0x0B0 => Addv("addv") [Write, Read, Read],
+    0x0B1 => Subv("subv") [Write, Read, Read],
+    0x0B2 => Mulv("mulv") [Write, Read, Read],
+
+    // 0x0C?? - LPE: Loop Extension
+    0x0C0 => Loop("loop") [ReadWrite, Address]
 }
@@ -1,13 +1,15 @@
+#[cfg(feature = "alloc")]
 use alloc::string::String;
 use core::fmt::{Display, Formatter};
 use core::fmt::Write;
 use crate::core::registers::StatusRegister;
+use crate::core::symbols::SymbolLookup;
 use crate::devices::errors::BusError;
 
 #[derive(Debug, Clone, Copy)]
 pub enum Interrupt {
-    Rtc, AsyncIO, Hardware, External,
-    Breakpoint, IllegalInstruction, IllegalMemory, DivideByZero,
+    Rtc, AsyncIO, Hardware, External, InterProcessor,
+    Breakpoint, IllegalInstruction, IllegalMemory, DivideByZero, PageFault, Overflow,
     StackOverflow, StackUnderflow,
     User1, User2, User3, User4, User5, User6, User7, User8,
     User9, User10, User11, User12, User13, User14, User15, User16,
@@ -21,10 +23,13 @@ impl From<Interrupt> for u32 {
             Interrupt::AsyncIO => 0x01,
             Interrupt::Hardware => 0x02,
             Interrupt::External => 0x03,
+            Interrupt::InterProcessor => 0x04,
             Interrupt::Breakpoint => 0x10,
             Interrupt::IllegalInstruction => 0x11,
             Interrupt::IllegalMemory => 0x12,
             Interrupt::DivideByZero => 0x13,
+            Interrupt::PageFault => 0x14,
+            Interrupt::Overflow => 0x15,
             Interrupt::StackOverflow => 0x20,
             Interrupt::StackUnderflow => 0x21,
             Interrupt::User1 => 0xE0,
@@ -61,63 +66,104 @@ impl From<BusError> for Interrupt {
 }
 
 impl Interrupt {
+    pub const ALL: [Interrupt; 31] = [
+        Interrupt::Rtc, Interrupt::AsyncIO, Interrupt::Hardware, Interrupt::External, Interrupt::InterProcessor,
+        Interrupt::Breakpoint, Interrupt::IllegalInstruction, Interrupt::IllegalMemory, Interrupt::DivideByZero,
+        Interrupt::PageFault, Interrupt::Overflow, Interrupt::StackOverflow, Interrupt::StackUnderflow,
+        Interrupt::User1, Interrupt::User2, Interrupt::User3, Interrupt::User4, Interrupt::User5, Interrupt::User6,
+        Interrupt::User7, Interrupt::User8, Interrupt::User9, Interrupt::User10, Interrupt::User11, Interrupt::User12,
+        Interrupt::User13, Interrupt::User14, Interrupt::User15, Interrupt::User16,
+        Interrupt::Failure, Interrupt::Reset
+    ];
+
+    // The reverse of the conversion to u32, for codes read back from outside the CPU
+    #[must_use]
+    pub fn from_code(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|interrupt| u32::from(*interrupt) == code)
+    }
+
     #[must_use]
     pub fn is_maskable(&self) -> bool {
-        matches!(self, Interrupt::Rtc | Interrupt::AsyncIO | Interrupt::IllegalInstruction)
+        matches!(self, Interrupt::Rtc | Interrupt::AsyncIO | Interrupt::InterProcessor | Interrupt::IllegalInstruction)
+    }
+
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        match self {
+            Interrupt::Rtc => "Real-time clock tick",
+            Interrupt::AsyncIO => "Asynchronous I/O event",
+            Interrupt::Hardware => "General hardware fault",
+            Interrupt::External => "External hardware interrupt",
+            Interrupt::InterProcessor => "Inter-processor interrupt",
+            Interrupt::Breakpoint => "Breakpoint hit",
+            Interrupt::IllegalInstruction => "Illegal instruction",
+            Interrupt::IllegalMemory => "Illegal memory access",
+            Interrupt::DivideByZero => "Divide by zero",
+            Interrupt::PageFault => "Page fault",
+            Interrupt::Overflow => "Arithmetic overflow",
+            Interrupt::StackOverflow => "Stack overflow",
+            Interrupt::StackUnderflow => "Stack underflow",
+            Interrupt::User1 => "User-defined interrupt 1",
+            Interrupt::User2 => "User-defined interrupt 2",
+            Interrupt::User3 => "User-defined interrupt 3",
+            Interrupt::User4 => "User-defined interrupt 4",
+            Interrupt::User5 => "User-defined interrupt 5",
+            Interrupt::User6 => "User-defined interrupt 6",
+            Interrupt::User7 => "User-defined interrupt 7",
+            Interrupt::User8 => "User-defined interrupt 8",
+            Interrupt::User9 => "User-defined interrupt 9",
+            Interrupt::User10 => "User-defined interrupt 10",
+            Interrupt::User11 => "User-defined interrupt 11",
+            Interrupt::User12 => "User-defined interrupt 12",
+            Interrupt::User13 => "User-defined interrupt 13",
+            Interrupt::User14 => "User-defined interrupt 14",
+            Interrupt::User15 => "User-defined interrupt 15",
+            Interrupt::User16 => "User-defined interrupt 16",
+            Interrupt::Failure => "Internal system failure",
+            Interrupt::Reset => "System reset",
+        }
     }
 
+    // Which frame on the system stack a fault came from, given how deep the nesting went
     #[must_use]
-    pub fn get_stack_trace(stack: &[u32], status_register: StatusRegister) -> String {
+    pub fn frame_cause(index: usize, status_register: StatusRegister) -> Option<&'static str> {
+        match (index, status_register.interrupt, status_register.double_fault) {
+            (0, _, true) => Some("<double fault cause>"),
+            (1, _, true) | (0, true, _) => Some("<root cause>"),
+            (_, _, _) => None
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn get_stack_trace(stack: &[u32], status_register: StatusRegister, symbols: Option<&dyn SymbolLookup>) -> String {
         let mut trace = String::new();
-        let frames = stack.chunks(2).rev();
+        // Writing to a String never fails
+        let _ = Self::write_stack_trace(&mut trace, stack, status_register, symbols);
+        trace
+    }
+
+    pub fn write_stack_trace(f: &mut dyn Write, stack: &[u32], status_register: StatusRegister,
+                             symbols: Option<&dyn SymbolLookup>) -> core::fmt::Result {
+        let frames = stack.chunks_exact(2).rev();
 
         for (i, frame) in frames.enumerate() {
-            let cause = match (i, status_register.interrupt, status_register.double_fault) {
-                (0, _, true) => "<double fault cause>",
-                (1, _, true) | (0, true, _) => "<root cause>",
-                (_, _, _) => "-"
-            };
+            let cause = Self::frame_cause(i, status_register).unwrap_or("-");
             // Status register dump in stack frame is 8-bit
             #[allow(clippy::cast_possible_truncation)]
-            writeln!(&mut trace, "->  {:0>8x}  {cause: <20}  {: <8}  ??",
-                     frame[1], StatusRegister::from(frame[0] as u8)).unwrap();
+            write!(f, "->  {:0>8x}  {cause: <20}  {: <8}  ", frame[1], StatusRegister::from(frame[0] as u8))?;
+            match symbols.and_then(|symbols| symbols.lookup(frame[1])) {
+                Some(symbol) => writeln!(f, "{symbol}")?,
+                None => writeln!(f, "??")?
+            }
         }
 
-        trace
+        Ok(())
     }
 }
 
 impl Display for Interrupt {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
-        write!(f, "{}", match self {
-            Interrupt::Rtc => "00 (Real-time clock tick)",
-            Interrupt::AsyncIO => "01 (Asynchronous I/O event)",
-            Interrupt::Hardware => "032 (General hardware fault)",
-            Interrupt::External => "03 (External hardware handling_interrupt)",
-            Interrupt::Breakpoint => "10 (Breakpoint hit)",
-            Interrupt::IllegalInstruction => "11 (Illegal instruction)",
-            Interrupt::IllegalMemory => "12 (Illegal memory access)",
-            Interrupt::DivideByZero => "13 (Divide by zero)",
-            Interrupt::StackOverflow => "20 (Stack overflow)",
-            Interrupt::StackUnderflow => "21 (Stack underflow)",
-            Interrupt::User1 => "e0 (User-defined interrupt 1)",
-            Interrupt::User2 => "e1 (User-defined interrupt 2)",
-            Interrupt::User3 => "e2 (User-defined interrupt 3)",
-            Interrupt::User4 => "e3 (User-defined interrupt 4)",
-            Interrupt::User5 => "e4 (User-defined interrupt 5)",
-            Interrupt::User6 => "e5 (User-defined interrupt 6)",
-            Interrupt::User7 => "e6 (User-defined interrupt 7)",
-            Interrupt::User8 => "e7 (User-defined interrupt 8)",
-            Interrupt::User9 => "e8 (User-defined interrupt 9)",
-            Interrupt::User10 => "e9 (User-defined interrupt 10)",
-            Interrupt::User11 => "ea (User-defined interrupt 11)",
-            Interrupt::User12 => "eb (User-defined interrupt 12)",
-            Interrupt::User13 => "ec (User-defined interrupt 13)",
-            Interrupt::User14 => "ed (User-defined interrupt 14)",
-            Interrupt::User15 => "ee (User-defined interrupt 15)",
-            Interrupt::User16 => "ef (User-defined interrupt 16)",
-            Interrupt::Failure => "fe (Internal system failure)",
-            Interrupt::Reset => "ff (System reset)",
-        })
+        write!(f, "{:0>2x} ({})", u32::from(*self), self.description())
     }
 }
@@ -1,7 +1,8 @@
 use crate::core::instruction::Addressing;
 use crate::core::Interrupt;
 use crate::core::Operand;
-use crate::core::registers::RegisterId;
+use crate::core::registers::{RegisterId, StatusRegister};
+use crate::cpu::mmu::{Access, AddressTranslation};
 use crate::CPU;
 use crate::CPUResult;
 
@@ -17,8 +18,9 @@ impl MemoryCell for Operand {
         Ok(match self {
             Operand::Literal(value) | Operand::Register(_, value) => *value,
             Operand::Memory(address, value) => {
-                if (0x0400_0200..0x0410_01ff).contains(address) {
-                    *value = cpu.io.read_bus(*address)?;
+                let physical = cpu.translate(*address, Access::Read)?;
+                if (0x0400_0200..0x0410_01ff).contains(&physical) {
+                    *value = cpu.io.read_bus(physical)?;
                 }
                 *value
             },
@@ -45,18 +47,40 @@ impl MemoryCell for Operand {
                     RegisterId::R11 => cpu.registers.r11 = value,
                     RegisterId::R12 => cpu.registers.r12 = value,
                     RegisterId::R13 => cpu.registers.r13 = value,
-                    RegisterId::R14 => cpu.registers.r14 = value
+                    RegisterId::R14 => cpu.registers.r14 = value,
+                    RegisterId::Sp => cpu.stack_pointer = value,
+                    // Writing PC is a jump, the CPU moves to the next instruction after this one
+                    RegisterId::Pc => cpu.program_counter = value.wrapping_sub(15),
+                    // Once paging is on, only interrupt handlers may switch address spaces
+                    RegisterId::Ptbr => {
+                        if cpu.mmu.enabled() && !cpu.status_register.interrupt {
+                            return Err(Interrupt::IllegalMemory);
+                        }
+                        cpu.mmu.set_page_table_base(value);
+                    },
+                    RegisterId::Pfar => cpu.mmu.set_fault_address(value),
+                    // The core ID is wired in by the machine
+                    RegisterId::Cid => return Err(Interrupt::IllegalMemory),
+                    // Interrupt state can only be cleared by iret/irets, keep it intact
+                    #[allow(clippy::cast_possible_truncation)]
+                    RegisterId::Sr => {
+                        let mut status_register = StatusRegister::from(value as u8);
+                        status_register.interrupt = cpu.status_register.interrupt;
+                        status_register.double_fault = cpu.status_register.double_fault;
+                        cpu.status_register = status_register;
+                    }
                 }
                 *initial_value = value;
                 Ok(())
             },
             Operand::Memory(addr, initial_value) => {
-                if (0x0400_0200..0xdfff_ffff).contains(addr) {
+                let physical = cpu.translate(*addr, Access::Write)?;
+                if (0x0400_0200..0xdfff_ffff).contains(&physical) {
                     let bytes = value.to_le_bytes();
-                    if (0x0400_0200..0x0410_01ff).contains(addr) {
-                        cpu.io.write_bus(*addr, value)?;
+                    if (0x0400_0200..0x0410_01ff).contains(&physical) {
+                        cpu.io.write_bus(physical, value)?;
                     } else {
-                        cpu.memory[(*addr as usize)..(*addr as usize + 4)].copy_from_slice(&bytes);
+                        cpu.write_virtual(*addr, &bytes)?;
                     }
                     *initial_value = value;
                     Ok(())
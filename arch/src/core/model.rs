@@ -0,0 +1,128 @@
+use crate::core::instruction::Operation;
+use crate::core::StaticSpecification;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Extension {
+    Arithmetic,
+    Logic,
+    Counting,
+    Comparison,
+    DataMovement,
+    ControlFlow,
+    Stack,
+    AdvancedFlow,
+    Multiprocessing,
+    ConditionalMove,
+    TrappingArithmetic,
+    Loop
+}
+
+impl Extension {
+    pub const ALL: &'static [Extension] = &[
+        Extension::Arithmetic, Extension::Logic, Extension::Counting, Extension::Comparison,
+        Extension::DataMovement, Extension::ControlFlow, Extension::Stack, Extension::AdvancedFlow,
+        Extension::Multiprocessing, Extension::ConditionalMove, Extension::TrappingArithmetic, Extension::Loop
+    ];
+
+    // Extension groups map to the upper nibbles of the opcode (0x01?? -> group 0x01)
+    #[must_use]
+    pub const fn group(self) -> u16 {
+        match self {
+            Extension::Arithmetic => 0x01,
+            Extension::Logic => 0x02,
+            Extension::Counting => 0x03,
+            Extension::Comparison => 0x04,
+            Extension::DataMovement => 0x05,
+            Extension::ControlFlow => 0x06,
+            Extension::Stack => 0x07,
+            Extension::AdvancedFlow => 0x08,
+            Extension::Multiprocessing => 0x09,
+            Extension::ConditionalMove => 0x0A,
+            Extension::TrappingArithmetic => 0x0B,
+            Extension::Loop => 0x0C
+        }
+    }
+
+    #[must_use]
+    pub const fn from_group(group: u16) -> Option<Self> {
+        match group {
+            0x01 => Some(Extension::Arithmetic),
+            0x02 => Some(Extension::Logic),
+            0x03 => Some(Extension::Counting),
+            0x04 => Some(Extension::Comparison),
+            0x05 => Some(Extension::DataMovement),
+            0x06 => Some(Extension::ControlFlow),
+            0x07 => Some(Extension::Stack),
+            0x08 => Some(Extension::AdvancedFlow),
+            0x09 => Some(Extension::Multiprocessing),
+            0x0A => Some(Extension::ConditionalMove),
+            0x0B => Some(Extension::TrappingArithmetic),
+            0x0C => Some(Extension::Loop),
+            _ => None
+        }
+    }
+
+    #[must_use]
+    pub const fn bit(self) -> u32 {
+        1 << self.group()
+    }
+
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Extension::Arithmetic => "Arithmetic and Algebric Instructions",
+            Extension::Logic => "Logic Instructions",
+            Extension::Counting => "Counting Instructions",
+            Extension::Comparison => "Comparison Instructions",
+            Extension::DataMovement => "Data Movement Instructions",
+            Extension::ControlFlow => "Control Flow Instructions",
+            Extension::Stack => "Stack Instructions",
+            Extension::AdvancedFlow => "AFE: Advanced Flow Extension",
+            Extension::Multiprocessing => "MPE: Multiprocessing Extension",
+            Extension::ConditionalMove => "CMX: Conditional Move Extension",
+            Extension::TrappingArithmetic => "TAE: Trapping Arithmetic Extension",
+            Extension::Loop => "LPE: Loop Extension"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ExtensionSet(pub u32);
+
+impl ExtensionSet {
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    #[must_use]
+    pub const fn with(self, extension: Extension) -> Self {
+        Self(self.0 | extension.bit())
+    }
+
+    #[must_use]
+    pub const fn contains(self, extension: Extension) -> bool {
+        self.0 & extension.bit() != 0
+    }
+}
+
+impl From<ExtensionSet> for u32 {
+    fn from(value: ExtensionSet) -> Self {
+        value.0
+    }
+}
+
+#[derive(Debug)]
+pub struct CpuModel {
+    pub short_name: &'static str,
+    pub specification: StaticSpecification<'static>,
+    pub extensions: ExtensionSet
+}
+
+impl CpuModel {
+    #[must_use]
+    pub fn supports(&self, operation: Operation) -> bool {
+        operation.extension()
+            .is_some_and(|extension| self.extensions.contains(extension))
+    }
+}
@@ -1,8 +1,10 @@
-use alloc::format;
+#[cfg(feature = "alloc")]
 use alloc::string::String;
+use core::fmt::Write;
 use crate::core::instruction::Addressing;
 use crate::core::Interrupt;
 use crate::core::registers::RegisterId;
+use crate::cpu::mmu::{Access, AddressTranslation};
 use crate::CPU;
 use crate::CPUResult;
 
@@ -25,39 +27,19 @@ impl Operand {
                 // but need to be interpreted as i32.
                 #[allow(clippy::cast_possible_wrap)]
                 let offset = raw_operand as i32;
-                let target = if offset > 0 {
-                    cpu.program_counter + offset.unsigned_abs()
-                } else {
-                    cpu.program_counter - offset.unsigned_abs()
-                };
-                #[allow(clippy::cast_possible_truncation)]
-                if target > (cpu.memory.len() - 4) as u32 {
-                    Err(Interrupt::IllegalMemory)
-                } else {
-                    Self::memory(target, cpu)
-                }
+                let target = cpu.program_counter
+                    .checked_add_signed(offset)
+                    .ok_or(Interrupt::IllegalMemory)?;
+                Self::memory(target, cpu)
             },
             Addressing::RegisterIndirect => {
                 let register = RegisterId::try_from(raw_operand)?;
                 let target = cpu.get_register(register);
-                #[allow(clippy::cast_possible_truncation)]
-                if target > (cpu.memory.len() - 4) as u32 {
-                    Err(Interrupt::IllegalMemory)
-                } else {
-                    Self::memory(target, cpu)
-                }
+                Self::memory(target, cpu)
             },
             Addressing::Indirect => {
-                let target = u32::from_le_bytes([
-                    cpu.memory[raw_operand as usize], cpu.memory[raw_operand as usize + 1],
-                    cpu.memory[raw_operand as usize + 2], cpu.memory[raw_operand as usize + 3]
-                ]);
-                #[allow(clippy::cast_possible_truncation)]
-                if target > (cpu.memory.len() - 4) as u32 {
-                    Err(Interrupt::IllegalMemory)
-                } else {
-                    Self::memory(target, cpu)
-                }
+                let target = u32::from_le_bytes(cpu.read_virtual(raw_operand, Access::Read)?);
+                Self::memory(target, cpu)
             }
             Addressing::Implied => Ok(Self::Void)
         }
@@ -70,44 +52,51 @@ impl Operand {
         Ok(Operand::Register(register, value))
     }
 
+    // Memory operands keep their virtual address, translation happens on every access
     fn memory(address: u32, cpu: &CPU) -> CPUResult<Self> {
-        #[allow(clippy::cast_possible_truncation)]
-        if address > (cpu.memory.len() - 4) as u32 {
-            return Err(Interrupt::IllegalMemory);
-        }
-
-        let value_word = u32::from_le_bytes([
-            cpu.memory[address as usize],
-            cpu.memory[address as usize + 1],
-            cpu.memory[address as usize + 2],
-            cpu.memory[address as usize + 3]
-        ]);
+        let value_word = u32::from_le_bytes(cpu.read_virtual(address, Access::Read)?);
         Ok(Operand::Memory(address, value_word))
     }
 
+    #[cfg(feature = "alloc")]
     #[must_use]
     pub fn disassemble(raw_operand: u32, cpu: &CPU, mode: Addressing) -> String {
+        let mut disassembled = String::new();
+        // Writing to a String never fails
+        let _ = Self::disassemble_into(raw_operand, cpu, mode, &mut disassembled);
+        disassembled
+    }
+
+    pub fn disassemble_into(raw_operand: u32, cpu: &CPU, mode: Addressing, f: &mut dyn Write) -> core::fmt::Result {
         match mode {
             Addressing::RegisterIndirect => match RegisterId::try_from(raw_operand) {
-                Ok(register) => format!("[{register:?}]").to_lowercase(),
-                Err(_) => String::from("<unk>")
+                Ok(register) => write!(f, "[{}]", register.name()),
+                Err(_) => f.write_str("<unk>")
             },
-            Addressing::Indirect => format!("[${raw_operand:0>8x}]"),
+            Addressing::Indirect => write!(f, "[${raw_operand:0>8x}]"),
             _ => if let Ok(operand) = Operand::decode(raw_operand, cpu, mode) {
-                operand.disassemble_self()
+                operand.disassemble_self_into(f)
             } else {
-                String::from("<unk>")
+                f.write_str("<unk>")
             }
         }
     }
 
+    #[cfg(feature = "alloc")]
     #[must_use]
     pub fn disassemble_self(&self) -> String {
+        let mut disassembled = String::new();
+        // Writing to a String never fails
+        let _ = self.disassemble_self_into(&mut disassembled);
+        disassembled
+    }
+
+    pub fn disassemble_self_into(&self, f: &mut dyn Write) -> core::fmt::Result {
         match self {
-            Self::Literal(value) => format!("#${value:X}"),
-            Self::Register(id, _) => format!("{id:?}").to_lowercase(),
-            Self::Memory(address, _) => format!("${address:0>8x}"),
-            Self::Void => String::new(),
+            Self::Literal(value) => write!(f, "#${value:X}"),
+            Self::Register(id, _) => f.write_str(id.name()),
+            Self::Memory(address, _) => write!(f, "${address:0>8x}"),
+            Self::Void => Ok(()),
         }
     }
 }
@@ -4,7 +4,7 @@ pub mod status_register;
 pub use register_id::RegisterId;
 pub use status_register::StatusRegister;
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct Registers {
     pub r0: u32,
     pub r1: u32,
@@ -1,3 +1,4 @@
+#[cfg(feature = "alloc")]
 use alloc::string::String;
 use crate::core::interrupt::Interrupt;
 use crate::CPUResult;
@@ -5,7 +6,8 @@ use crate::CPUResult;
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum RegisterId {
     R0, R1, R2, R3, R4, R5, R6, R7,
-    R8, R9, R10, R11, R12, R13, R14
+    R8, R9, R10, R11, R12, R13, R14,
+    Sp, Pc, Sr, Ptbr, Pfar, Cid
 }
 
 impl TryFrom<u32> for RegisterId {
@@ -28,11 +30,18 @@ impl TryFrom<u32> for RegisterId {
             0x1009 => Ok(Self::R12),
             0x100A => Ok(Self::R13),
             0x100B => Ok(Self::R14),
+            0x2000 => Ok(Self::Sp),
+            0x2001 => Ok(Self::Pc),
+            0x2002 => Ok(Self::Sr),
+            0x2003 => Ok(Self::Ptbr),
+            0x2004 => Ok(Self::Pfar),
+            0x2005 => Ok(Self::Cid),
             _ => Err(Interrupt::IllegalMemory)
         }
     }
 }
 
+#[cfg(feature = "alloc")]
 impl TryFrom<&String> for RegisterId {
     type Error = Interrupt;
 
@@ -46,6 +55,19 @@ impl TryFrom<&String> for RegisterId {
             "r5" => Ok(Self::R5),
             "r6" => Ok(Self::R6),
             "r7" => Ok(Self::R7),
+            "r8" => Ok(Self::R8),
+            "r9" => Ok(Self::R9),
+            "r10" => Ok(Self::R10),
+            "r11" => Ok(Self::R11),
+            "r12" => Ok(Self::R12),
+            "r13" => Ok(Self::R13),
+            "r14" => Ok(Self::R14),
+            "sp" => Ok(Self::Sp),
+            "pc" => Ok(Self::Pc),
+            "sr" => Ok(Self::Sr),
+            "ptbr" => Ok(Self::Ptbr),
+            "pfar" => Ok(Self::Pfar),
+            "cid" => Ok(Self::Cid),
             _ => Err(Interrupt::IllegalMemory),
         }
     }
@@ -69,6 +91,42 @@ impl From<RegisterId> for u32 {
             RegisterId::R12 => 0x1009,
             RegisterId::R13 => 0x100A,
             RegisterId::R14 => 0x100B,
+            RegisterId::Sp => 0x2000,
+            RegisterId::Pc => 0x2001,
+            RegisterId::Sr => 0x2002,
+            RegisterId::Ptbr => 0x2003,
+            RegisterId::Pfar => 0x2004,
+            RegisterId::Cid => 0x2005,
+        }
+    }
+}
+
+impl RegisterId {
+    // Name as written in assembly
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            RegisterId::R0 => "r0",
+            RegisterId::R1 => "r1",
+            RegisterId::R2 => "r2",
+            RegisterId::R3 => "r3",
+            RegisterId::R4 => "r4",
+            RegisterId::R5 => "r5",
+            RegisterId::R6 => "r6",
+            RegisterId::R7 => "r7",
+            RegisterId::R8 => "r8",
+            RegisterId::R9 => "r9",
+            RegisterId::R10 => "r10",
+            RegisterId::R11 => "r11",
+            RegisterId::R12 => "r12",
+            RegisterId::R13 => "r13",
+            RegisterId::R14 => "r14",
+            RegisterId::Sp => "sp",
+            RegisterId::Pc => "pc",
+            RegisterId::Sr => "sr",
+            RegisterId::Ptbr => "ptbr",
+            RegisterId::Pfar => "pfar",
+            RegisterId::Cid => "cid",
         }
     }
 }
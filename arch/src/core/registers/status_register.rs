@@ -13,6 +13,14 @@ pub struct StatusRegister {
     pub carry: bool
 }
 
+impl StatusRegister {
+    // Zero and negative as set by every operation producing a result
+    pub fn set_result(&mut self, result: u32) {
+        self.zero = result == 0;
+        self.negative = result >> 31 == 1;
+    }
+}
+
 impl Display for StatusRegister {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let negative = get_flag_char('i', self.negative);
@@ -1,7 +1,15 @@
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use crate::BASE_SYSTEM_SIZE;
+use crate::core::CpuModel;
+use crate::models::find_model_by_id;
+use crate::storage::FixedVec;
+
+// Three length-prefixed strings of at most 255 bytes, plus the fixed-size fields
+pub const SPECIFICATION_CAPACITY: usize = 3 * 256 + 4 + 2 + 5 * 4;
 
 #[allow(clippy::module_name_repetitions)]
+#[derive(Debug, Clone, Copy)]
 pub struct StaticSpecification<'a> {
     pub name: &'a [u8],
     pub id: u32,
@@ -15,50 +23,67 @@ pub struct StaticSpecification<'a> {
 pub struct Specification<'a> {
     pub specification: StaticSpecification<'a>,
     pub available_ram: u32,
-    pub vm_end: u32
+    pub vm_end: u32,
+    pub features: u32,
+    pub cores: u32
 }
 
 impl<'a> Specification<'a> {
+    // A single core, with the extensions of the model this specification belongs to, if any
     #[must_use]
     pub fn new(static_specification: StaticSpecification<'a>, memory: usize) -> Specification<'a> {
         #[allow(clippy::cast_possible_truncation)]
         Self {
             specification: static_specification,
-            available_ram: (memory - BASE_SYSTEM_SIZE) as u32,
-            vm_end: memory as u32
+            available_ram: memory.saturating_sub(BASE_SYSTEM_SIZE) as u32,
+            vm_end: memory as u32,
+            features: find_model_by_id(static_specification.id).map_or(0, |model| model.extensions.into()),
+            cores: 1
         }
     }
-}
 
-impl From<Specification<'_>> for Vec<u8> {
-    // Strings in the specification are truncated to max 255 chars
-    #[allow(clippy::cast_possible_truncation)]
-    fn from(value: Specification) -> Self {
-        let mut bytes: Vec<u8> = Vec::with_capacity(511);
+    #[must_use]
+    pub fn for_model(model: &CpuModel, memory: usize, cores: u32) -> Specification<'a> {
+        Self {
+            features: model.extensions.into(),
+            cores,
+            ..Self::new(model.specification, memory)
+        }
+    }
+}
 
-        let name_bytes = value.specification.name;
-        let id_bytes = value.specification.id.to_le_bytes();
-        let microarchitecture_bytes = value.specification.microarchitecture;
-        let microarchitecture_name_bytes = value.specification.microarchitecture_name;
-        let data_width = value.specification.data_width;
-        let address_width = value.specification.address_width;
-        let microcode_bytes = value.specification.microcode.to_le_bytes();
-        let available_ram_bytes = value.available_ram.to_le_bytes();
-        let vm_end_bytes = value.vm_end.to_le_bytes();
+impl Specification<'_> {
+    #[must_use]
+    pub fn to_bytes(&self) -> FixedVec<u8, SPECIFICATION_CAPACITY> {
+        let mut bytes = FixedVec::new();
 
-        bytes.push(name_bytes.len() as u8);
-        bytes.extend_from_slice(name_bytes);
-        bytes.extend_from_slice(&id_bytes);
-        bytes.push(microarchitecture_bytes.len() as u8);
-        bytes.extend_from_slice(microarchitecture_bytes);
-        bytes.push(microarchitecture_name_bytes.len() as u8);
-        bytes.extend_from_slice(microarchitecture_name_bytes);
-        bytes.push(data_width);
-        bytes.push(address_width);
-        bytes.extend_from_slice(&available_ram_bytes);
-        bytes.extend_from_slice(&microcode_bytes);
-        bytes.extend_from_slice(&vm_end_bytes);
+        push_string(&mut bytes, self.specification.name);
+        bytes.extend_from_slice(&self.specification.id.to_le_bytes());
+        push_string(&mut bytes, self.specification.microarchitecture);
+        push_string(&mut bytes, self.specification.microarchitecture_name);
+        bytes.push(self.specification.data_width);
+        bytes.push(self.specification.address_width);
+        bytes.extend_from_slice(&self.available_ram.to_le_bytes());
+        bytes.extend_from_slice(&self.specification.microcode.to_le_bytes());
+        bytes.extend_from_slice(&self.vm_end.to_le_bytes());
+        bytes.extend_from_slice(&self.features.to_le_bytes());
+        bytes.extend_from_slice(&self.cores.to_le_bytes());
 
         bytes
     }
 }
+
+#[cfg(feature = "alloc")]
+impl From<Specification<'_>> for Vec<u8> {
+    fn from(value: Specification) -> Self {
+        value.to_bytes().to_vec()
+    }
+}
+
+// Strings in the specification are truncated to max 255 chars
+#[allow(clippy::cast_possible_truncation)]
+fn push_string(bytes: &mut FixedVec<u8, SPECIFICATION_CAPACITY>, string: &[u8]) {
+    let string = &string[..string.len().min(255)];
+    bytes.push(string.len() as u8);
+    bytes.extend_from_slice(string);
+}
@@ -1,34 +1,77 @@
 use core::fmt::{Display, Formatter};
 use crate::core::Interrupt;
+use crate::core::registers::StatusRegister;
+use crate::core::symbols::SymbolLookup;
 use crate::CPU;
 use crate::cpu::Decoder;
 
-pub struct StackTrace<'a> {
-    cpu: &'a CPU,
-    interrupt: Interrupt
+// Formats straight into the output, so traces can be printed without allocating
+pub(crate) struct Disassembly<'a, 'b>(pub(crate) &'a CPU<'b>);
+struct Frames<'a, 'b>(&'a CPU<'b>, Option<&'a dyn SymbolLookup>);
+struct Location<'a>(u32, Option<&'a dyn SymbolLookup>);
+
+impl Display for Disassembly<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.0.write_instruction_string(self.0.program_counter, f)
+    }
+}
+
+impl Display for Frames<'_, '_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Interrupt::write_stack_trace(f, &self.0.system_stack, self.0.status_register, self.1)
+    }
+}
+
+impl Display for Location<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{:0>8x}", self.0)?;
+        match self.1.and_then(|symbols| symbols.lookup(self.0)) {
+            Some(symbol) => write!(f, " <{symbol}>"),
+            None => Ok(())
+        }
+    }
 }
 
-impl<'a> StackTrace<'a> {
+// `'b` is how long the CPU's devices live, the trace only borrows the CPU for `'a`
+pub struct StackTrace<'a, 'b> {
+    cpu: &'a CPU<'b>,
+    interrupt: Interrupt,
+    symbols: Option<&'a dyn SymbolLookup>
+}
+
+impl<'a, 'b> StackTrace<'a, 'b> {
     #[must_use]
-    pub fn new(interrupt: Interrupt, cpu: &'a CPU) -> Self {
+    pub fn new(interrupt: Interrupt, cpu: &'a CPU<'b>) -> Self {
         Self {
             cpu,
-            interrupt
+            interrupt,
+            symbols: None
         }
     }
+
+    // Names code addresses after the labels they fall under instead of leaving them as ??
+    #[must_use]
+    pub fn with_symbols(mut self, symbols: &'a dyn SymbolLookup) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+}
+
+// How deep into nested interrupt handling the CPU was when it gave up
+pub(crate) fn fault_reason(status_register: StatusRegister) -> &'static str {
+    match (status_register.interrupt, status_register.double_fault) {
+        (false, false) => "interrupt",
+        (true, _) => "double fault",
+        (_, true) => "triple fault"
+    }
 }
 
-impl Display for StackTrace<'_> {
+impl Display for StackTrace<'_, '_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let cpu = self.cpu;
-        let stack_trace = Interrupt::get_stack_trace(&cpu.system_stack, cpu.status_register);
 
         write!(f, include!("stack_trace_template.txt"),
-               reason = match (cpu.status_register.interrupt, cpu.status_register.double_fault) {
-                (false, false) => "interrupt",
-                (true, _) => "double fault",
-                (_, true) => "triple fault"
-            },
+               reason = fault_reason(cpu.status_register),
                interrupt = self.interrupt,
                r0 = cpu.registers.r0,
                r1 = cpu.registers.r1,
@@ -47,10 +90,10 @@ impl Display for StackTrace<'_> {
                r14 = cpu.registers.r14,
                sp = cpu.stack_pointer,
                sr = cpu.status_register,
-               pc = cpu.program_counter,
+               pc = Location(cpu.program_counter, self.symbols),
                state = cpu.extract_instruction_infailible(cpu.program_counter),
-               disassembler = cpu.read_instruction_string(cpu.program_counter),
-               stack_trace = stack_trace,
+               disassembler = Disassembly(cpu),
+               stack_trace = Frames(cpu, self.symbols),
         )
     }
 }
@@ -0,0 +1,151 @@
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+// A code address expressed relative to the closest label at or before it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub offset: u32
+}
+
+impl Display for Symbol<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        if self.offset == 0 {
+            f.write_str(self.name)
+        } else {
+            write!(f, "{}+{:#x}", self.name, self.offset)
+        }
+    }
+}
+
+pub trait SymbolLookup {
+    fn lookup(&self, address: u32) -> Option<Symbol<'_>>;
+}
+
+// Labels sorted by address, e.g. a table built into the firmware when there is no allocator
+impl<S: AsRef<str>> SymbolLookup for [(u32, S)] {
+    fn lookup(&self, address: u32) -> Option<Symbol<'_>> {
+        let index = self.partition_point(|(start, _)| *start <= address).checked_sub(1)?;
+        let (start, name) = &self[index];
+
+        Some(Symbol {
+            name: name.as_ref(),
+            offset: address - start
+        })
+    }
+}
+
+// Symbol map written by vasm: one `<address in hex> <label>` pair per line. Ends mark where the
+// code under a label stops, e.g. the end of the image, addresses past them don't resolve
+#[cfg(feature = "alloc")]
+#[derive(Debug, Default, Clone)]
+pub struct SymbolTable {
+    symbols: Vec<(u32, String)>,
+    ends: Vec<u32>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SymbolMapError {
+    pub line: usize
+}
+
+#[cfg(feature = "alloc")]
+impl SymbolTable {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Labels sharing an address resolve to the first one in alphabetical order
+    pub fn insert(&mut self, address: u32, name: &str) {
+        let index = self.symbols.partition_point(|(start, existing)| (*start, existing.as_str()) <= (address, name));
+        self.symbols.insert(index, (address, name.to_string()));
+    }
+
+    // Addresses from `address` on no longer belong to the labels before it
+    pub fn insert_end(&mut self, address: u32) {
+        let index = self.ends.partition_point(|end| *end < address);
+        self.ends.insert(index, address);
+    }
+
+    // Tables for images that don't overlap, e.g. a BIOS and the program it loads
+    pub fn merge(&mut self, other: &Self) {
+        for (address, name) in other.iter() {
+            self.insert(address, name);
+        }
+        for end in &other.ends {
+            self.insert_end(*end);
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &str)> {
+        self.symbols.iter().map(|(address, name)| (*address, name.as_str()))
+    }
+
+    pub fn parse_map(map: &str) -> Result<Self, SymbolMapError> {
+        let mut table = Self::new();
+
+        for (index, line) in map.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = SymbolMapError { line: index + 1 };
+            let (address, name) = line.split_once(' ').ok_or(error)?;
+            let address = u32::from_str_radix(address, 16).map_err(|_| error)?;
+            table.insert(address, name.trim());
+        }
+
+        Ok(table)
+    }
+
+    #[must_use]
+    pub fn to_map(&self) -> String {
+        let mut map = String::new();
+        for (address, name) in self.iter() {
+            // Writing to a String never fails
+            let _ = core::fmt::Write::write_fmt(&mut map, format_args!("{address:0>8x} {name}\n"));
+        }
+        map
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl SymbolLookup for SymbolTable {
+    fn lookup(&self, address: u32) -> Option<Symbol<'_>> {
+        // The first of several labels at the same address wins, the slice lookup picks the last
+        let symbol = self.symbols.as_slice().lookup(address)?;
+        let start = address - symbol.offset;
+        let next_end = self.ends.partition_point(|end| *end <= start);
+        if self.ends.get(next_end).is_some_and(|end| *end <= address) {
+            return None;
+        }
+
+        let first = self.symbols.partition_point(|(existing, _)| *existing < start);
+
+        Some(Symbol {
+            name: &self.symbols[first].1,
+            offset: symbol.offset
+        })
+    }
+}
+
+impl Display for SymbolMapError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid symbol map entry on line {}", self.line)
+    }
+}
@@ -1,74 +1,156 @@
 pub mod system_stack;
 pub mod user_stack;
 pub mod decoder;
+pub mod mmu;
+pub mod cache;
 mod io_controller;
 
-use alloc::boxed::Box;
 pub use decoder::Decoder;
 pub use system_stack::SystemStack;
 pub use user_stack::UserStack;
+pub use mmu::{AddressTranslation, Mmu};
+pub use cache::{Cache, CacheConfig, CacheConfigError, CacheStatistics, Caches, WritePolicy};
 
-use alloc::vec;
-use alloc::vec::Vec;
-use crate::core::{Interrupt, Specification};
+#[cfg(feature = "alloc")]
+use alloc::{vec, vec::Vec};
+#[cfg(feature = "alloc")]
+use crate::models::DEFAULT_MODEL;
+use crate::core::{CpuModel, Interrupt, Specification};
 use crate::core::registers::RegisterId;
 use crate::core::Registers;
 use crate::core::registers::StatusRegister;
-use crate::{BusDevice, CPUResult, InstructionResult, CPU_SPECIFICATION};
+use crate::{CPUResult, InstructionResult};
 use crate::cpu::io_controller::IOController;
+use crate::storage::{Device, Memory, SystemStackWords};
 
 #[derive(Debug)]
-pub struct CPU {
+pub struct CPU<'a> {
     pub registers: Registers,
     pub stack_pointer: u32,
     pub program_counter: u32,
     pub status_register: StatusRegister,
-    pub memory: Box<[u8]>,
-    pub system_stack: Vec<u32>,
-    pub io: IOController
+    pub memory: Memory<'a>,
+    pub system_stack: SystemStackWords,
+    pub io: IOController<'a>,
+    pub mmu: Mmu,
+    pub caches: Caches,
+    pub cycles: u64,
+    pub model: &'static CpuModel,
+    pub core_id: u32,
+    pub core_count: u32,
+    // Pending inter-processor interrupts, one bit per core
+    pub ipi_requests: u32,
+    // Where execution starts, and starts over on reset
+    pub entry_point: u32
 }
 
-impl CPU {
+// Each core gets its own slice of the stack area
+pub const CORE_STACK_SIZE: u32 = 0x0001_0000;
+pub const DEFAULT_ENTRY_POINT: u32 = 0x0000_0200;
+
+// A piece of an image put in memory before the CPU starts, e.g. a BIOS or a program
+#[derive(Debug, Clone, Copy)]
+pub struct LoadSegment<'a> {
+    pub address: u32,
+    pub data: &'a [u8]
+}
+
+impl<'a> CPU<'a> {
+    #[cfg(feature = "alloc")]
     #[must_use]
     pub fn new(memory_size: usize) -> Self {
+        Self::new_with_model(memory_size, DEFAULT_MODEL)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub fn new_with_model(memory_size: usize, model: &'static CpuModel) -> Self {
+        Self::with_memory(vec![0u8; memory_size].into_boxed_slice(), model)
+    }
+
+    // Memory is used as is, callers providing their own buffer decide what it starts with
+    #[must_use]
+    pub fn with_memory(memory: Memory<'a>, model: &'static CpuModel) -> Self {
         Self {
             registers: Registers::default(),
             stack_pointer: 0x0000_0000,
-            program_counter: 0x0000_0200,
+            program_counter: DEFAULT_ENTRY_POINT,
             status_register: StatusRegister::default(),
-            memory: vec![0u8; memory_size].into_boxed_slice(),
-            system_stack: vec![],
-            io: IOController::default()
+            memory,
+            system_stack: SystemStackWords::default(),
+            io: IOController::default(),
+            mmu: Mmu::default(),
+            caches: Caches::default(),
+            cycles: 0,
+            model,
+            core_id: 0,
+            core_count: 1,
+            ipi_requests: 0,
+            entry_point: DEFAULT_ENTRY_POINT
         }
     }
 
     pub fn load_rom(&mut self, rom: &[u8]) -> CPUResult<()> {
-        let base_address = 0x0000_0200;
-        let end_address = base_address + rom.len();
-        let rom_region = base_address..end_address;
-        self.memory[rom_region].copy_from_slice(rom);
+        let segment = LoadSegment { address: DEFAULT_ENTRY_POINT, data: rom };
+        self.load_segments(&[segment], DEFAULT_ENTRY_POINT)
+    }
 
-        let specification: Vec<u8> = Specification::new(CPU_SPECIFICATION, self.memory.len()).into();
+    // Later segments overwrite earlier ones where they overlap, the specification block goes in
+    // last
+    pub fn load_segments(&mut self, segments: &[LoadSegment], entry_point: u32) -> CPUResult<()> {
+        for segment in segments {
+            let base_address = segment.address as usize;
+            let end_address = base_address.checked_add(segment.data.len()).ok_or(Interrupt::IllegalMemory)?;
+            self.memory.get_mut(base_address..end_address)
+                .ok_or(Interrupt::IllegalMemory)?
+                .copy_from_slice(segment.data);
+        }
+
+        let specification = Specification::for_model(self.model, self.memory.len(), self.core_count).to_bytes();
         let base_address = 0x0000_0000;
         let end_address = base_address + specification.len();
         let specification_region = base_address..end_address;
-        self.memory[specification_region].copy_from_slice(specification.as_slice());
+        self.memory.get_mut(specification_region)
+            .ok_or(Interrupt::IllegalMemory)?
+            .copy_from_slice(&specification);
 
-        // Reset stack pointer to the start of the stack
-        self.stack_pointer = 0x0410_0201;
-        self.system_stack_save_state()?;
+        self.entry_point = entry_point;
+        self.program_counter = entry_point;
+        self.reset_stacks()
+    }
 
-        Ok(())
+    pub fn reset_stacks(&mut self) -> CPUResult<()> {
+        // Reset stack pointer to the start of this core's stack
+        self.stack_pointer = 0x0410_0201 + self.core_id * CORE_STACK_SIZE;
+        self.system_stack.clear();
+        self.system_stack_save_state()
+    }
+
+    // Warm reset, the core starts over from its entry point while memory and devices are left as
+    // they are
+    pub fn reset(&mut self) -> CPUResult<()> {
+        self.registers = Registers::default();
+        self.program_counter = self.entry_point;
+        self.status_register = StatusRegister::default();
+        self.mmu = Mmu::default();
+        self.caches.invalidate();
+        self.ipi_requests = 0;
+        self.reset_stacks()
     }
 
-    pub fn register_devices(&mut self, devices: Vec<Box<dyn BusDevice>>) -> CPUResult<()> {
+    #[cfg(feature = "alloc")]
+    pub fn register_devices(&mut self, devices: Vec<Device<'a>>) -> CPUResult<()> {
         for device in devices {
-            self.io.add(device)?;
+            self.add_device(device)?;
         }
 
         Ok(())
     }
 
+    pub fn add_device(&mut self, device: Device<'a>) -> CPUResult<()> {
+        self.io.add(device)
+    }
+
     #[must_use]
     pub fn get_register(&self, register_id: RegisterId) -> u32 {
         match register_id {
@@ -86,23 +168,51 @@ impl CPU {
             RegisterId::R11 => self.registers.r11,
             RegisterId::R12 => self.registers.r12,
             RegisterId::R13 => self.registers.r13,
-            RegisterId::R14 => self.registers.r14
+            RegisterId::R14 => self.registers.r14,
+            RegisterId::Sp => self.stack_pointer,
+            // Reading PC yields the address of the instruction being executed
+            RegisterId::Pc => self.program_counter,
+            RegisterId::Sr => u32::from(u8::from(self.status_register)),
+            RegisterId::Ptbr => self.mmu.page_table_base(),
+            RegisterId::Pfar => self.mmu.fault_address(),
+            RegisterId::Cid => self.core_id
         }
     }
 
     fn has_interrupt_handler(&self) -> bool {
-        let ptr = self.memory.get(0x0450_0200..=0x0450_0203).unwrap_or_default();
-        ptr != 0u32.to_le_bytes()
+        self.read_vector(0x0450_0200).is_ok_and(|address| address != 0)
+    }
+
+    // Vectors live in the system area, which may be missing on machines with no RAM
+    fn read_vector(&self, address: usize) -> CPUResult<u32> {
+        let bytes = self.memory.get(address..address + 4).ok_or(Interrupt::IllegalMemory)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
     }
 
     pub fn tick_unhandled(&mut self) -> InstructionResult {
-        if let Err(e) = self.io.tick() {
-            if !self.status_register.interrupt && !self.status_register.double_fault {
-                return Err(e)
+        let in_handler = self.status_register.interrupt || self.status_register.double_fault;
+
+        // Devices are driven by (and interrupt) the bootstrap core
+        if self.core_id == 0 {
+            if let Err(e) = self.io.tick() {
+                if !in_handler {
+                    return Err(e)
+                }
             }
         }
+
+        // Inter-processor interrupts stay pending until the target core can take them
+        let ipi = 1 << self.core_id;
+        if self.ipi_requests & ipi != 0 && !in_handler && !self.status_register.interrupt_disable {
+            self.ipi_requests &= !ipi;
+            return Err(Interrupt::InterProcessor);
+        }
+
         let mut instruction = self.read_instruction(self.program_counter)?;
-        if let Err(interrupt) = instruction.execute_unhandled(self) {
+        let result = instruction.execute_unhandled(self);
+        self.cycles += 1 + self.caches.take_penalty();
+
+        if let Err(interrupt) = result {
             if self.status_register.interrupt_disable && interrupt.is_maskable() {
                 Ok(())
             } else {
@@ -120,19 +230,11 @@ impl CPU {
         if self.status_register.interrupt {
             self.status_register.double_fault = true;
             self.registers.r14 = interrupt.into();
-            let address = u32::from_le_bytes([
-                self.memory[0x0450_0204], self.memory[0x0450_0205],
-                self.memory[0x0450_0206], self.memory[0x0450_0207]
-            ]);
-            self.program_counter = address;
+            self.program_counter = self.read_vector(0x0450_0204)?;
         // Otherwise this is the first time we see an interrupt, so just use the configured handler
         } else {
             self.status_register.interrupt = true;
-            let address = u32::from_le_bytes([
-                self.memory[0x0450_0200], self.memory[0x0450_0201],
-                self.memory[0x0450_0202], self.memory[0x0450_0203]
-            ]);
-            self.program_counter = address;
+            self.program_counter = self.read_vector(0x0450_0200)?;
         }
 
         Ok(())
@@ -0,0 +1,300 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::cell::{Cell, RefCell};
+use core::fmt::{Display, Formatter};
+use crate::cpu::mmu::Access;
+#[cfg(not(feature = "alloc"))]
+use crate::storage::{FixedVec, CACHE_LINE_CAPACITY};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WritePolicy {
+    // Writes go straight to memory and do not allocate a line on miss
+    WriteThrough,
+    // Writes allocate a line and only reach memory when a dirty line is evicted
+    WriteBack
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CacheConfig {
+    pub size: usize,
+    pub line_size: usize,
+    pub associativity: usize,
+    pub write_policy: WritePolicy,
+    pub miss_penalty: u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CacheConfigError {
+    // Line size in bytes, must be a power of two
+    LineSize(usize),
+    NoWays,
+    // Size in bytes, must split into a power of two number of sets
+    Size(usize),
+    // Number of lines, more than fit the fixed line storage without alloc
+    TooManyLines(usize)
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CacheStatistics {
+    pub hits: u64,
+    pub misses: u64,
+    pub writebacks: u64,
+    pub memory_writes: u64
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Line {
+    tag: u32,
+    valid: bool,
+    dirty: bool,
+    last_used: u64
+}
+
+// Lines of every set one after the other, `associativity` lines per set
+#[cfg(feature = "alloc")]
+type Lines = Vec<Line>;
+#[cfg(not(feature = "alloc"))]
+type Lines = FixedVec<Line, CACHE_LINE_CAPACITY>;
+
+#[derive(Debug)]
+pub struct Cache {
+    config: CacheConfig,
+    lines: Lines,
+    statistics: CacheStatistics,
+    clock: u64
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            size: 4096,
+            line_size: 32,
+            associativity: 2,
+            write_policy: WritePolicy::WriteBack,
+            miss_penalty: 10
+        }
+    }
+}
+
+impl CacheStatistics {
+    #[must_use]
+    pub fn accesses(&self) -> u64 {
+        self.hits + self.misses
+    }
+
+    // Rates are only meant for display, precision loss is fine
+    #[allow(clippy::cast_precision_loss)]
+    #[must_use]
+    pub fn hit_rate(&self) -> f64 {
+        if self.accesses() == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.accesses() as f64
+        }
+    }
+}
+
+impl Cache {
+    pub fn new(config: CacheConfig) -> Result<Self, CacheConfigError> {
+        if !config.line_size.is_power_of_two() {
+            return Err(CacheConfigError::LineSize(config.line_size));
+        }
+        if config.associativity == 0 {
+            return Err(CacheConfigError::NoWays);
+        }
+
+        let set_size = config.line_size.saturating_mul(config.associativity);
+        if !config.size.is_multiple_of(set_size) || !(config.size / set_size).is_power_of_two() {
+            return Err(CacheConfigError::Size(config.size));
+        }
+
+        #[cfg(not(feature = "alloc"))]
+        if config.size / config.line_size > CACHE_LINE_CAPACITY {
+            return Err(CacheConfigError::TooManyLines(config.size / config.line_size));
+        }
+
+        let mut lines = Lines::default();
+        for _ in 0..config.size / config.line_size {
+            lines.push(Line::default());
+        }
+
+        Ok(Self {
+            config,
+            lines,
+            statistics: CacheStatistics::default(),
+            clock: 0
+        })
+    }
+
+    #[must_use]
+    pub fn config(&self) -> CacheConfig {
+        self.config
+    }
+
+    #[must_use]
+    pub fn statistics(&self) -> CacheStatistics {
+        self.statistics
+    }
+
+    pub fn reset_statistics(&mut self) {
+        self.statistics = CacheStatistics::default();
+    }
+
+    // Drops every line without writing dirty ones back, statistics are kept
+    pub fn invalidate(&mut self) {
+        self.lines.fill(Line::default());
+        self.clock = 0;
+    }
+
+    // Returns the number of penalty cycles caused by this access
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn access(&mut self, address: u32, write: bool) -> u64 {
+        let line_number = address / self.config.line_size as u32;
+        let associativity = self.config.associativity;
+        let set_count = (self.lines.len() / associativity) as u32;
+        let set_index = (line_number % set_count) as usize;
+        let tag = line_number / set_count;
+
+        self.clock += 1;
+        let clock = self.clock;
+        let write_back = self.config.write_policy == WritePolicy::WriteBack;
+        let set = &mut self.lines[set_index * associativity..(set_index + 1) * associativity];
+
+        if write && !write_back {
+            self.statistics.memory_writes += 1;
+        }
+
+        if let Some(line) = set.iter_mut().find(|line| line.valid && line.tag == tag) {
+            line.last_used = clock;
+            line.dirty |= write && write_back;
+            self.statistics.hits += 1;
+            return 0;
+        }
+
+        self.statistics.misses += 1;
+        let mut penalty = self.config.miss_penalty;
+
+        // Write-through caches don't allocate on a write miss
+        if write && !write_back {
+            return penalty;
+        }
+
+        // Evict the least recently used line
+        let victim = set.iter_mut()
+            .min_by_key(|line| (line.valid, line.last_used))
+            .unwrap_or_else(|| unreachable!("cache sets are never empty"));
+
+        if victim.valid && victim.dirty {
+            self.statistics.writebacks += 1;
+            self.statistics.memory_writes += 1;
+            penalty += self.config.miss_penalty;
+        }
+
+        *victim = Line {
+            tag,
+            valid: true,
+            dirty: write && write_back,
+            last_used: clock
+        };
+
+        penalty
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Caches {
+    pub instruction: Option<RefCell<Cache>>,
+    pub data: Option<RefCell<Cache>>,
+    penalty: Cell<u64>,
+    bypass: Cell<bool>
+}
+
+impl Caches {
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.instruction.is_some() || self.data.is_some()
+    }
+
+    pub fn set_instruction_cache(&mut self, config: Option<CacheConfig>) -> Result<(), CacheConfigError> {
+        self.instruction = config.map(Cache::new).transpose()?.map(RefCell::new);
+        Ok(())
+    }
+
+    pub fn set_data_cache(&mut self, config: Option<CacheConfig>) -> Result<(), CacheConfigError> {
+        self.data = config.map(Cache::new).transpose()?.map(RefCell::new);
+        Ok(())
+    }
+
+    // On a reset the caches come back empty, but stay configured
+    pub fn invalidate(&self) {
+        for cache in [&self.instruction, &self.data].into_iter().flatten() {
+            cache.borrow_mut().invalidate();
+        }
+        self.penalty.set(0);
+    }
+
+    #[must_use]
+    pub fn instruction_statistics(&self) -> Option<CacheStatistics> {
+        self.instruction.as_ref().map(|cache| cache.borrow().statistics())
+    }
+
+    #[must_use]
+    pub fn data_statistics(&self) -> Option<CacheStatistics> {
+        self.data.as_ref().map(|cache| cache.borrow().statistics())
+    }
+
+    // Record an access to a physically contiguous range of memory
+    pub fn record(&self, access: Access, address: u32, length: u32) {
+        match access {
+            Access::Execute => self.access(self.instruction.as_ref(), address, length, false),
+            Access::Read => self.access(self.data.as_ref(), address, length, false),
+            Access::Write => self.access(self.data.as_ref(), address, length, true)
+        }
+    }
+
+    // Penalty cycles accumulated since the last call
+    pub fn take_penalty(&self) -> u64 {
+        self.penalty.take()
+    }
+
+    // Run host-side inspection (disassembly, stack traces) without touching the statistics
+    pub fn bypass<T>(&self, f: impl FnOnce() -> T) -> T {
+        let previous = self.bypass.replace(true);
+        let result = f();
+        self.bypass.set(previous);
+        result
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn access(&self, cache: Option<&RefCell<Cache>>, address: u32, length: u32, write: bool) {
+        // Device registers are never cached
+        if self.bypass.get() || length == 0 || (0x0400_0200..0x0410_01ff).contains(&address) {
+            return;
+        }
+
+        if let Some(cache) = cache {
+            let mut cache = cache.borrow_mut();
+            let line_size = cache.config.line_size as u32;
+            let first_line = address / line_size;
+            let last_line = address.saturating_add(length - 1) / line_size;
+
+            for line in first_line..=last_line {
+                let penalty = cache.access(line * line_size, write);
+                self.penalty.set(self.penalty.get() + penalty);
+            }
+        }
+    }
+}
+
+impl Display for CacheConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CacheConfigError::LineSize(size) => write!(f, "cache line size {size} is not a power of two"),
+            CacheConfigError::NoWays => write!(f, "cache needs at least one way per set"),
+            CacheConfigError::Size(size) => {
+                write!(f, "cache size {size} does not split into a power of two number of sets")
+            }
+            CacheConfigError::TooManyLines(lines) => write!(f, "cache has {lines} lines, more than can be stored"),
+        }
+    }
+}
@@ -1,7 +1,10 @@
+#[cfg(feature = "alloc")]
 use alloc::string::String;
+use core::fmt::Write;
 use crate::core::binary::ExtractedBinaryData;
-use crate::core::{Instruction, Interrupt};
+use crate::core::Instruction;
 use crate::core::instruction::DecodedInstruction;
+use crate::cpu::mmu::{Access, AddressTranslation};
 use crate::CPU;
 use crate::CPUResult;
 
@@ -10,30 +13,24 @@ pub trait Decoder {
     fn extract_instruction_infailible(&self, position: u32) -> ExtractedBinaryData;
     fn decode_instruction(&self, position: u32) -> CPUResult<DecodedInstruction>;
     fn read_instruction(&self, position: u32) -> CPUResult<Instruction>;
+    #[cfg(feature = "alloc")]
     fn read_instruction_string(&self, position: u32) -> String;
+    fn write_instruction_string(&self, position: u32, f: &mut dyn Write) -> core::fmt::Result;
 }
 
-impl Decoder for CPU {
+impl Decoder for CPU<'_> {
     fn extract_instruction(&self, position: u32) -> CPUResult<ExtractedBinaryData> {
-        let index = position as usize;
-        if index + 15 < self.memory.len() {
-            Ok(ExtractedBinaryData(&self.memory[index..index + 15]))
-        } else {
-            Err(Interrupt::IllegalMemory)
-        }
+        let bytes = self.read_virtual(position, Access::Execute)?;
+        Ok(ExtractedBinaryData::new(bytes))
     }
 
     fn extract_instruction_infailible(&self, position: u32) -> ExtractedBinaryData {
-        let index = position as usize;
-        if index + 15 < self.memory.len() {
-            ExtractedBinaryData(&self.memory[index..index + 15])
-        } else {
-            ExtractedBinaryData(&[0u8; 0])
-        }
+        self.inspect(|| self.extract_instruction(position))
+            .unwrap_or_else(|_| ExtractedBinaryData::empty())
     }
 
     fn decode_instruction(&self, position: u32) -> CPUResult<DecodedInstruction> {
-        let opcode = self.extract_instruction(position)?.0;
+        let opcode = self.extract_instruction(position)?.bytes;
 
         let instruction = u32::from_le_bytes([
             opcode[0], opcode[1],
@@ -76,11 +73,21 @@ impl Decoder for CPU {
         instruction.into_instruction(self)
     }
 
+    #[cfg(feature = "alloc")]
     fn read_instruction_string(&self, position: u32) -> String {
-        if let Ok(instruction) = self.decode_instruction(position) {
-            instruction.disassemble(self)
-        } else {
-            String::from("<invalid>")
-        }
+        let mut disassembled = String::new();
+        // Writing to a String never fails
+        let _ = self.write_instruction_string(position, &mut disassembled);
+        disassembled
+    }
+
+    fn write_instruction_string(&self, position: u32, f: &mut dyn Write) -> core::fmt::Result {
+        self.inspect(|| {
+            if let Ok(instruction) = self.decode_instruction(position) {
+                instruction.disassemble_into(self, f)
+            } else {
+                f.write_str("<invalid>")
+            }
+        })
     }
 }
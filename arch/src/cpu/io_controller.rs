@@ -1,19 +1,65 @@
-use alloc::boxed::Box;
+#[cfg(feature = "alloc")]
 use alloc::vec::Vec;
 use core::ops::Range;
 use crate::{BusDevice, CPUResult};
 use crate::core::Interrupt;
 use crate::devices::errors::BusResult;
+#[cfg(not(feature = "alloc"))]
+use crate::storage::DEVICE_CAPACITY;
+use crate::storage::Device;
+
+#[cfg(feature = "alloc")]
+type DeviceTable<'a> = Vec<(Range<u32>, Device<'a>)>;
+#[cfg(not(feature = "alloc"))]
+type DeviceTable<'a> = [Option<(Range<u32>, Device<'a>)>; DEVICE_CAPACITY];
 
 #[derive(Debug, Default)]
-pub struct IOController {
-    devices: Vec<(Range<u32>, Box<dyn BusDevice>)>
+pub struct IOController<'a> {
+    devices: DeviceTable<'a>
 }
 
-impl IOController {
+impl<'a> IOController<'a> {
+    #[cfg(feature = "alloc")]
+    fn entries(&mut self) -> impl Iterator<Item = &mut (Range<u32>, Device<'a>)> {
+        self.devices.iter_mut()
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    fn entries(&mut self) -> impl Iterator<Item = &mut (Range<u32>, Device<'a>)> {
+        self.devices.iter_mut().flatten()
+    }
+
+    // Address ranges and devices in the order they were attached
+    #[cfg(feature = "alloc")]
+    pub fn devices(&self) -> impl Iterator<Item = (&Range<u32>, &dyn BusDevice)> + use<'_, 'a> {
+        self.devices.iter().map(|(range, device)| (range, &**device))
+    }
+
+    #[cfg(not(feature = "alloc"))]
+    pub fn devices(&self) -> impl Iterator<Item = (&Range<u32>, &dyn BusDevice)> + use<'_, 'a> {
+        self.devices.iter().flatten().map(|(range, device)| (range, &**device))
+    }
+
+    // Never fails, but keeps the signature of the fixed-size table below
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::unnecessary_wraps)]
+    fn insert(&mut self, entry: (Range<u32>, Device<'a>)) -> CPUResult<()> {
+        self.devices.push(entry);
+        Ok(())
+    }
+
+    // A full table is reported like any other device that can't be attached
+    #[cfg(not(feature = "alloc"))]
+    fn insert(&mut self, entry: (Range<u32>, Device<'a>)) -> CPUResult<()> {
+        let slot = self.devices.iter_mut()
+            .find(|slot| slot.is_none())
+            .ok_or(Interrupt::Hardware)?;
+        *slot = Some(entry);
+        Ok(())
+    }
+
     pub fn find_device_port(&mut self, address: u32) -> BusResult<(&mut dyn BusDevice, u32)> {
-        let (range, device) = self.devices
-            .iter_mut()
+        let (range, device) = self.entries()
             .find(|(range, _)| range.contains(&address))
             .ok_or(Interrupt::IllegalMemory)?;
 
@@ -34,22 +80,27 @@ impl IOController {
     }
 
     pub fn tick(&mut self) -> CPUResult<()> {
-        for (_, device) in &mut self.devices {
+        for (_, device) in self.entries() {
             device.tick()?;
         }
 
         Ok(())
     }
 
-    pub fn add(&mut self, device: Box<dyn BusDevice>) -> CPUResult<()> {
+    // Devices whose ports run past the end of the address space or into another device's are
+    // refused, as is one starting inside another device
+    pub fn add(&mut self, device: Device<'a>) -> CPUResult<()> {
         let start = device.get_base_address();
-        let end = start + (device.get_port_count() * 4);
+        let end = device.get_port_count().checked_mul(4)
+            .and_then(|length| start.checked_add(length))
+            .ok_or(Interrupt::Hardware)?;
 
-        if self.find_device_port(start).is_ok() {
+        let overlaps = self.entries()
+            .any(|(range, _)| range.contains(&start) || (start < range.end && range.start < end));
+        if overlaps {
             return Err(Interrupt::Hardware);
         }
 
-        self.devices.push((start..end, device));
-        Ok(())
+        self.insert((start..end, device))
     }
 }
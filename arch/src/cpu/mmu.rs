@@ -0,0 +1,241 @@
+// Paged virtual memory. Paging is on while bit 0 of PTBR is set, the rest of PTBR above the low 12
+// bits is the physical address of the page directory. Writing PTBR always flushes the TLB.
+//
+// Translation takes two levels of 4 KiB pages. The top 10 bits of a virtual address pick one of
+// the 1024 words in the page directory, the next 10 one of the words in the page table that entry
+// points to, and the low 12 bits are the offset into the page. Entries at both levels look alike:
+//
+//     bit 0     present (0b001)
+//     bit 1     writable (0b010)
+//     bit 2     user (0b100)
+//     bits 12+  frame, the physical address of the table or page shifted right by 12
+//
+// A page is only writable or reachable from user code if both levels say so. Interrupt handlers,
+// which run with the interrupt flag set, are the supervisor and aren't held to the user bit.
+//
+// The last 16 translations are kept in a TLB, replacing the oldest one first. Page tables aren't
+// watched, guests have to write PTBR again after changing them. A missing page or an access the
+// entry doesn't allow raises a page fault and leaves the virtual address in PFAR.
+
+use core::cell::Cell;
+use crate::core::Interrupt;
+use crate::CPU;
+use crate::CPUResult;
+
+pub const PAGE_SIZE: u32 = 4096;
+pub const TLB_SIZE: usize = 16;
+
+const PAGE_PRESENT: u32 = 0b001;
+const PAGE_WRITABLE: u32 = 0b010;
+const PAGE_USER: u32 = 0b100;
+const PAGING_ENABLE: u32 = 0b001;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Access {
+    Read,
+    Write,
+    Execute
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TlbEntry {
+    pub page: u32,
+    pub frame: u32,
+    pub writable: bool,
+    pub user: bool
+}
+
+#[derive(Debug, Default)]
+pub struct Mmu {
+    page_table_base: u32,
+    fault_address: Cell<u32>,
+    tlb: [Cell<Option<TlbEntry>>; TLB_SIZE],
+    next_victim: Cell<usize>
+}
+
+impl Mmu {
+    #[must_use]
+    pub fn enabled(&self) -> bool {
+        self.page_table_base & PAGING_ENABLE == PAGING_ENABLE
+    }
+
+    #[must_use]
+    pub fn page_table_base(&self) -> u32 {
+        self.page_table_base
+    }
+
+    // Reloading the page table base always flushes the TLB, even if the value is unchanged
+    pub fn set_page_table_base(&mut self, value: u32) {
+        self.page_table_base = value;
+        self.flush();
+    }
+
+    #[must_use]
+    pub fn fault_address(&self) -> u32 {
+        self.fault_address.get()
+    }
+
+    pub fn set_fault_address(&self, address: u32) {
+        self.fault_address.set(address);
+    }
+
+    pub fn flush(&self) {
+        for entry in &self.tlb {
+            entry.set(None);
+        }
+    }
+
+    fn lookup(&self, page: u32) -> Option<TlbEntry> {
+        self.tlb.iter()
+            .filter_map(Cell::get)
+            .find(|entry| entry.page == page)
+    }
+
+    fn insert(&self, entry: TlbEntry) {
+        let victim = self.next_victim.get();
+        self.tlb[victim].set(Some(entry));
+        self.next_victim.set((victim + 1) % TLB_SIZE);
+    }
+}
+
+// Groups byte accesses into physically contiguous runs before they are reported to the caches
+#[derive(Default)]
+struct PhysicalRun {
+    start: u32,
+    length: u32
+}
+
+impl PhysicalRun {
+    fn push(&mut self, cpu: &CPU, access: Access, physical: u32) {
+        if self.length > 0 && self.start.wrapping_add(self.length) == physical {
+            self.length += 1;
+        } else {
+            self.finish(cpu, access);
+            self.start = physical;
+            self.length = 1;
+        }
+    }
+
+    fn finish(&mut self, cpu: &CPU, access: Access) {
+        if cpu.caches.enabled() {
+            cpu.caches.record(access, self.start, self.length);
+        }
+        self.length = 0;
+    }
+}
+
+pub trait AddressTranslation {
+    fn translate(&self, address: u32, access: Access) -> CPUResult<u32>;
+    fn read_virtual<const N: usize>(&self, address: u32, access: Access) -> CPUResult<[u8; N]>;
+    fn write_virtual(&mut self, address: u32, bytes: &[u8]) -> CPUResult<()>;
+}
+
+impl CPU<'_> {
+    fn read_physical_word(&self, address: u32) -> Option<u32> {
+        let index = address as usize;
+        let bytes = self.memory.get(index..index + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn walk_page_table(&self, page: u32) -> Option<TlbEntry> {
+        let directory_index = page >> 10;
+        let table_index = page & 0x3ff;
+
+        let directory_base = self.mmu.page_table_base & !(PAGE_SIZE - 1);
+        let directory_entry = self.read_physical_word(directory_base + directory_index * 4)?;
+        if directory_entry & PAGE_PRESENT == 0 {
+            return None;
+        }
+
+        let table_base = directory_entry & !(PAGE_SIZE - 1);
+        let table_entry = self.read_physical_word(table_base + table_index * 4)?;
+        if table_entry & PAGE_PRESENT == 0 {
+            return None;
+        }
+
+        // Permissions are the intersection of both levels
+        let flags = directory_entry & table_entry;
+        Some(TlbEntry {
+            page,
+            frame: table_entry >> 12,
+            writable: flags & PAGE_WRITABLE == PAGE_WRITABLE,
+            user: flags & PAGE_USER == PAGE_USER
+        })
+    }
+
+    // Host-side inspection (disassembly, crash reports) reads memory the way the guest would, but
+    // must leave neither cache statistics nor a fault address behind
+    pub fn inspect<T>(&self, f: impl FnOnce() -> T) -> T {
+        let fault_address = self.mmu.fault_address();
+        let result = self.caches.bypass(f);
+        self.mmu.set_fault_address(fault_address);
+        result
+    }
+
+    fn page_fault(&self, address: u32) -> Interrupt {
+        self.mmu.set_fault_address(address);
+        Interrupt::PageFault
+    }
+}
+
+impl AddressTranslation for CPU<'_> {
+    fn translate(&self, address: u32, access: Access) -> CPUResult<u32> {
+        if !self.mmu.enabled() {
+            return Ok(address);
+        }
+
+        let page = address >> 12;
+        let entry = if let Some(entry) = self.mmu.lookup(page) {
+            entry
+        } else {
+            let entry = self.walk_page_table(page)
+                .ok_or_else(|| self.page_fault(address))?;
+            self.mmu.insert(entry);
+            entry
+        };
+
+        // Interrupt handlers run in supervisor mode, everything else is user code
+        let supervisor = self.status_register.interrupt;
+        if (access == Access::Write && !entry.writable) || (!supervisor && !entry.user) {
+            return Err(self.page_fault(address));
+        }
+
+        Ok((entry.frame << 12) | (address & (PAGE_SIZE - 1)))
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn read_virtual<const N: usize>(&self, address: u32, access: Access) -> CPUResult<[u8; N]> {
+        let mut bytes = [0u8; N];
+        let mut run = PhysicalRun::default();
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            let physical = self.translate(address.wrapping_add(i as u32), access)?;
+            *byte = *self.memory.get(physical as usize).ok_or(Interrupt::IllegalMemory)?;
+            run.push(self, access, physical);
+        }
+
+        run.finish(self, access);
+        Ok(bytes)
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn write_virtual(&mut self, address: u32, bytes: &[u8]) -> CPUResult<()> {
+        // Check every byte before writing so that a faulting write leaves memory untouched
+        for i in 0..bytes.len() {
+            let physical = self.translate(address.wrapping_add(i as u32), Access::Write)?;
+            if physical as usize >= self.memory.len() {
+                return Err(Interrupt::IllegalMemory);
+            }
+        }
+
+        let mut run = PhysicalRun::default();
+        for (i, byte) in bytes.iter().enumerate() {
+            let physical = self.translate(address.wrapping_add(i as u32), Access::Write)?;
+            self.memory[physical as usize] = *byte;
+            run.push(self, Access::Write, physical);
+        }
+
+        run.finish(self, Access::Write);
+        Ok(())
+    }
+}
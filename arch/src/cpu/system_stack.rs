@@ -2,6 +2,7 @@ use crate::core::Interrupt;
 use crate::core::registers::StatusRegister;
 use crate::CPU;
 use crate::CPUResult;
+use crate::storage::SYSTEM_STACK_CAPACITY;
 
 pub trait SystemStack {
     fn system_stack_push_word(&mut self, value: u32) -> CPUResult<()>;
@@ -11,9 +12,9 @@ pub trait SystemStack {
     fn system_stack_pull_state(&mut self) -> CPUResult<(u32, StatusRegister)>;
 }
 
-impl SystemStack for CPU {
+impl SystemStack for CPU<'_> {
     fn system_stack_push_word(&mut self, value: u32) -> CPUResult<()> {
-        if self.system_stack.len() > 256 {
+        if self.system_stack.len() >= SYSTEM_STACK_CAPACITY {
             Err(Interrupt::StackOverflow)
         } else {
             self.system_stack.push(value);
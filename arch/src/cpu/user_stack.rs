@@ -1,5 +1,6 @@
 use crate::core::Interrupt;
 use crate::core::registers::StatusRegister;
+use crate::cpu::mmu::{Access, AddressTranslation};
 use crate::CPU;
 use crate::CPUResult;
 
@@ -11,14 +12,18 @@ pub trait UserStack {
     fn user_stack_pull_state(&mut self) -> CPUResult<(u32, StatusRegister)>;
 }
 
-impl UserStack for CPU {
+impl UserStack for CPU<'_> {
     fn user_stack_push_word(&mut self, value: u32) -> CPUResult<()> {
         if self.stack_pointer <= 0x0000_0004 {
             Err(Interrupt::StackOverflow)
         } else {
             let bytes = value.to_le_bytes();
-            self.memory[(self.stack_pointer as usize)..(self.stack_pointer as usize + 4)].copy_from_slice(&bytes);
-            self.stack_pointer += 4;
+            let stack_pointer = self.stack_pointer.checked_add(4).ok_or(Interrupt::StackOverflow)?;
+            self.write_virtual(self.stack_pointer, &bytes).map_err(|interrupt| match interrupt {
+                Interrupt::IllegalMemory => Interrupt::StackOverflow,
+                interrupt => interrupt
+            })?;
+            self.stack_pointer = stack_pointer;
             Ok(())
         }
     }
@@ -27,13 +32,13 @@ impl UserStack for CPU {
         if self.stack_pointer >= 0x1fff_fffb {
             Err(Interrupt::StackUnderflow)
         } else {
-            self.stack_pointer -= 4;
-            Ok(u32::from_le_bytes([
-                self.memory[self.stack_pointer as usize],
-                self.memory[(self.stack_pointer + 1) as usize],
-                self.memory[(self.stack_pointer + 2) as usize],
-                self.memory[(self.stack_pointer + 3) as usize]
-            ]))
+            let stack_pointer = self.stack_pointer.checked_sub(4).ok_or(Interrupt::StackUnderflow)?;
+            let bytes = self.read_virtual(stack_pointer, Access::Read).map_err(|interrupt| match interrupt {
+                Interrupt::IllegalMemory => Interrupt::StackUnderflow,
+                interrupt => interrupt
+            })?;
+            self.stack_pointer = stack_pointer;
+            Ok(u32::from_le_bytes(bytes))
         }
     }
 
@@ -6,6 +6,10 @@ pub mod logic;
 pub mod stack;
 pub mod comparison;
 pub mod advanced_flow;
+pub mod multiprocessing;
+pub mod conditional_move;
+pub mod trapping_arithmetic;
+pub mod loops;
 
 pub use advanced_flow::*;
 pub use arithmetic::*;
@@ -15,3 +19,7 @@ pub use counting::*;
 pub use logic::*;
 pub use stack::*;
 pub use comparison::*;
+pub use multiprocessing::*;
+pub use conditional_move::*;
+pub use trapping_arithmetic::*;
+pub use loops::*;
@@ -9,7 +9,7 @@ pub fn jnae(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 < val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -22,7 +22,7 @@ pub fn jae(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 >= val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -35,7 +35,7 @@ pub fn jna(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 <= val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -48,7 +48,7 @@ pub fn ja(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 > val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -62,7 +62,7 @@ pub fn jl(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 < val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -76,7 +76,7 @@ pub fn jge(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 >= val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -90,7 +90,7 @@ pub fn jle(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 <= val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -104,7 +104,7 @@ pub fn jg(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[2].get_address()?;
 
     if val1 > val2 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -117,7 +117,7 @@ pub fn jp(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[1].get_address()?;
 
     if value % 2 == 0 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
@@ -130,7 +130,7 @@ pub fn jnp(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[1].get_address()?;
 
     if value % 2 == 1 {
-        cpu.program_counter = position - 15;
+        cpu.program_counter = position.wrapping_sub(15);
         Ok(())
     } else {
         Ok(())
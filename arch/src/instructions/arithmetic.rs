@@ -15,8 +15,7 @@ pub fn add(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
     cpu.status_register.carry = sum.1;
     cpu.status_register.overflow = (number1_negative == number2_negative) && (sum_negative != number1_negative);
-    cpu.status_register.zero = sum.0 == 0;
-    cpu.status_register.negative = sum_negative;
+    cpu.status_register.set_result(sum.0);
     operands[0].write_word(cpu, sum.0)?;
 
     Ok(())
@@ -33,27 +32,23 @@ pub fn sub(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
     cpu.status_register.carry = diff.1;
     cpu.status_register.overflow = (number1_negative != number2_negative) && (diff_negative != number1_negative);
-    cpu.status_register.zero = diff.0 == 0;
-    cpu.status_register.negative = diff_negative;
+    cpu.status_register.set_result(diff.0);
     operands[0].write_word(cpu, diff.0)?;
 
     Ok(())
 }
 
+// Overflow is (by definition) for signed operations
+#[allow(clippy::cast_possible_wrap)]
 pub fn mul(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number1 = operands[1].read_word(cpu)?;
-    let number1_negative = number1 >> 31 == 1;
     let number2 = operands[2].read_word(cpu)?;
-    let number2_negative = number2 >> 31 == 1;
 
     let result = number1.overflowing_mul(number2);
-    let result_negative = result.0 >> 31 == 1;
 
     cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = ((number1_negative == number2_negative) && result_negative) ||
-        ((number1_negative != number2_negative) && !result_negative);
-    cpu.status_register.zero = result.0 == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.overflow = (number1 as i32).checked_mul(number2 as i32).is_none();
+    cpu.status_register.set_result(result.0);
     operands[0].write_word(cpu, result.0)?;
 
     Ok(())
@@ -65,14 +60,13 @@ pub fn div(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
     if number2 == 0 { return Err(Interrupt::DivideByZero); }
 
-    let result = number1.overflowing_div(number2);
-    let result_negative = result.0 >> 31 == 1;
+    // Unsigned division can neither carry nor overflow
+    let result = number1 / number2;
 
-    cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = number1 == 2_147_483_647 && number2 == 4_294_967_295;
-    cpu.status_register.zero = result.0 == 0;
-    cpu.status_register.negative = result_negative;
-    operands[0].write_word(cpu, result.0)?;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
+    operands[0].write_word(cpu, result)?;
 
     Ok(())
 }
@@ -84,10 +78,10 @@ pub fn r#mod(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     if number2 == 0 { return Err(Interrupt::DivideByZero); }
 
     let result = number1 % number2;
-    let result_negative = result >> 31 == 1;
 
-    cpu.status_register.zero = result == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -98,10 +92,10 @@ pub fn r#mod(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 pub fn sqrt(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[1].read_word(cpu)?;
     let result = libm::sqrt(f64::from(number)) as u32;
-    let result_negative = result >> 31 == 1;
 
-    cpu.status_register.zero = result == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -112,10 +106,10 @@ pub fn sqrt(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 pub fn cbrt(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[1].read_word(cpu)?;
     let result = libm::cbrt(f64::from(number)) as u32;
-    let result_negative = result >> 31 == 1;
 
-    cpu.status_register.zero = result == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -125,15 +119,12 @@ pub fn cbrt(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 #[allow(clippy::cast_possible_wrap)]
 pub fn sqre(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[1].read_word(cpu)?;
-    let number_abs = (number as i32).unsigned_abs();
 
     let result = number.overflowing_pow(2);
-    let result_negative = result.0 >> 31 == 1;
 
     cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = number_abs > 65535;
-    cpu.status_register.zero = result.0 == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.overflow = (number as i32).checked_pow(2).is_none();
+    cpu.status_register.set_result(result.0);
     operands[0].write_word(cpu, result.0)?;
 
     Ok(())
@@ -143,28 +134,26 @@ pub fn sqre(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 #[allow(clippy::cast_possible_wrap)]
 pub fn cube(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[1].read_word(cpu)?;
-    let number_abs = (number as i32).unsigned_abs();
 
     let result = number.overflowing_pow(3);
-    let result_negative = result.0 >> 31 == 1;
 
     cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = number_abs > 1625;
-    cpu.status_register.zero = result.0 == 0;
-    cpu.status_register.negative = result_negative;
+    cpu.status_register.overflow = (number as i32).checked_pow(3).is_none();
+    cpu.status_register.set_result(result.0);
     operands[0].write_word(cpu, result.0)?;
 
     Ok(())
 }
 
 pub fn min(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
-    let number1 = operands[0].read_word(cpu)?;
-    let number2 = operands[1].read_word(cpu)?;
+    let number1 = operands[1].read_word(cpu)?;
+    let number2 = operands[2].read_word(cpu)?;
 
     let result = number1.min(number2);
 
-    cpu.status_register.zero = result == 0;
-    cpu.status_register.negative = result >> 31 == 1;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -176,8 +165,9 @@ pub fn max(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
     let result = number1.max(number2);
 
-    cpu.status_register.zero = result == 0;
-    cpu.status_register.negative = result >> 31 == 1;
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -191,12 +181,12 @@ pub fn addc(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
     let sum_pre = number1.overflowing_add(number2);
     let sum = sum_pre.0.overflowing_add(u32::from(cpu.status_register.carry));
-    let sum_negative = sum_pre.0 >> 31 == 1;
+    let sum_negative = sum.0 >> 31 == 1;
 
-    cpu.status_register.carry = sum.1;
+    // At most one of the two additions can carry out
+    cpu.status_register.carry = sum_pre.1 || sum.1;
     cpu.status_register.overflow = (number1_negative == number2_negative) && (sum_negative != number1_negative);
-    cpu.status_register.zero = sum.0 == 0;
-    cpu.status_register.negative = sum_negative;
+    cpu.status_register.set_result(sum.0);
     operands[0].write_word(cpu, sum.0)?;
 
     Ok(())
@@ -208,14 +198,14 @@ pub fn subc(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let number2_negative = number2 >> 31 == 1;
 
+    // Carry holds the borrow of the previous sub/subc, the same way addc chains after add
     let diff_pre = number1.overflowing_sub(number2);
-    let diff = diff_pre.0.overflowing_sub(u32::from(!cpu.status_register.carry));
+    let diff = diff_pre.0.overflowing_sub(u32::from(cpu.status_register.carry));
     let diff_negative = diff.0 >> 31 == 1;
 
-    cpu.status_register.carry = diff.1;
+    cpu.status_register.carry = diff_pre.1 || diff.1;
     cpu.status_register.overflow = (number1_negative != number2_negative) && (diff_negative != number1_negative);
-    cpu.status_register.zero = diff.0 == 0;
-    cpu.status_register.negative = diff_negative;
+    cpu.status_register.set_result(diff.0);
     operands[0].write_word(cpu, diff.0)?;
 
     Ok(())
@@ -228,10 +218,14 @@ pub fn sar(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     // shift right on that (>> does ASR on i32, LSR on u32), and then convert it back to an 32-bit
     // word and update memory.
     // c.f. https://doc.rust-lang.org/stable/reference/expressions/operator-expr.html#arithmetic-and-logical-binary-operators
-    let word = operands[0].read_word(cpu)?;
-    let word = word as i32;
+    let initial = operands[0].read_word(cpu)?;
+    let word = initial as i32;
     let word = word >> 1;
     let word = word as u32;
+
+    cpu.status_register.carry = initial & 1 == 1;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(word);
     operands[0].write_word(cpu, word)
 }
 
@@ -242,9 +236,13 @@ pub fn sal(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     // shift left on that (<< does ASL on i32, LSR on u32), and then convert it back to an 32-bit
     // word and update memory.
     // c.f. https://doc.rust-lang.org/stable/reference/expressions/operator-expr.html#arithmetic-and-logical-binary-operators
-    let word = operands[0].read_word(cpu)?;
-    let word = word as i32;
+    let initial = operands[0].read_word(cpu)?;
+    let word = initial as i32;
     let word = word << 1;
     let word = word as u32;
+
+    cpu.status_register.carry = initial >> 31 == 1;
+    cpu.status_register.overflow = (initial ^ word) >> 31 == 1;
+    cpu.status_register.set_result(word);
     operands[0].write_word(cpu, word)
 }
@@ -0,0 +1,72 @@
+use crate::core::MemoryCell;
+use crate::core::Operand;
+use crate::CPU;
+use crate::InstructionResult;
+
+// Signed counterpart of cmp, flags follow the same layout so every cmov works after either
+#[allow(clippy::cast_possible_wrap)]
+pub fn cmps(operand: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let value1 = operand[0].read_word(cpu)? as i32;
+    let value2 = operand[1].read_word(cpu)? as i32;
+
+    cpu.status_register.zero = value1 == value2;
+    cpu.status_register.carry = value1 >= value2;
+    cpu.status_register.negative = value1 < value2;
+
+    Ok(())
+}
+
+fn cmov(operands: &mut [Operand; 3], cpu: &mut CPU, condition: bool) -> InstructionResult {
+    // The source is always read so faults don't depend on the flags
+    let value = operands[1].read_word(cpu)?;
+
+    if condition {
+        operands[0].write_word(cpu, value)?;
+    }
+
+    Ok(())
+}
+
+pub fn cmovz(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, cpu.status_register.zero)
+}
+
+pub fn cmovnz(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, !cpu.status_register.zero)
+}
+
+pub fn cmovc(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, cpu.status_register.carry)
+}
+
+pub fn cmovnc(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, !cpu.status_register.carry)
+}
+
+pub fn cmovo(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, cpu.status_register.overflow)
+}
+
+pub fn cmovno(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, !cpu.status_register.overflow)
+}
+
+pub fn cmovs(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, cpu.status_register.negative)
+}
+
+pub fn cmovns(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    cmov(operands, cpu, !cpu.status_register.negative)
+}
+
+// Less than or equal after cmp (unsigned) or cmps (signed)
+pub fn cmovle(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let condition = cpu.status_register.negative || cpu.status_register.zero;
+    cmov(operands, cpu, condition)
+}
+
+// Greater than after cmp (unsigned) or cmps (signed)
+pub fn cmovg(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let condition = cpu.status_register.carry && !cpu.status_register.zero;
+    cmov(operands, cpu, condition)
+}
@@ -8,13 +8,13 @@ use crate::InstructionResult;
 pub fn jmp(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[0].get_address()?;
     cpu.system_stack_save_state()?;
-    cpu.program_counter = position - 15;
+    cpu.program_counter = position.wrapping_sub(15);
     Ok(())
 }
 
 pub fn jmpl(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let position = operands[0].get_address()?;
-    cpu.program_counter = position - 15;
+    cpu.program_counter = position.wrapping_sub(15);
     Ok(())
 }
 
@@ -97,7 +97,7 @@ pub fn iret(operands: &[Operand; 3], cpu: &mut CPU) -> InstructionResult {
     cpu.status_register.interrupt = false;
     cpu.status_register.double_fault = false;
     ret(operands, cpu)?;
-    cpu.program_counter -= 15;
+    cpu.program_counter = cpu.program_counter.wrapping_sub(15);
     Ok(())
 }
 
@@ -126,8 +126,8 @@ pub fn js(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
 pub fn jns(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     if cpu.status_register.negative {
-        jmpl(operands, cpu)
-    } else {
         Ok(())
+    } else {
+        jmpl(operands, cpu)
     }
 }
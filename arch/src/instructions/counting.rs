@@ -8,7 +8,8 @@ pub fn inc(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let result = initial.overflowing_add(1);
 
     cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = initial == 2_147_483_646;
+    cpu.status_register.overflow = initial == 0x7fff_ffff;
+    cpu.status_register.set_result(result.0);
     operands[0].write_word(cpu, result.0)?;
 
     Ok(())
@@ -19,8 +20,8 @@ pub fn dec(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let result = initial.overflowing_sub(1);
 
     cpu.status_register.carry = result.1;
-    cpu.status_register.overflow = initial == 2_147_483_647;
-    cpu.status_register.zero = result.0 == 0;
+    cpu.status_register.overflow = initial == 0x8000_0000;
+    cpu.status_register.set_result(result.0);
     operands[0].write_word(cpu, result.0)?;
 
     Ok(())
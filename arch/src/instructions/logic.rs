@@ -3,12 +3,18 @@ use crate::core::Operand;
 use crate::CPU;
 use crate::InstructionResult;
 
+fn set_logic_flags(cpu: &mut CPU, result: u32) {
+    cpu.status_register.carry = false;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
+}
+
 pub fn and(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number1 = operands[1].read_word(cpu)?;
     let number2 = operands[2].read_word(cpu)?;
     let result = number1 & number2;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -19,7 +25,7 @@ pub fn or(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let result = number1 | number2;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -30,7 +36,7 @@ pub fn xor(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let result = number1 ^ number2;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -41,7 +47,7 @@ pub fn nor(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let result = !(number1 | number2);
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -52,7 +58,7 @@ pub fn nand(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let result = !(number1 & number2);
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -63,7 +69,7 @@ pub fn imp(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number2 = operands[2].read_word(cpu)?;
     let result = (!number1) | number2;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -71,7 +77,7 @@ pub fn imp(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
 
 pub fn not(_operands: &[Operand; 3], cpu: &mut CPU) -> InstructionResult {
     cpu.registers.r0 = !cpu.registers.r0;
-    cpu.status_register.zero = cpu.registers.r0 == 0;
+    set_logic_flags(cpu, cpu.registers.r0);
     Ok(())
 }
 
@@ -79,7 +85,8 @@ pub fn shl(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[0].read_word(cpu)?;
     let result = number << 1;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
+    cpu.status_register.carry = number >> 31 == 1;
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -89,7 +96,8 @@ pub fn shr(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[0].read_word(cpu)?;
     let result = number >> 1;
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
+    cpu.status_register.carry = number & 1 == 1;
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -99,7 +107,8 @@ pub fn rol(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[0].read_word(cpu)?;
     let result = number.rotate_left(1);
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
+    cpu.status_register.carry = number >> 31 == 1;
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -109,7 +118,8 @@ pub fn ror(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
     let number = operands[0].read_word(cpu)?;
     let result = number.rotate_right(1);
 
-    cpu.status_register.zero = result == 0;
+    set_logic_flags(cpu, result);
+    cpu.status_register.carry = number & 1 == 1;
     operands[0].write_word(cpu, result)?;
 
     Ok(())
@@ -0,0 +1,18 @@
+use crate::core::MemoryCell;
+use crate::core::Operand;
+use crate::instructions::dec;
+use crate::CPU;
+use crate::InstructionResult;
+
+// Flags are exactly the ones dec sets on the counter
+pub fn r#loop(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let position = operands[1].get_address()?;
+    dec(operands, cpu)?;
+
+    if cpu.status_register.zero {
+        Ok(())
+    } else {
+        cpu.program_counter = position.wrapping_sub(15);
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use crate::core::{Interrupt, MemoryCell};
+use crate::core::Operand;
+use crate::CPU;
+use crate::InstructionResult;
+
+// Cores never switch in the middle of an instruction, so the read-modify-write
+// sequences below are atomic with respect to every other core on the machine.
+
+pub fn cas(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let current = operands[0].read_word(cpu)?;
+    let expected = operands[1].read_word(cpu)?;
+    let new = operands[2].read_word(cpu)?;
+
+    if current == expected {
+        operands[0].write_word(cpu, new)?;
+        cpu.status_register.zero = true;
+    } else {
+        // Hand the value we lost against back to the caller when it can hold it
+        if let Operand::Register(..) = operands[1] {
+            operands[1].write_word(cpu, current)?;
+        }
+        cpu.status_register.zero = false;
+    }
+
+    Ok(())
+}
+
+pub fn xadd(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let current = operands[1].read_word(cpu)?;
+    let current_negative = current >> 31 == 1;
+    let value = operands[2].read_word(cpu)?;
+    let value_negative = value >> 31 == 1;
+
+    let sum = current.overflowing_add(value);
+    let sum_negative = sum.0 >> 31 == 1;
+
+    operands[1].write_word(cpu, sum.0)?;
+    operands[0].write_word(cpu, current)?;
+
+    // Flags describe the addition, not the old value handed back
+    cpu.status_register.carry = sum.1;
+    cpu.status_register.overflow = (current_negative == value_negative) && (sum_negative != current_negative);
+    cpu.status_register.set_result(sum.0);
+    Ok(())
+}
+
+pub fn ipi(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let target = operands[0].read_word(cpu)?;
+
+    if target >= cpu.core_count {
+        return Err(Interrupt::IllegalMemory);
+    }
+
+    cpu.ipi_requests |= 1 << target;
+    Ok(())
+}
@@ -0,0 +1,51 @@
+use crate::core::Interrupt;
+use crate::core::MemoryCell;
+use crate::core::Operand;
+use crate::CPU;
+use crate::InstructionResult;
+
+// Same as add/sub/mul, except that signed overflow raises an interrupt before anything is written
+// back, so the fault points at the offending instruction with the machine state left untouched.
+
+fn store(operands: &mut [Operand; 3], cpu: &mut CPU, result: u32, carry: bool) -> InstructionResult {
+    cpu.status_register.carry = carry;
+    cpu.status_register.overflow = false;
+    cpu.status_register.set_result(result);
+    operands[0].write_word(cpu, result)
+}
+
+pub fn addv(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let number1 = operands[1].read_word(cpu)?;
+    let number2 = operands[2].read_word(cpu)?;
+
+    if number1.cast_signed().checked_add(number2.cast_signed()).is_none() {
+        return Err(Interrupt::Overflow);
+    }
+
+    let sum = number1.overflowing_add(number2);
+    store(operands, cpu, sum.0, sum.1)
+}
+
+pub fn subv(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let number1 = operands[1].read_word(cpu)?;
+    let number2 = operands[2].read_word(cpu)?;
+
+    if number1.cast_signed().checked_sub(number2.cast_signed()).is_none() {
+        return Err(Interrupt::Overflow);
+    }
+
+    let diff = number1.overflowing_sub(number2);
+    store(operands, cpu, diff.0, diff.1)
+}
+
+pub fn mulv(operands: &mut [Operand; 3], cpu: &mut CPU) -> InstructionResult {
+    let number1 = operands[1].read_word(cpu)?;
+    let number2 = operands[2].read_word(cpu)?;
+
+    if number1.cast_signed().checked_mul(number2.cast_signed()).is_none() {
+        return Err(Interrupt::Overflow);
+    }
+
+    let product = number1.overflowing_mul(number2);
+    store(operands, cpu, product.0, product.1)
+}
@@ -1,23 +1,23 @@
 #![no_std]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
-pub const CPU_SPECIFICATION: StaticSpecification = StaticSpecification {
-    name: b"Floofi(TM) Vixen(TM) Kanto",
-    id: 0x0003,
-    microarchitecture: b"VXAv2.1",
-    microarchitecture_name: b"Goupix",
-    data_width: 32,
-    address_width: 32,
-    microcode: 0x0006
-};
+// The default model's specification, kept for code written before there was more than one model
+pub const CPU_SPECIFICATION: StaticSpecification = models::DEFAULT_MODEL.specification;
 
 pub mod core;
 pub mod cpu;
 pub mod instructions;
 pub mod devices;
 pub mod memory_types;
+pub mod models;
+pub mod storage;
+#[cfg(feature = "alloc")]
+pub mod machine;
 
 pub use cpu::CPU;
+#[cfg(feature = "alloc")]
+pub use machine::Machine;
 pub use memory_types::*;
 pub use devices::BusDevice;
 
@@ -0,0 +1,191 @@
+use alloc::vec::Vec;
+use core::mem;
+use crate::core::{CpuModel, Interrupt, Registers};
+use crate::core::registers::StatusRegister;
+use crate::cpu::{Caches, LoadSegment, Mmu, DEFAULT_ENTRY_POINT};
+use crate::{CPUResult, CPU};
+
+// IPI requests are a 32-bit mask, one bit per core
+pub const MAX_CORES: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheduling {
+    // Each core runs `quantum` instructions in turn, in core ID order
+    RoundRobin { quantum: u32 },
+    // A core is picked after every instruction from a seeded generator, still reproducible
+    Random { seed: u64 }
+}
+
+// Architectural state private to a core while another one is running
+#[derive(Debug)]
+struct CoreState {
+    registers: Registers,
+    stack_pointer: u32,
+    program_counter: u32,
+    status_register: StatusRegister,
+    system_stack: Vec<u32>,
+    mmu: Mmu,
+    caches: Caches,
+    cycles: u64,
+    core_id: u32
+}
+
+#[derive(Debug)]
+pub struct Machine<'a> {
+    // Shared memory and device bus, plus the state of the core currently running
+    pub cpu: CPU<'a>,
+    cores: Vec<CoreState>,
+    scheduling: Scheduling,
+    turn: u32,
+    remaining: u32,
+    seed: u64
+}
+
+impl CoreState {
+    fn new(core_id: u32) -> Self {
+        Self {
+            registers: Registers::default(),
+            stack_pointer: 0x0000_0000,
+            program_counter: 0x0000_0200,
+            status_register: StatusRegister::default(),
+            system_stack: Vec::new(),
+            mmu: Mmu::default(),
+            caches: Caches::default(),
+            cycles: 0,
+            core_id
+        }
+    }
+
+    fn swap(&mut self, cpu: &mut CPU) {
+        mem::swap(&mut self.registers, &mut cpu.registers);
+        mem::swap(&mut self.stack_pointer, &mut cpu.stack_pointer);
+        mem::swap(&mut self.program_counter, &mut cpu.program_counter);
+        mem::swap(&mut self.status_register, &mut cpu.status_register);
+        mem::swap(&mut self.system_stack, &mut cpu.system_stack);
+        mem::swap(&mut self.mmu, &mut cpu.mmu);
+        mem::swap(&mut self.caches, &mut cpu.caches);
+        mem::swap(&mut self.cycles, &mut cpu.cycles);
+        mem::swap(&mut self.core_id, &mut cpu.core_id);
+    }
+}
+
+impl Machine<'_> {
+    /// # Panics
+    ///
+    /// Panics if `core_count` is zero or above [`MAX_CORES`].
+    #[must_use]
+    pub fn new(memory_size: usize, core_count: u32, model: &'static CpuModel) -> Self {
+        assert!((1..=MAX_CORES).contains(&core_count), "a machine has between 1 and {MAX_CORES} cores");
+
+        let mut cpu = CPU::new_with_model(memory_size, model);
+        cpu.core_count = core_count;
+
+        // The slot of the active core only ever holds stale state
+        let cores = (0..core_count).map(CoreState::new).collect();
+
+        Self {
+            cpu,
+            cores,
+            scheduling: Scheduling::RoundRobin { quantum: 1 },
+            turn: core_count - 1,
+            remaining: 0,
+            seed: 0
+        }
+    }
+
+    pub fn set_scheduling(&mut self, scheduling: Scheduling) {
+        self.scheduling = scheduling;
+        self.restart_schedule();
+        if let Scheduling::Random { seed } = scheduling {
+            // Xorshift gets stuck on zero
+            self.seed = seed.max(1);
+        }
+    }
+
+    #[must_use]
+    pub fn core_count(&self) -> u32 {
+        self.cpu.core_count
+    }
+
+    #[must_use]
+    pub fn active_core(&self) -> u32 {
+        self.cpu.core_id
+    }
+
+    // Every core starts at the beginning of the ROM and tells itself apart using cid
+    pub fn load_rom(&mut self, rom: &[u8]) -> CPUResult<()> {
+        let segment = LoadSegment { address: DEFAULT_ENTRY_POINT, data: rom };
+        self.load_segments(&[segment], DEFAULT_ENTRY_POINT)
+    }
+
+    // Every core starts at the same entry point
+    pub fn load_segments(&mut self, segments: &[LoadSegment], entry_point: u32) -> CPUResult<()> {
+        self.switch_to(0);
+        self.cpu.load_segments(segments, entry_point)?;
+
+        for core in 1..self.core_count() {
+            self.switch_to(core);
+            self.cpu.program_counter = entry_point;
+            self.cpu.reset_stacks()?;
+        }
+
+        self.switch_to(0);
+        self.restart_schedule();
+        Ok(())
+    }
+
+    /// Gives access to the full CPU view of a given core
+    ///
+    /// # Panics
+    ///
+    /// Panics if `core` does not exist on this machine.
+    pub fn with_core<T>(&mut self, core: u32, f: impl FnOnce(&mut CPU) -> T) -> T {
+        self.switch_to(core);
+        f(&mut self.cpu)
+    }
+
+    // Runs a single instruction on whichever core is next, reporting unhandled interrupts with their core
+    pub fn step(&mut self) -> Result<(), (u32, Interrupt)> {
+        let core = self.next_core();
+        self.switch_to(core);
+
+        self.cpu.tick().map_err(|interrupt| (core, interrupt))?;
+        self.cpu.program_counter = self.cpu.program_counter.wrapping_add(15);
+        Ok(())
+    }
+
+    // The next step always starts with core 0
+    fn restart_schedule(&mut self) {
+        self.turn = self.core_count() - 1;
+        self.remaining = 0;
+    }
+
+    // Host-side switches through with_core don't affect the order cores are run in
+    #[allow(clippy::cast_possible_truncation)]
+    fn next_core(&mut self) -> u32 {
+        match self.scheduling {
+            Scheduling::RoundRobin { quantum } => {
+                if self.remaining == 0 {
+                    self.remaining = quantum.max(1);
+                    self.turn = (self.turn + 1) % self.core_count();
+                }
+                self.remaining -= 1;
+                self.turn
+            },
+            Scheduling::Random { .. } => {
+                self.seed ^= self.seed << 13;
+                self.seed ^= self.seed >> 7;
+                self.seed ^= self.seed << 17;
+                (self.seed % u64::from(self.core_count())) as u32
+            }
+        }
+    }
+
+    fn switch_to(&mut self, core: u32) {
+        let current = self.cpu.core_id as usize;
+        if current != core as usize {
+            self.cores[current].swap(&mut self.cpu);
+            self.cores[core as usize].swap(&mut self.cpu);
+        }
+    }
+}
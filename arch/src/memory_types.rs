@@ -1,4 +1,6 @@
 pub const BASE_SYSTEM_SIZE: usize = 72_352_256;
+// Bus devices are mapped from here, between ROM and the interrupt vectors at the start of RAM
+pub const DEVICE_SPACE_START: usize = 0x0400_0200;
 pub const MEMORY_NONE: usize = BASE_SYSTEM_SIZE;
 pub const MEMORY_1M: usize = BASE_SYSTEM_SIZE + 1_048_576;
 pub const MEMORY_2M: usize = BASE_SYSTEM_SIZE + 2_097_152;
@@ -18,3 +20,20 @@ pub const MEMORY_1G: usize = BASE_SYSTEM_SIZE + 1_073_741_824;
 pub const MEMORY_2G: usize = BASE_SYSTEM_SIZE + 2_147_483_648;
 pub const MEMORY_3G: usize = BASE_SYSTEM_SIZE + 3_221_225_472;
 pub const MEMORY_MAX: usize = 4_294_967_295 - BASE_SYSTEM_SIZE;
+
+pub const MEMORY_PRESETS: &[(&str, usize)] = &[
+    ("NONE", MEMORY_NONE), ("1M", MEMORY_1M), ("2M", MEMORY_2M), ("4M", MEMORY_4M), ("8M", MEMORY_8M),
+    ("16M", MEMORY_16M), ("32M", MEMORY_32M), ("64M", MEMORY_64M), ("96M", MEMORY_96M),
+    ("128M", MEMORY_128M), ("192M", MEMORY_192M), ("256M", MEMORY_256M), ("384M", MEMORY_384M),
+    ("512M", MEMORY_512M), ("768M", MEMORY_768M), ("1G", MEMORY_1G), ("2G", MEMORY_2G), ("3G", MEMORY_3G),
+    ("MAX", MEMORY_MAX)
+];
+
+// Accepts both the bare preset name and the constant name, e.g. `64M` or `MEMORY_64M`
+#[must_use]
+pub fn find_memory_preset(name: &str) -> Option<usize> {
+    let name = name.strip_prefix("MEMORY_").or_else(|| name.strip_prefix("memory_")).unwrap_or(name);
+    MEMORY_PRESETS.iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+        .map(|(_, size)| *size)
+}
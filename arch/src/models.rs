@@ -0,0 +1,77 @@
+use crate::core::model::{CpuModel, Extension, ExtensionSet};
+use crate::core::StaticSpecification;
+
+const BASE_EXTENSIONS: ExtensionSet = ExtensionSet::empty()
+    .with(Extension::Arithmetic)
+    .with(Extension::Logic)
+    .with(Extension::Counting)
+    .with(Extension::Comparison)
+    .with(Extension::DataMovement)
+    .with(Extension::ControlFlow)
+    .with(Extension::Stack);
+
+pub const PALLET: CpuModel = CpuModel {
+    short_name: "pallet",
+    specification: StaticSpecification {
+        name: b"Floofi(TM) Vixen(TM) Pallet",
+        id: 0x0002,
+        microarchitecture: b"VXAv2.0",
+        microarchitecture_name: b"Goupix",
+        data_width: 32,
+        address_width: 32,
+        microcode: 0x0005
+    },
+    extensions: BASE_EXTENSIONS
+};
+
+pub const KANTO: CpuModel = CpuModel {
+    short_name: "kanto",
+    specification: StaticSpecification {
+        name: b"Floofi(TM) Vixen(TM) Kanto",
+        id: 0x0003,
+        microarchitecture: b"VXAv2.1",
+        microarchitecture_name: b"Goupix",
+        data_width: 32,
+        address_width: 32,
+        microcode: 0x0006
+    },
+    extensions: BASE_EXTENSIONS
+        .with(Extension::AdvancedFlow)
+};
+
+pub const JOHTO: CpuModel = CpuModel {
+    short_name: "johto",
+    specification: StaticSpecification {
+        name: b"Floofi(TM) Vixen(TM) Johto",
+        id: 0x0004,
+        microarchitecture: b"VXAv2.2",
+        microarchitecture_name: b"Goupix",
+        data_width: 32,
+        address_width: 32,
+        microcode: 0x0007
+    },
+    extensions: BASE_EXTENSIONS
+        .with(Extension::AdvancedFlow)
+        .with(Extension::Multiprocessing)
+        .with(Extension::ConditionalMove)
+        .with(Extension::TrappingArithmetic)
+        .with(Extension::Loop)
+};
+
+pub const DEFAULT_MODEL: &CpuModel = &KANTO;
+
+pub const MODELS: &[&CpuModel] = &[&PALLET, &KANTO, &JOHTO];
+
+#[must_use]
+pub fn find_model(short_name: &str) -> Option<&'static CpuModel> {
+    MODELS.iter()
+        .copied()
+        .find(|model| model.short_name.eq_ignore_ascii_case(short_name))
+}
+
+#[must_use]
+pub fn find_model_by_id(id: u32) -> Option<&'static CpuModel> {
+    MODELS.iter()
+        .copied()
+        .find(|model| model.specification.id == id)
+}
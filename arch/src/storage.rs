@@ -0,0 +1,95 @@
+// Backing storage for the CPU. With the `alloc` feature, memory and tables live on the heap and grow
+// as needed. Without it, memory and devices are borrowed from the caller for as long as the CPU
+// lives and tables are fixed-capacity arrays, so the core runs on targets with no allocator at all.
+// Either way devices only need to outlive the CPU, not the whole program.
+
+use core::ops::{Deref, DerefMut};
+use crate::BusDevice;
+
+// Words on the system stack, pushing past this raises a stack overflow
+pub const SYSTEM_STACK_CAPACITY: usize = 257;
+// Only used without `alloc`, the heap-backed tables have no fixed limit
+pub const DEVICE_CAPACITY: usize = 8;
+pub const CACHE_LINE_CAPACITY: usize = 128;
+
+#[cfg(feature = "alloc")]
+pub type Memory<'a> = alloc::boxed::Box<[u8]>;
+#[cfg(not(feature = "alloc"))]
+pub type Memory<'a> = &'a mut [u8];
+
+#[cfg(feature = "alloc")]
+pub type Device<'a> = alloc::boxed::Box<dyn BusDevice + 'a>;
+#[cfg(not(feature = "alloc"))]
+pub type Device<'a> = &'a mut (dyn BusDevice + 'a);
+
+#[cfg(feature = "alloc")]
+pub type SystemStackWords = alloc::vec::Vec<u32>;
+#[cfg(not(feature = "alloc"))]
+pub type SystemStackWords = FixedVec<u32, SYSTEM_STACK_CAPACITY>;
+
+// A vector with its items stored inline, for plain data with a known upper bound
+#[derive(Debug, Clone)]
+pub struct FixedVec<T: Copy + Default, const N: usize> {
+    items: [T; N],
+    len: usize
+}
+
+impl<T: Copy + Default, const N: usize> FixedVec<T, N> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            items: [T::default(); N],
+            len: 0
+        }
+    }
+
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// # Panics
+    /// Panics when the vector is full, callers check their limits first.
+    pub fn push(&mut self, value: T) {
+        assert!(self.len < N, "FixedVec capacity of {N} exceeded");
+        self.items[self.len] = value;
+        self.len += 1;
+    }
+
+    /// # Panics
+    /// Panics when the items don't fit, callers check their limits first.
+    pub fn extend_from_slice(&mut self, values: &[T]) {
+        assert!(self.len + values.len() <= N, "FixedVec capacity of {N} exceeded");
+        self.items[self.len..self.len + values.len()].copy_from_slice(values);
+        self.len += values.len();
+    }
+
+    pub fn pop(&mut self) -> Option<T> {
+        self.len = self.len.checked_sub(1)?;
+        Some(self.items[self.len])
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for FixedVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Deref for FixedVec<T, N> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        &self.items[..self.len]
+    }
+}
+
+impl<T: Copy + Default, const N: usize> DerefMut for FixedVec<T, N> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        &mut self.items[..self.len]
+    }
+}
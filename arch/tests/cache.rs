@@ -0,0 +1,160 @@
+// Checks the cache model counts what it should: hits, misses, evictions and the writes reaching
+// memory under each write policy, and nothing at all while the host inspects memory.
+
+use vixen::cpu::mmu::Access;
+use vixen::cpu::{Cache, CacheConfig, CacheConfigError, Caches, WritePolicy};
+
+const PENALTY: u64 = 10;
+
+// Two sets of two 16 byte lines, addresses 0x40 apart share a set
+fn small(write_policy: WritePolicy) -> CacheConfig {
+    CacheConfig {
+        size: 64,
+        line_size: 16,
+        associativity: 2,
+        write_policy,
+        miss_penalty: PENALTY
+    }
+}
+
+#[test]
+fn counts_hits_and_misses() {
+    let mut cache = Cache::new(small(WritePolicy::WriteBack)).unwrap();
+
+    assert_eq!(cache.access(0x100, false), PENALTY);
+    assert_eq!(cache.access(0x104, false), 0);
+    assert_eq!(cache.access(0x10f, false), 0);
+    assert_eq!(cache.access(0x110, false), PENALTY);
+
+    let statistics = cache.statistics();
+    assert_eq!((statistics.hits, statistics.misses), (2, 2));
+    assert_eq!(statistics.accesses(), 4);
+    assert!((statistics.hit_rate() - 0.5).abs() < f64::EPSILON);
+
+    cache.reset_statistics();
+    assert_eq!(cache.statistics().accesses(), 0);
+}
+
+#[test]
+fn evicts_least_recently_used() {
+    let mut cache = Cache::new(small(WritePolicy::WriteBack)).unwrap();
+
+    cache.access(0x000, false);
+    cache.access(0x040, false);
+    cache.access(0x000, false);
+    // The set is full, 0x40 was used longest ago
+    assert_eq!(cache.access(0x080, false), PENALTY);
+
+    assert_eq!(cache.access(0x000, false), 0);
+    assert_eq!(cache.access(0x040, false), PENALTY);
+    assert_eq!(cache.statistics().misses, 4);
+}
+
+#[test]
+fn write_back_writes_dirty_lines_on_eviction() {
+    let mut cache = Cache::new(small(WritePolicy::WriteBack)).unwrap();
+
+    // A write miss allocates the line, later writes hit it
+    assert_eq!(cache.access(0x000, true), PENALTY);
+    assert_eq!(cache.access(0x000, true), 0);
+    assert_eq!(cache.statistics().memory_writes, 0);
+
+    cache.access(0x040, false);
+    // Evicting the dirty line costs a second penalty for the write back
+    assert_eq!(cache.access(0x080, false), 2 * PENALTY);
+
+    let statistics = cache.statistics();
+    assert_eq!((statistics.writebacks, statistics.memory_writes), (1, 1));
+
+    // Clean lines are dropped for free
+    assert_eq!(cache.access(0x0c0, false), PENALTY);
+    assert_eq!(cache.statistics().writebacks, 1);
+}
+
+#[test]
+fn write_through_writes_every_store() {
+    let mut cache = Cache::new(small(WritePolicy::WriteThrough)).unwrap();
+
+    // A write miss doesn't allocate, so the read after it misses too
+    assert_eq!(cache.access(0x000, true), PENALTY);
+    assert_eq!(cache.access(0x000, false), PENALTY);
+    assert_eq!(cache.access(0x000, true), 0);
+
+    cache.access(0x040, false);
+    assert_eq!(cache.access(0x080, false), PENALTY);
+
+    let statistics = cache.statistics();
+    assert_eq!((statistics.hits, statistics.misses), (1, 4));
+    assert_eq!((statistics.writebacks, statistics.memory_writes), (0, 2));
+}
+
+#[test]
+fn invalidate_keeps_statistics() {
+    let mut cache = Cache::new(small(WritePolicy::WriteBack)).unwrap();
+    cache.access(0x000, true);
+    cache.invalidate();
+
+    assert_eq!(cache.access(0x000, false), PENALTY);
+    let statistics = cache.statistics();
+    assert_eq!((statistics.misses, statistics.writebacks), (2, 0));
+}
+
+#[test]
+fn records_ranges_by_line() {
+    let mut caches = Caches::default();
+    caches.set_instruction_cache(Some(small(WritePolicy::WriteBack))).unwrap();
+    caches.set_data_cache(Some(small(WritePolicy::WriteBack))).unwrap();
+    assert!(caches.enabled());
+
+    // 15 bytes across a line boundary touch two lines
+    caches.record(Access::Execute, 0x20a, 15);
+    assert_eq!(caches.instruction_statistics().unwrap().misses, 2);
+    assert_eq!(caches.data_statistics().unwrap().accesses(), 0);
+    assert_eq!(caches.take_penalty(), 2 * PENALTY);
+    assert_eq!(caches.take_penalty(), 0);
+
+    caches.record(Access::Read, 0x300, 4);
+    caches.record(Access::Write, 0x300, 4);
+    assert_eq!(caches.data_statistics().unwrap().hits, 1);
+
+    // Device registers are never cached
+    caches.record(Access::Read, 0x0400_0200, 4);
+    assert_eq!(caches.data_statistics().unwrap().accesses(), 2);
+}
+
+#[test]
+fn bypass_leaves_statistics_alone() {
+    let mut caches = Caches::default();
+    caches.set_data_cache(Some(small(WritePolicy::WriteBack))).unwrap();
+
+    let result = caches.bypass(|| {
+        caches.record(Access::Read, 0x300, 4);
+        caches.record(Access::Write, 0x400, 4);
+        7
+    });
+    assert_eq!(result, 7);
+    assert_eq!(caches.data_statistics().unwrap().accesses(), 0);
+    assert_eq!(caches.take_penalty(), 0);
+
+    // Only for the duration of the call
+    caches.record(Access::Read, 0x300, 4);
+    assert_eq!(caches.data_statistics().unwrap().misses, 1);
+}
+
+#[test]
+fn rejects_bad_configurations() {
+    let config = small(WritePolicy::WriteBack);
+
+    assert_eq!(Cache::new(CacheConfig { line_size: 24, ..config }).unwrap_err(), CacheConfigError::LineSize(24));
+    assert_eq!(Cache::new(CacheConfig { line_size: 0, ..config }).unwrap_err(), CacheConfigError::LineSize(0));
+    assert_eq!(Cache::new(CacheConfig { associativity: 0, ..config }).unwrap_err(), CacheConfigError::NoWays);
+    assert_eq!(Cache::new(CacheConfig { size: 100, ..config }).unwrap_err(), CacheConfigError::Size(100));
+    // Three sets
+    assert_eq!(Cache::new(CacheConfig { size: 96, ..config }).unwrap_err(), CacheConfigError::Size(96));
+    assert_eq!(Cache::new(CacheConfig { size: 0, ..config }).unwrap_err(), CacheConfigError::Size(0));
+    assert!(Cache::new(CacheConfig::default()).is_ok());
+
+    let mut caches = Caches::default();
+    assert!(caches.set_data_cache(Some(CacheConfig { associativity: 0, ..config })).is_err());
+    assert!(!caches.enabled());
+}
@@ -0,0 +1,56 @@
+// Shared by the integration tests: operands for hand-assembled programs and running them the way
+// the host does. Not every test uses every helper.
+#![allow(dead_code)]
+
+use vixen::core::instruction::{Addressing, Operation};
+use vixen::core::Interrupt;
+use vixen::CPU;
+
+pub const IMMEDIATE: Addressing = Addressing::Immediate;
+pub const DIRECT: Addressing = Addressing::Direct;
+pub const INDIRECT: Addressing = Addressing::Indirect;
+pub const ABSOLUTE: Addressing = Addressing::Absolute;
+pub const IMPLIED: Addressing = Addressing::Implied;
+
+pub const NONE: (Addressing, u32) = (IMPLIED, 0);
+
+pub const R0: u32 = 0x0001;
+pub const R1: u32 = 0x0011;
+pub const R2: u32 = 0x0012;
+
+// Appends the 15 bytes the decoder reads back as `operation` with these operands
+pub fn emit(rom: &mut Vec<u8>, operation: Operation, operands: [(Addressing, u32); 3]) {
+    let modes = operands.iter().enumerate()
+        .fold(0, |modes, (index, (mode, _))| modes | u32::from(u8::from(*mode)) << (index * 4));
+    let opcode = (u32::from(u16::from(operation)) << 12 | modes).to_le_bytes();
+    rom.extend_from_slice(&opcode[..3]);
+    for (_, value) in operands {
+        rom.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+// mov r0, #1
+// div r0, r0, #0
+pub fn divide_by_zero() -> Vec<u8> {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R0), (IMMEDIATE, 1), NONE]);
+    emit(&mut rom, Operation::Div, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 0)]);
+    rom
+}
+
+// Executes `count` instructions that must not raise anything
+pub fn step(cpu: &mut CPU, count: usize) {
+    for _ in 0..count {
+        cpu.tick().unwrap();
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+    }
+}
+
+pub fn run_to_interrupt(cpu: &mut CPU) -> Interrupt {
+    loop {
+        if let Err(interrupt) = cpu.tick() {
+            return interrupt;
+        }
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+    }
+}
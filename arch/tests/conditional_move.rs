@@ -0,0 +1,103 @@
+#![cfg(feature = "alloc")]
+// Checks every conditional move, under each of its names, moves exactly when its condition holds
+// for the flags cmp and cmps leave behind.
+
+use core::cmp::Ordering;
+
+use vixen::core::instruction::Operation;
+use vixen::models::JOHTO;
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{DIRECT, IMMEDIATE, NONE, R0, R1, R2, emit, step};
+
+const VALUES: [u32; 6] = [0, 1, 2, 0x7fff_ffff, 0x8000_0000, 0xffff_ffff];
+
+// How the first operand compares to the second, and the overflow flag, which cmp leaves alone
+type Condition = fn(Ordering, bool) -> bool;
+
+const CONDITIONS: &[(&str, Condition)] = &[
+    ("cmovz", |order, _| order.is_eq()),
+    ("cmove", |order, _| order.is_eq()),
+    ("cmovnz", |order, _| order.is_ne()),
+    ("cmovne", |order, _| order.is_ne()),
+    ("cmovc", |order, _| order.is_ge()),
+    ("cmovge", |order, _| order.is_ge()),
+    ("cmovae", |order, _| order.is_ge()),
+    ("cmovnc", |order, _| order.is_lt()),
+    ("cmovo", |_, overflow| overflow),
+    ("cmovno", |_, overflow| !overflow),
+    ("cmovs", |order, _| order.is_lt()),
+    ("cmovl", |order, _| order.is_lt()),
+    ("cmovb", |order, _| order.is_lt()),
+    ("cmovns", |order, _| order.is_ge()),
+    ("cmovle", |order, _| order.is_le()),
+    ("cmovbe", |order, _| order.is_le()),
+    ("cmovg", |order, _| order.is_gt()),
+    ("cmova", |order, _| order.is_gt()),
+];
+
+// cmp r0, r1 (or cmps)
+// cmovX r2, #1
+fn moves(compare: Operation, operation: Operation, [a, b]: [u32; 2], overflow: bool) -> bool {
+    let mut program = Vec::new();
+    emit(&mut program, compare, [(DIRECT, R0), (DIRECT, R1), NONE]);
+    emit(&mut program, operation, [(DIRECT, R2), (IMMEDIATE, 1), NONE]);
+
+    let mut cpu = CPU::new_with_model(MEMORY_1M, &JOHTO);
+    cpu.load_rom(&program).unwrap();
+    cpu.registers.r0 = a;
+    cpu.registers.r1 = b;
+    cpu.status_register.overflow = overflow;
+    step(&mut cpu, 2);
+
+    match cpu.registers.r2 {
+        0 => false,
+        1 => true,
+        other => panic!("{operation} wrote {other:#x}")
+    }
+}
+
+#[test]
+fn every_name_is_covered() {
+    let mut names: Vec<&str> = Operation::ALL.iter()
+        .filter(|operation| operation.mnemonic().starts_with("cmov"))
+        .flat_map(|operation| core::iter::once(operation.mnemonic()).chain(operation.aliases().iter().copied()))
+        .collect();
+    names.sort_unstable();
+
+    let mut tested: Vec<&str> = CONDITIONS.iter().map(|(name, _)| *name).collect();
+    tested.sort_unstable();
+    assert_eq!(names, tested);
+}
+
+#[test]
+fn moves_after_cmp() {
+    for (name, condition) in CONDITIONS {
+        let operation = Operation::from_mnemonic(name).unwrap();
+        for a in VALUES {
+            for b in VALUES {
+                for overflow in [false, true] {
+                    assert_eq!(moves(Operation::Cmp, operation, [a, b], overflow), condition(a.cmp(&b), overflow),
+                               "cmp {a:#x}, {b:#x}; {name} with overflow {overflow}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn moves_after_cmps() {
+    for (name, condition) in CONDITIONS {
+        let operation = Operation::from_mnemonic(name).unwrap();
+        for a in VALUES {
+            for b in VALUES {
+                let order = a.cast_signed().cmp(&b.cast_signed());
+                for overflow in [false, true] {
+                    assert_eq!(moves(Operation::Cmps, operation, [a, b], overflow), condition(order, overflow),
+                               "cmps {a:#x}, {b:#x}; {name} with overflow {overflow}");
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "alloc")]
+// Checks a core dump brings back the machine it was taken from.
+
+use vixen::core::core_dump::{CoreDumpError, CORE_DUMP_MAGIC};
+use vixen::core::{CoreDump, Interrupt};
+use vixen::models::PALLET;
+use vixen::{BusDevice, CPU, MEMORY_1M};
+use vixen::devices::errors::BusResult;
+
+mod common;
+use common::{divide_by_zero, run_to_interrupt};
+
+#[derive(Debug)]
+struct Latch {
+    value: u32,
+}
+
+impl BusDevice for Latch {
+    fn get_port_count(&self) -> u32 { 1 }
+    fn get_base_address(&self) -> u32 { 0x0400_0000 }
+    fn read_port(&mut self, _: u32) -> BusResult<u32> { Ok(self.value) }
+    fn write_port(&mut self, _: u32, data: u32) -> BusResult<()> { self.value = data; Ok(()) }
+    fn tick(&mut self) -> BusResult<()> { Ok(()) }
+}
+
+fn crash() -> (CPU<'static>, Interrupt) {
+    let mut cpu = CPU::new_with_model(MEMORY_1M + 100, &PALLET);
+    cpu.load_rom(&divide_by_zero()).unwrap();
+    cpu.add_device(Box::new(Latch { value: 7 })).unwrap();
+    let last = cpu.memory.len() - 1;
+    cpu.memory[last] = 0xAB;
+
+    let interrupt = run_to_interrupt(&mut cpu);
+    (cpu, interrupt)
+}
+
+#[test]
+fn round_trip() {
+    let (cpu, interrupt) = crash();
+    let bytes = CoreDump::capture(interrupt, &cpu).to_bytes();
+    let dump = CoreDump::parse(&bytes).unwrap();
+
+    assert_eq!(u32::from(dump.interrupt), 0x13);
+    assert_eq!(dump.model.short_name, "pallet");
+    assert_eq!(dump.devices.len(), 1);
+    assert_eq!(dump.devices[0].ports, 0x0400_0000..0x0400_0004);
+    assert_eq!(dump.devices[0].state, "Latch { value: 7 }");
+
+    let restored = dump.restore();
+    assert_eq!(restored.memory, cpu.memory);
+    assert_eq!(restored.registers.r0, 1);
+    assert_eq!(restored.program_counter, cpu.program_counter);
+    assert_eq!(restored.stack_pointer, cpu.stack_pointer);
+    assert_eq!(restored.system_stack, cpu.system_stack);
+    assert_eq!(restored.cycles, cpu.cycles);
+    assert_eq!(u8::from(restored.status_register), u8::from(cpu.status_register));
+}
+
+#[test]
+fn keeps_mmu_state() {
+    let (mut cpu, interrupt) = crash();
+    cpu.mmu.set_page_table_base(0x0001_0001);
+    cpu.mmu.set_fault_address(0x0040_1234);
+
+    let bytes = CoreDump::capture(interrupt, &cpu).to_bytes();
+    let restored = CoreDump::parse(&bytes).unwrap().restore();
+    assert!(restored.mmu.enabled());
+    assert_eq!(restored.mmu.page_table_base(), 0x0001_0001);
+    assert_eq!(restored.mmu.fault_address(), 0x0040_1234);
+}
+
+#[test]
+fn skips_empty_memory() {
+    let (cpu, interrupt) = crash();
+    let bytes = CoreDump::capture(interrupt, &cpu).to_bytes();
+    assert!(bytes.len() < cpu.memory.len() / 64, "{} bytes", bytes.len());
+}
+
+#[test]
+fn rejects_bad_files() {
+    let (cpu, interrupt) = crash();
+    let bytes = CoreDump::capture(interrupt, &cpu).to_bytes();
+
+    assert_eq!(CoreDump::parse(b"VXROM\0\0\0").unwrap_err(), CoreDumpError::NotACoreDump);
+    assert_eq!(CoreDump::parse(&bytes[..bytes.len() - 1]).unwrap_err(), CoreDumpError::Truncated);
+
+    // Memory size, after the magic, version, interrupt and model name
+    let mut huge = bytes.clone();
+    let size = 6 + 2 + 4 + 1 + cpu.model.short_name.len();
+    huge[size..size + 8].copy_from_slice(&(u64::from(u32::MAX) + 2).to_le_bytes());
+    assert!(matches!(CoreDump::parse(&huge).unwrap_err(), CoreDumpError::Invalid(_)));
+
+    let mut future = CORE_DUMP_MAGIC.to_vec();
+    future.extend_from_slice(&2u16.to_le_bytes());
+    assert_eq!(CoreDump::parse(&future).unwrap_err(), CoreDumpError::UnsupportedVersion(2));
+}
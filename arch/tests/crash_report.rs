@@ -0,0 +1,66 @@
+#![cfg(feature = "alloc")]
+// Checks the JSON crash report carries the fault state, without pulling in a JSON parser.
+
+use vixen::core::{CrashReport, Interrupt, SymbolTable};
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{divide_by_zero, run_to_interrupt};
+
+fn crash() -> (CPU<'static>, Interrupt) {
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_rom(&divide_by_zero()).unwrap();
+
+    let interrupt = run_to_interrupt(&mut cpu);
+    (cpu, interrupt)
+}
+
+#[test]
+fn report_describes_the_fault() {
+    let (cpu, interrupt) = crash();
+    let report = CrashReport::new(interrupt, &cpu).with_memory_window(4).to_string();
+
+    assert!(report.contains(r#""interrupt": {"code": 19, "description": "Divide by zero"}"#), "{report}");
+    assert!(report.contains(r#""reason": "interrupt""#), "{report}");
+    assert!(report.contains(r#""pc": 527"#), "{report}");
+    assert!(report.contains(r#""symbol": null"#), "{report}");
+    assert!(report.contains(r#""r0": 1, "r1": 0"#), "{report}");
+    assert!(report.contains(r#""instruction": {"bytes": [17, 48, 1, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0], "disassembly": "div r0, r0, #$0"}"#), "{report}");
+    assert!(report.contains(r#""memory": {"start": 525, "bytes": [0, 0, 17, 48]}"#), "{report}");
+
+    assert!(report.contains(r#""pc": 527, "ptbr": 0, "pfar": 0, "cid": 0}"#), "{report}");
+
+    let opening = report.matches(['{', '[']).count();
+    let closing = report.matches(['}', ']']).count();
+    assert_eq!(opening, closing, "{report}");
+}
+
+#[test]
+fn report_includes_mmu_and_core_registers() {
+    let (mut cpu, interrupt) = crash();
+    cpu.mmu.set_page_table_base(0x0001_0001);
+    cpu.mmu.set_fault_address(0x0040_1234);
+    cpu.core_id = 3;
+
+    let report = CrashReport::new(interrupt, &cpu).to_string();
+    assert!(report.contains(r#""ptbr": 65537, "pfar": 4198964, "cid": 3"#), "{report}");
+}
+
+#[test]
+fn strings_are_escaped() {
+    let (cpu, interrupt) = crash();
+    let mut symbols = SymbolTable::new();
+    symbols.insert(0x200, "say \"hi\"\\");
+
+    let report = CrashReport::new(interrupt, &cpu).with_symbols(&symbols).to_string();
+    assert!(report.contains(r#""symbol": "say \"hi\"\\+0xf""#), "{report}");
+}
+
+#[test]
+fn memory_outside_ram_is_null() {
+    let (mut cpu, interrupt) = crash();
+    cpu.program_counter = u32::try_from(MEMORY_1M).unwrap() - 1;
+
+    let report = CrashReport::new(interrupt, &cpu).with_memory_window(4).to_string();
+    assert!(report.contains(r#""bytes": [0, 0, 0, null]"#), "{report}");
+}
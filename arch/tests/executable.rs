@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+// Checks executables survive being written out and read back, and refuse CPUs they can't run on.
+
+use vixen::core::executable::{ExecutableError, Segment, EXECUTABLE_MAGIC};
+use vixen::core::{Executable, Extension, ExtensionSet, SymbolLookup, SymbolTable};
+use vixen::models::{JOHTO, KANTO, PALLET};
+use vixen::{CPU, MEMORY_1M};
+
+fn sample() -> Executable {
+    let mut symbols = SymbolTable::new();
+    symbols.insert(0x0001_0000, "main");
+    symbols.insert(0x0001_000f, "done");
+
+    Executable {
+        model: Some(&KANTO),
+        extensions: ExtensionSet::empty().with(Extension::DataMovement).with(Extension::ControlFlow),
+        entry_point: 0x0001_0000,
+        segments: vec![
+            Segment { address: 0x0001_0000, data: vec![1, 2, 3, 4] },
+            Segment { address: 0x0002_0000, data: vec![0xff; 16] },
+        ],
+        symbols: Some(symbols),
+        debug: Some("source main.asm".into()),
+    }
+}
+
+#[test]
+fn round_trip() {
+    let executable = Executable::parse(&sample().to_bytes()).unwrap();
+
+    assert_eq!(executable.model.map(|model| model.short_name), Some(KANTO.short_name));
+    assert_eq!(executable.extensions, sample().extensions);
+    assert_eq!(executable.entry_point, 0x0001_0000);
+    assert_eq!(executable.segments.len(), 2);
+    assert_eq!(executable.segments[1].address, 0x0002_0000);
+    assert_eq!(executable.segments[1].data, [0xff; 16]);
+    assert_eq!(executable.symbols.unwrap().to_map(), sample().symbols.unwrap().to_map());
+    assert_eq!(executable.debug.as_deref(), Some("source main.asm"));
+}
+
+#[test]
+fn symbols_stop_at_segment_ends() {
+    let symbols = Executable::parse(&sample().to_bytes()).unwrap().symbols.unwrap();
+
+    assert_eq!(symbols.lookup(0x0001_0003).unwrap().to_string(), "main+0x3");
+    assert_eq!(symbols.lookup(0x0001_0004), None);
+    // The second segment has no label of its own, the one between them covers nothing
+    assert_eq!(symbols.lookup(0x0002_0000), None);
+}
+
+#[test]
+fn loads_segments_at_their_addresses() {
+    let executable = sample();
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_segments(&executable.load_segments(), executable.entry_point).unwrap();
+
+    assert_eq!(cpu.program_counter, 0x0001_0000);
+    assert_eq!(cpu.memory[0x0001_0000..0x0001_0004], [1, 2, 3, 4]);
+    assert_eq!(cpu.memory[0x0002_0000], 0xff);
+}
+
+#[test]
+fn checks_the_cpu() {
+    assert_eq!(sample().check(&KANTO), Ok(()));
+    assert_eq!(sample().check(&JOHTO), Err(ExecutableError::WrongModel { required: "kanto", actual: "johto" }));
+
+    let multicore = Executable {
+        model: None,
+        extensions: ExtensionSet::empty().with(Extension::Multiprocessing),
+        ..sample()
+    };
+    assert_eq!(multicore.check(&JOHTO), Ok(()));
+    assert_eq!(multicore.check(&PALLET),
+               Err(ExecutableError::MissingExtensions(ExtensionSet::empty().with(Extension::Multiprocessing))));
+}
+
+#[test]
+fn skips_unknown_sections() {
+    let mut bytes = Executable::new(0x200, vec![7; 15]).to_bytes();
+    // No sections, then one of a kind from the future
+    let count = bytes.len() - 4;
+    bytes[count] = 1;
+    bytes.extend_from_slice(&[0x7f, 3, 0, 0, 0, b'n', b'e', b'w']);
+
+    let executable = Executable::parse(&bytes).unwrap();
+    assert!(executable.symbols.is_none());
+    assert_eq!(executable.segments[0].data, [7; 15]);
+}
+
+#[test]
+fn rejects_bad_files() {
+    let bytes = sample().to_bytes();
+
+    assert!(Executable::is_executable(&bytes));
+    assert!(!Executable::is_executable(&[0x03, 0x05, 0x05]));
+    assert_eq!(Executable::parse(b"VXCORE\x01\x00").unwrap_err(), ExecutableError::NotAnExecutable);
+    assert_eq!(Executable::parse(&bytes[..bytes.len() - 1]).unwrap_err(), ExecutableError::Truncated);
+
+    let mut future = EXECUTABLE_MAGIC.to_vec();
+    future.extend_from_slice(&2u16.to_le_bytes());
+    assert_eq!(Executable::parse(&future).unwrap_err(), ExecutableError::UnsupportedVersion(2));
+
+    let mut unknown = EXECUTABLE_MAGIC.to_vec();
+    unknown.extend_from_slice(&1u16.to_le_bytes());
+    unknown.push(6);
+    unknown.extend_from_slice(b"sinnoh");
+    assert_eq!(Executable::parse(&unknown).unwrap_err(), ExecutableError::UnknownModel("sinnoh".into()));
+}
@@ -0,0 +1,196 @@
+// Runs the CPU the way an allocation-free build does: on memory handed in by the caller, with
+// disassembly and stack traces formatted into a fixed buffer. Also run with --no-default-features.
+
+use core::cell::Cell;
+use core::fmt::Write;
+use vixen::core::instruction::Operation;
+use vixen::core::{Interrupt, StackTrace};
+use vixen::cpu::SystemStack;
+use vixen::devices::errors::{BusError, BusResult};
+use vixen::models::KANTO;
+use vixen::storage::{Device, Memory, SYSTEM_STACK_CAPACITY};
+use vixen::{BusDevice, CPU, MEMORY_NONE};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, IMMEDIATE, NONE, R0, R1, emit, run_to_interrupt, step};
+
+const DEVICE_BASE: u32 = 0x0400_0300;
+
+#[derive(Debug, Clone)]
+struct Constant(u32, u32);
+
+impl BusDevice for Constant {
+    fn get_port_count(&self) -> u32 {
+        1
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.0
+    }
+
+    fn read_port(&mut self, _index: u32) -> BusResult<u32> {
+        Ok(self.1)
+    }
+
+    fn write_port(&mut self, _index: u32, _data: u32) -> BusResult<()> {
+        Err(BusError::ReadOnly)
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        Ok(())
+    }
+}
+
+// Fixed-size text buffer, standing in for a UART or a display on a board
+struct Text {
+    bytes: [u8; 2048],
+    len: usize
+}
+
+impl Write for Text {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        self.bytes.get_mut(self.len..end).ok_or(core::fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+impl Text {
+    fn new() -> Self {
+        Self {
+            bytes: [0; 2048],
+            len: 0
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+    }
+}
+
+// Without an allocator the CPU borrows what the caller owns, with one it takes copies
+#[cfg(feature = "alloc")]
+fn memory(buffer: &mut [u8]) -> Memory<'_> {
+    Box::from(&*buffer)
+}
+
+#[cfg(not(feature = "alloc"))]
+fn memory(buffer: &mut [u8]) -> Memory<'_> {
+    buffer
+}
+
+#[cfg(feature = "alloc")]
+fn device(device: &mut Constant) -> Device<'_> {
+    Box::new(device.clone())
+}
+
+#[cfg(not(feature = "alloc"))]
+fn device(device: &mut Constant) -> Device<'_> {
+    device
+}
+
+#[test]
+fn runs_on_caller_provided_memory() {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (ABSOLUTE, DEVICE_BASE), NONE]);
+    emit(&mut rom, Operation::Div, [(DIRECT, R0), (DIRECT, R1), (IMMEDIATE, 0)]);
+
+    let mut buffer = vec![0; MEMORY_NONE];
+    let mut constant = Constant(DEVICE_BASE, 0x1234);
+    let mut cpu = CPU::with_memory(memory(&mut buffer), &KANTO);
+    cpu.load_rom(&rom).unwrap();
+    cpu.add_device(device(&mut constant)).unwrap();
+
+    let interrupt = run_to_interrupt(&mut cpu);
+
+    assert_eq!(u32::from(interrupt), u32::from(Interrupt::DivideByZero));
+    assert_eq!(cpu.registers.r1, 0x1234);
+
+    let mut text = Text::new();
+    write!(text, "{}", StackTrace::new(interrupt, &cpu)).unwrap();
+    assert!(text.as_str().contains("13 (Divide by zero)"), "{}", text.as_str());
+    assert!(text.as_str().contains("-> div r0, r1, #$0"), "{}", text.as_str());
+}
+
+#[test]
+fn system_stack_overflows_at_capacity() {
+    let mut buffer = vec![0; MEMORY_NONE];
+    let mut cpu = CPU::with_memory(memory(&mut buffer), &KANTO);
+    cpu.system_stack.clear();
+
+    for word in 0..SYSTEM_STACK_CAPACITY {
+        cpu.system_stack_push_word(u32::try_from(word).unwrap()).unwrap();
+    }
+
+    assert!(matches!(cpu.system_stack_push_word(0), Err(Interrupt::StackOverflow)));
+    assert_eq!(cpu.system_stack.len(), SYSTEM_STACK_CAPACITY);
+}
+
+#[cfg(not(feature = "alloc"))]
+#[test]
+fn device_table_is_fixed() {
+    use vixen::storage::DEVICE_CAPACITY;
+
+    let mut buffer = vec![0; MEMORY_NONE];
+    let mut constants: Vec<Constant> = (0..=DEVICE_CAPACITY)
+        .map(|slot| Constant(DEVICE_BASE + u32::try_from(slot).unwrap() * 4, 0))
+        .collect();
+    let (extra, constants) = constants.split_last_mut().unwrap();
+
+    let mut cpu = CPU::with_memory(memory(&mut buffer), &KANTO);
+    for constant in constants {
+        cpu.add_device(device(constant)).unwrap();
+    }
+    assert!(matches!(cpu.add_device(device(extra)), Err(Interrupt::Hardware)));
+}
+
+// Counts its ticks into a cell the caller keeps, so it can't outlive the caller's stack frame
+#[derive(Debug)]
+struct Ticks<'a>(&'a Cell<u32>);
+
+impl BusDevice for Ticks<'_> {
+    fn get_port_count(&self) -> u32 {
+        1
+    }
+
+    fn get_base_address(&self) -> u32 {
+        DEVICE_BASE
+    }
+
+    fn read_port(&mut self, _index: u32) -> BusResult<u32> {
+        Ok(self.0.get())
+    }
+
+    fn write_port(&mut self, _index: u32, _data: u32) -> BusResult<()> {
+        Err(BusError::ReadOnly)
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+}
+
+#[test]
+fn devices_borrow_from_the_caller() {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R0), (IMMEDIATE, 1), NONE]);
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (ABSOLUTE, DEVICE_BASE), NONE]);
+
+    let ticks = Cell::new(0);
+    let mut buffer = vec![0; MEMORY_NONE];
+    #[cfg(feature = "alloc")]
+    let counter: Device = Box::new(Ticks(&ticks));
+    #[cfg(not(feature = "alloc"))]
+    let mut counter = Ticks(&ticks);
+    #[cfg(not(feature = "alloc"))]
+    let counter: Device = &mut counter;
+
+    let mut cpu = CPU::with_memory(memory(&mut buffer), &KANTO);
+    cpu.load_rom(&rom).unwrap();
+    cpu.add_device(counter).unwrap();
+    step(&mut cpu, 2);
+
+    assert_eq!(ticks.get(), 2);
+}
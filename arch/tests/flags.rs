@@ -0,0 +1,247 @@
+#![cfg(feature = "alloc")]
+// Checks the status flags produced by every opcode against the table in
+// vixen::core::instruction::flags, using an independent model of the computed flags.
+
+use vixen::core::instruction::{FlagEffect, Operation};
+use vixen::core::registers::StatusRegister;
+use vixen::cpu::UserStack;
+use vixen::models::JOHTO;
+use vixen::{CPU, MEMORY_NONE};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, emit};
+
+const REGISTERS: [u32; 3] = [0x0001, 0x0011, 0x0012];
+const JUMP_TARGET: u32 = 0x300;
+
+const VALUES: [u32; 15] = [
+    0, 1, 2, 3, 1290, 1291, 46340, 46341, 0x1234_5678,
+    0x7fff_fffe, 0x7fff_ffff, 0x8000_0000, 0x8000_0001, 0xffff_fffe, 0xffff_ffff
+];
+
+type Condition = fn(StatusRegister) -> bool;
+
+// Flags an operation can change: negative, overflow, interrupt disable, zero, carry
+const FLAG_MASK: u8 = 0b1100_0111;
+
+// Operand holding a jump target, everything else is a register
+fn address_operand(operation: Operation) -> Option<usize> {
+    match operation {
+        Operation::Jmp | Operation::Jmpl | Operation::Jz | Operation::Jnz | Operation::Jc | Operation::Jnc
+            | Operation::Jo | Operation::Jno | Operation::Js | Operation::Jns => Some(0),
+        Operation::Jp | Operation::Jnp | Operation::Loop => Some(1),
+        Operation::Jnae | Operation::Jae | Operation::Jna | Operation::Ja | Operation::Jl
+            | Operation::Jge | Operation::Jle | Operation::Jg => Some(2),
+        _ => None
+    }
+}
+
+// <operation> r0, r1, r2 with the jump target in place of its register
+fn program(operation: Operation) -> Vec<u8> {
+    let mut operands = REGISTERS.map(|register| (DIRECT, register));
+    if let Some(index) = address_operand(operation) {
+        operands[index] = (ABSOLUTE, JUMP_TARGET);
+    }
+    let mut rom = Vec::new();
+    emit(&mut rom, operation, operands);
+    rom
+}
+
+fn fits_i32(value: i128) -> bool {
+    i32::try_from(value).is_ok()
+}
+
+fn fits_u32(value: i128) -> bool {
+    u32::try_from(value).is_ok()
+}
+
+fn signed(value: u32) -> i128 {
+    i128::from(value.cast_signed())
+}
+
+// Reference model for every flag the table marks as computed
+#[allow(clippy::too_many_lines)]
+fn computed(operation: Operation, before: StatusRegister, [a, b, c]: [u32; 3]) -> StatusRegister {
+    let mut flags = StatusRegister::default();
+    let carry_in = i128::from(before.carry);
+    let (ua, ub, uc) = (i128::from(a), i128::from(b), i128::from(c));
+
+    match operation {
+        Operation::Addv => flags.carry = !fits_u32(ub + uc),
+        Operation::Subv => flags.carry = ub < uc,
+        Operation::Mulv => flags.carry = !fits_u32(ub * uc),
+        Operation::Add => {
+            flags.carry = !fits_u32(ub + uc);
+            flags.overflow = !fits_i32(signed(b) + signed(c));
+        },
+        Operation::Sub => {
+            flags.carry = ub < uc;
+            flags.overflow = !fits_i32(signed(b) - signed(c));
+        },
+        Operation::Addc => {
+            flags.carry = !fits_u32(ub + uc + carry_in);
+            flags.overflow = !fits_i32(signed(b) + signed(c) + carry_in);
+        },
+        Operation::Subc => {
+            flags.carry = ub < uc + carry_in;
+            flags.overflow = !fits_i32(signed(b) - signed(c) - carry_in);
+        },
+        Operation::Mul => {
+            flags.carry = !fits_u32(ub * uc);
+            flags.overflow = !fits_i32(signed(b) * signed(c));
+        },
+        Operation::Sqre => {
+            flags.carry = !fits_u32(ub.pow(2));
+            flags.overflow = !fits_i32(signed(b).pow(2));
+        },
+        Operation::Cube => {
+            flags.carry = !fits_u32(ub.pow(3));
+            flags.overflow = !fits_i32(signed(b).pow(3));
+        },
+        Operation::Sal => {
+            flags.carry = a >> 31 == 1;
+            flags.overflow = (a >> 31) != ((a >> 30) & 1);
+        },
+        Operation::Shl | Operation::Rol => flags.carry = a >> 31 == 1,
+        Operation::Sar | Operation::Shr | Operation::Ror => flags.carry = a & 1 == 1,
+        Operation::Inc => {
+            flags.carry = a == u32::MAX;
+            flags.overflow = a == 0x7fff_ffff;
+        },
+        Operation::Dec | Operation::Loop => {
+            flags.carry = a == 0;
+            flags.overflow = a == 0x8000_0000;
+        },
+        Operation::Cmp => {
+            flags.zero = ua == ub;
+            flags.carry = ua >= ub;
+            flags.negative = ua < ub;
+        },
+        Operation::Cmps => {
+            flags.zero = signed(a) == signed(b);
+            flags.carry = signed(a) >= signed(b);
+            flags.negative = signed(a) < signed(b);
+        },
+        Operation::Lte => {
+            flags.zero = ua <= ub;
+            flags.carry = ua > ub;
+            flags.negative = ua < ub;
+        },
+        Operation::Gte => {
+            flags.zero = ua >= ub;
+            flags.carry = ua < ub;
+            flags.negative = ua < ub;
+        },
+        Operation::Cas => flags.zero = a == b,
+        Operation::Xadd => {
+            let sum = b.wrapping_add(c);
+            flags.zero = sum == 0;
+            flags.negative = sum >> 31 == 1;
+            flags.carry = !fits_u32(ub + uc);
+            flags.overflow = !fits_i32(signed(b) + signed(c));
+        },
+        _ => panic!("{operation} has no computed flags in the reference model")
+    }
+
+    flags
+}
+
+fn check(name: &str, effect: FlagEffect, before: bool, after: bool, result: bool, computed: impl FnOnce() -> bool) -> Result<(), String> {
+    let expected = match effect {
+        FlagEffect::Unaffected => before,
+        FlagEffect::Set => true,
+        FlagEffect::Cleared => false,
+        FlagEffect::Result => result,
+        FlagEffect::Computed => computed(),
+        FlagEffect::Restored => return Ok(())
+    };
+
+    if expected == after {
+        Ok(())
+    } else {
+        Err(format!("{name} should be {expected} ({effect:?})"))
+    }
+}
+
+fn run_case(cpu: &mut CPU, operation: Operation, before: StatusRegister, inputs: [u32; 3]) -> Option<Result<(), String>> {
+    cpu.program_counter = 0x200;
+    cpu.registers.r0 = inputs[0];
+    cpu.registers.r1 = inputs[1];
+    cpu.registers.r2 = inputs[2];
+    cpu.reset_stacks().unwrap();
+    cpu.user_stack_push_word(0x1234).unwrap();
+    cpu.status_register = before;
+
+    // Faulting executions make no promise about flags
+    cpu.tick_unhandled().ok()?;
+
+    let after = cpu.status_register;
+    let result = cpu.registers.r0;
+    let effects = operation.flags();
+    let computed = || computed(operation, before, inputs);
+
+    Some(check("zero", effects.zero, before.zero, after.zero, result == 0, || computed().zero)
+        .and_then(|()| check("carry", effects.carry, before.carry, after.carry, false, || computed().carry))
+        .and_then(|()| check("overflow", effects.overflow, before.overflow, after.overflow, false, || computed().overflow))
+        .and_then(|()| check("negative", effects.negative, before.negative, after.negative, result >> 31 == 1, || computed().negative))
+        .and_then(|()| check("interrupt disable", effects.interrupt_disable, before.interrupt_disable, after.interrupt_disable, false, || false)))
+}
+
+#[test]
+fn every_opcode_matches_flag_table() {
+    let operations = (0..0x100)
+        .filter_map(|opcode| Operation::try_from(opcode).ok())
+        // hlt never returns and int always raises an interrupt
+        .filter(|operation| !matches!(operation, Operation::Hlt | Operation::Int));
+
+    for operation in operations {
+        let mut cpu = CPU::new_with_model(MEMORY_NONE, &JOHTO);
+        cpu.load_rom(&program(operation)).unwrap();
+        let mut executed = 0;
+
+        for (case, inputs) in VALUES.iter()
+            .flat_map(|a| VALUES.iter().flat_map(move |b| VALUES.iter().map(move |c| [*a, *b, *c])))
+            .enumerate() {
+            #[allow(clippy::cast_possible_truncation)]
+            let before = StatusRegister::from((case as u8).wrapping_mul(37) & FLAG_MASK);
+
+            if let Some(outcome) = run_case(&mut cpu, operation, before, inputs) {
+                if let Err(error) = outcome {
+                    panic!("{operation} with {inputs:08x?} and flags {before}: {error}");
+                }
+                executed += 1;
+            }
+        }
+
+        assert!(executed > 0, "{operation} never executed successfully");
+    }
+}
+
+#[test]
+fn conditional_jumps_follow_their_flag() {
+    let jumps: [(Operation, Condition); 8] = [
+        (Operation::Jz, |flags| flags.zero),
+        (Operation::Jnz, |flags| !flags.zero),
+        (Operation::Jc, |flags| flags.carry),
+        (Operation::Jnc, |flags| !flags.carry),
+        (Operation::Jo, |flags| flags.overflow),
+        (Operation::Jno, |flags| !flags.overflow),
+        (Operation::Js, |flags| flags.negative),
+        (Operation::Jns, |flags| !flags.negative)
+    ];
+
+    for (operation, taken) in jumps {
+        let mut cpu = CPU::new_with_model(MEMORY_NONE, &JOHTO);
+        cpu.load_rom(&program(operation)).unwrap();
+
+        for pattern in 0..=FLAG_MASK {
+            let before = StatusRegister::from(pattern & FLAG_MASK);
+            cpu.program_counter = 0x200;
+            cpu.status_register = before;
+            cpu.tick_unhandled().unwrap();
+
+            let jumped = cpu.program_counter.wrapping_add(15) == JUMP_TARGET;
+            assert_eq!(jumped, taken(before), "{operation} with flags {before}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "alloc")]
+// Runs pseudo-random ROMs through the CPU to check that guest programs can only ever
+// raise interrupts and never panic the host. See fuzz/ for the coverage-guided version.
+
+use vixen::core::instruction::Operation;
+use vixen::cpu::Decoder;
+use vixen::{CPU, MEMORY_1M, MEMORY_NONE};
+
+const ROMS_PER_MEMORY_SIZE: usize = 300;
+const MAX_INSTRUCTIONS: usize = 32;
+const MAX_TICKS: usize = 512;
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        #[allow(clippy::cast_possible_truncation)]
+        let value = (self.0 >> 32) as u32;
+        value
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        self.next() as usize % bound
+    }
+}
+
+fn valid_opcodes() -> Vec<u16> {
+    (0..0x100)
+        .filter(|opcode| Operation::try_from(*opcode).is_ok())
+        .filter(|opcode| *opcode != u16::from(Operation::Hlt))
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn random_operand(rng: &mut XorShift, memory_size: usize) -> u32 {
+    let interesting = [
+        0x0000_0000, 0x0000_0001, 0x0000_0200, 0xffff_ffff, 0xffff_fff0, 0x7fff_ffff, 0x8000_0000,
+        0x0400_0200, 0x0410_0201, 0x0450_0200, 0x0450_0204, 0x1fff_fffb,
+        memory_size as u32, memory_size as u32 - 1, memory_size as u32 - 4,
+        0x0001, 0x0011, 0x1000, 0x100B, 0x2000, 0x2001, 0x2002, 0x2003, 0x2004, 0x2005
+    ];
+
+    if rng.below(4) == 0 {
+        rng.next()
+    } else {
+        interesting[rng.below(interesting.len())]
+    }
+}
+
+fn random_rom(rng: &mut XorShift, opcodes: &[u16], memory_size: usize) -> Vec<u8> {
+    let mut rom = Vec::new();
+
+    for _ in 0..=rng.below(MAX_INSTRUCTIONS) {
+        let operation = if rng.below(8) == 0 {
+            rng.next() & 0xfff
+        } else {
+            u32::from(opcodes[rng.below(opcodes.len())])
+        };
+        let modes = rng.next() & 0x777;
+        let opcode = (operation << 12 | modes).to_le_bytes();
+        rom.extend_from_slice(&opcode[..3]);
+
+        for _ in 0..3 {
+            rom.extend_from_slice(&random_operand(rng, memory_size).to_le_bytes());
+        }
+    }
+
+    rom
+}
+
+fn run(rom: &[u8], memory_size: usize) {
+    let mut cpu = CPU::new(memory_size);
+    if cpu.load_rom(rom).is_err() {
+        return;
+    }
+
+    for _ in 0..MAX_TICKS {
+        // hlt spins forever by design, stop before executing it
+        let halted = cpu.decode_instruction(cpu.program_counter)
+            .is_ok_and(|i| i.operation == u32::from(u16::from(Operation::Hlt)));
+        if halted || cpu.tick().is_err() {
+            break;
+        }
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+    }
+}
+
+#[test]
+fn random_roms_never_panic() {
+    let opcodes = valid_opcodes();
+    let mut rng = XorShift(0x5eed_f00d_cafe_beef);
+
+    for memory_size in [0x1000, MEMORY_NONE, MEMORY_1M] {
+        for _ in 0..ROMS_PER_MEMORY_SIZE {
+            let rom = random_rom(&mut rng, &opcodes, memory_size);
+            run(&rom, memory_size);
+        }
+    }
+}
+
+#[test]
+fn oversized_rom_is_rejected() {
+    let mut cpu = CPU::new(0x1000);
+    assert!(cpu.load_rom(&[0u8; 0x1000]).is_err());
+}
@@ -0,0 +1,81 @@
+#![cfg(feature = "alloc")]
+// Loads a small boot stub and a program as separate segments, the way a BIOS and the program it
+// starts end up in memory.
+
+use vixen::core::instruction::Operation;
+use vixen::core::Interrupt;
+use vixen::cpu::{LoadSegment, DEFAULT_ENTRY_POINT};
+use vixen::models::JOHTO;
+use vixen::{Machine, CPU, MEMORY_1M};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, IMMEDIATE, NONE, R0, R1, emit, step};
+
+const PROGRAM: u32 = 0x0001_0000;
+
+// mov r0, #1
+// jmpl PROGRAM
+fn boot_stub() -> Vec<u8> {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R0), (IMMEDIATE, 1), NONE]);
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, PROGRAM), NONE, NONE]);
+    rom
+}
+
+// mov r1, #42
+fn program() -> Vec<u8> {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (IMMEDIATE, 42), NONE]);
+    rom
+}
+
+#[test]
+fn boot_stub_starts_program() {
+    let (stub, program) = (boot_stub(), program());
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_segments(&[
+        LoadSegment { address: DEFAULT_ENTRY_POINT, data: &stub },
+        LoadSegment { address: PROGRAM, data: &program },
+    ], DEFAULT_ENTRY_POINT).unwrap();
+
+    step(&mut cpu, 3);
+    assert_eq!(cpu.registers.r0, 1);
+    assert_eq!(cpu.registers.r1, 42);
+    assert_eq!(cpu.program_counter, PROGRAM + 15);
+}
+
+#[test]
+fn starts_and_resets_at_entry_point() {
+    let program = program();
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_segments(&[LoadSegment { address: PROGRAM, data: &program }], PROGRAM).unwrap();
+    assert_eq!(cpu.program_counter, PROGRAM);
+
+    step(&mut cpu, 1);
+    assert_eq!(cpu.registers.r1, 42);
+
+    cpu.reset().unwrap();
+    assert_eq!(cpu.program_counter, PROGRAM);
+    assert_eq!(cpu.registers.r1, 0);
+}
+
+#[test]
+fn every_core_starts_at_entry_point() {
+    let program = program();
+    let mut machine = Machine::new(MEMORY_1M, 2, &JOHTO);
+    machine.load_segments(&[LoadSegment { address: PROGRAM, data: &program }], PROGRAM).unwrap();
+
+    for core in 0..2 {
+        assert_eq!(machine.with_core(core, |cpu| cpu.program_counter), PROGRAM);
+    }
+}
+
+#[test]
+fn rejects_segments_outside_memory() {
+    let mut cpu = CPU::new(0x1000);
+    let segment = LoadSegment { address: 0x0800, data: &[0u8; 0x1000] };
+    assert!(matches!(cpu.load_segments(&[segment], 0x0800), Err(Interrupt::IllegalMemory)));
+
+    let segment = LoadSegment { address: u32::MAX, data: &[0u8; 2] };
+    assert!(matches!(cpu.load_segments(&[segment], DEFAULT_ENTRY_POINT), Err(Interrupt::IllegalMemory)));
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "alloc")]
+// Checks loop decrements its counter and branches until the counter reaches zero, and that only
+// models with the loop extension run it.
+
+use vixen::core::instruction::Operation;
+use vixen::core::{CpuModel, Interrupt};
+use vixen::models::{JOHTO, KANTO};
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, NONE, R0, emit, step};
+
+// loop r0, $<target>
+fn load(model: &'static CpuModel, counter: u32, target: u32) -> CPU<'static> {
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Loop, [(DIRECT, R0), (ABSOLUTE, target), NONE]);
+
+    let mut cpu = CPU::new_with_model(MEMORY_1M, model);
+    cpu.load_rom(&program).unwrap();
+    cpu.registers.r0 = counter;
+    cpu
+}
+
+#[test]
+fn branches_while_the_counter_is_not_zero() {
+    let mut cpu = load(&JOHTO, 3, 0x300);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.registers.r0, 2);
+    assert_eq!(cpu.program_counter, 0x300);
+    assert!(!cpu.status_register.zero);
+}
+
+#[test]
+fn falls_through_at_zero() {
+    let mut cpu = load(&JOHTO, 1, 0x300);
+    step(&mut cpu, 1);
+    assert_eq!(cpu.registers.r0, 0);
+    assert_eq!(cpu.program_counter, 0x20f);
+    assert!(cpu.status_register.zero);
+}
+
+#[test]
+fn repeats_until_the_counter_runs_out() {
+    // Jumping back onto itself, the loop runs once per count
+    let mut cpu = load(&JOHTO, 3, 0x200);
+    step(&mut cpu, 2);
+    assert_eq!((cpu.registers.r0, cpu.program_counter), (1, 0x200));
+    step(&mut cpu, 1);
+    assert_eq!((cpu.registers.r0, cpu.program_counter), (0, 0x20f));
+}
+
+#[test]
+fn needs_the_loop_extension() {
+    let mut cpu = load(&KANTO, 3, 0x300);
+    assert!(matches!(cpu.tick(), Err(Interrupt::IllegalInstruction)));
+    assert_eq!(cpu.registers.r0, 3);
+}
@@ -0,0 +1,179 @@
+#![cfg(feature = "alloc")]
+// Checks address translation through the two-level page table: where pages land, when they fault
+// and what PFAR says about it, and that the TLB only forgets mappings when PTBR is written.
+
+use vixen::core::instruction::Operation;
+use vixen::core::Interrupt;
+use vixen::cpu::mmu::Access;
+use vixen::cpu::AddressTranslation;
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{DIRECT, IMMEDIATE, NONE, R0, emit, run_to_interrupt, step};
+
+const PRESENT: u32 = 0b001;
+const WRITABLE: u32 = 0b010;
+const USER: u32 = 0b100;
+const ALL: u32 = PRESENT | WRITABLE | USER;
+
+const DIRECTORY: u32 = 0x0001_0000;
+const TABLE: u32 = 0x0001_1000;
+const PTBR: u32 = 0x2003;
+const PFAR: u32 = 0x2004;
+
+fn write_word(cpu: &mut CPU, address: u32, value: u32) {
+    let address = address as usize;
+    cpu.memory[address..address + 4].copy_from_slice(&value.to_le_bytes());
+}
+
+fn map(cpu: &mut CPU, page: u32, frame: u32, flags: u32) {
+    write_word(cpu, TABLE + page * 4, frame << 12 | flags);
+}
+
+// Paging on, with the first 4 MiB described by one table. Only page 0, holding the program, is
+// mapped so far
+fn paged(program: &[u8]) -> CPU<'static> {
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.memory[0x200..0x200 + program.len()].copy_from_slice(program);
+    write_word(&mut cpu, DIRECTORY, TABLE | ALL);
+    map(&mut cpu, 0, 0, ALL);
+    cpu.mmu.set_page_table_base(DIRECTORY | 1);
+    cpu
+}
+
+#[test]
+fn identity_without_paging() {
+    let cpu = CPU::new(MEMORY_1M);
+    assert!(!cpu.mmu.enabled());
+    assert_eq!(cpu.translate(0x0001_2345, Access::Write).ok(), Some(0x0001_2345));
+    assert_eq!(cpu.translate(0x7fff_ffff, Access::Execute).ok(), Some(0x7fff_ffff));
+}
+
+#[test]
+fn remaps_pages() {
+    let mut cpu = paged(&[]);
+    map(&mut cpu, 0x20, 0x30, ALL);
+    map(&mut cpu, 0x40, 0x40, ALL);
+
+    assert_eq!(cpu.translate(0x0002_0abc, Access::Read).ok(), Some(0x0003_0abc));
+    assert_eq!(cpu.translate(0x0002_0fff, Access::Write).ok(), Some(0x0003_0fff));
+    assert_eq!(cpu.translate(0x0004_0123, Access::Execute).ok(), Some(0x0004_0123));
+
+    cpu.memory[0x0003_0abc] = 0x5a;
+    assert_eq!(cpu.read_virtual::<1>(0x0002_0abc, Access::Read).ok(), Some([0x5a]));
+}
+
+#[test]
+fn missing_entries_fault() {
+    let cpu = paged(&[]);
+
+    // No table behind the second directory entry
+    assert!(matches!(cpu.translate(0x0040_1234, Access::Read), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0040_1234);
+
+    // A table, but nothing mapped in it
+    assert!(matches!(cpu.translate(0x0002_0010, Access::Read), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0002_0010);
+}
+
+#[test]
+fn supervisor_pages() {
+    let mut cpu = paged(&[]);
+    map(&mut cpu, 0x20, 0x30, PRESENT | WRITABLE);
+
+    assert!(matches!(cpu.translate(0x0002_0004, Access::Read), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0002_0004);
+
+    cpu.status_register.interrupt = true;
+    assert_eq!(cpu.translate(0x0002_0004, Access::Read).ok(), Some(0x0003_0004));
+
+    // Permissions are the intersection of both levels, the directory entry can take user access away
+    write_word(&mut cpu, DIRECTORY, TABLE | PRESENT | WRITABLE);
+    cpu.mmu.flush();
+    cpu.status_register.interrupt = false;
+    assert!(matches!(cpu.translate(0x0000_0200, Access::Execute), Err(Interrupt::PageFault)));
+}
+
+#[test]
+fn read_only_pages() {
+    let mut cpu = paged(&[]);
+    map(&mut cpu, 0x20, 0x30, PRESENT | USER);
+    cpu.memory[0x0003_0000] = 7;
+
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_0000));
+    assert_eq!(cpu.translate(0x0002_0000, Access::Execute).ok(), Some(0x0003_0000));
+    assert!(matches!(cpu.write_virtual(0x0002_0000, &[1]), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0002_0000);
+    assert_eq!(cpu.memory[0x0003_0000], 7);
+}
+
+#[test]
+fn tlb_keeps_stale_mappings_until_flushed() {
+    let mut cpu = paged(&[]);
+    map(&mut cpu, 0x20, 0x30, ALL);
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_0000));
+
+    // The walk is cached, changing the table alone does nothing
+    map(&mut cpu, 0x20, 0x31, ALL);
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_0000));
+
+    // Reloading PTBR flushes even when the value is the same
+    cpu.mmu.set_page_table_base(cpu.mmu.page_table_base());
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_1000));
+}
+
+#[test]
+fn ptbr_writes_flush_the_tlb() {
+    // mov ptbr, #DIRECTORY | 1
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, PTBR), (IMMEDIATE, DIRECTORY | 1), NONE]);
+    let mut cpu = paged(&program);
+    map(&mut cpu, 0x20, 0x30, ALL);
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_0000));
+    map(&mut cpu, 0x20, 0x31, ALL);
+
+    cpu.status_register.interrupt = true;
+    step(&mut cpu, 1);
+    assert_eq!(cpu.translate(0x0002_0000, Access::Read).ok(), Some(0x0003_1000));
+
+    // Outside an interrupt handler, switching address spaces is refused
+    cpu.status_register.interrupt = false;
+    cpu.program_counter = 0x200;
+    assert!(matches!(run_to_interrupt(&mut cpu), Interrupt::IllegalMemory));
+}
+
+#[test]
+fn pfar_is_readable_and_writable() {
+    // mov r0, pfar
+    // mov pfar, #0
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (DIRECT, PFAR), NONE]);
+    emit(&mut program, Operation::Mov, [(DIRECT, PFAR), (IMMEDIATE, 0), NONE]);
+    let mut cpu = paged(&program);
+
+    assert!(cpu.translate(0x0002_0000, Access::Read).is_err());
+    step(&mut cpu, 2);
+    assert_eq!(cpu.registers.r0, 0x0002_0000);
+    assert_eq!(cpu.mmu.fault_address(), 0);
+}
+
+#[test]
+fn accesses_across_pages() {
+    let mut cpu = paged(&[]);
+    // Neighbouring virtual pages, far apart physically
+    map(&mut cpu, 0x20, 0x30, ALL);
+    map(&mut cpu, 0x21, 0x50, ALL);
+
+    cpu.write_virtual(0x0002_0ffe, &[1, 2, 3, 4]).unwrap();
+    assert_eq!(cpu.memory[0x0003_0ffe..0x0003_1000], [1, 2]);
+    assert_eq!(cpu.memory[0x0005_0000..0x0005_0002], [3, 4]);
+    assert_eq!(cpu.read_virtual::<4>(0x0002_0ffe, Access::Read).ok(), Some([1, 2, 3, 4]));
+
+    // A write running into an unmapped page faults on its first byte there and writes nothing
+    assert!(matches!(cpu.write_virtual(0x0002_1ffe, &[9, 9, 9, 9]), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0002_2000);
+    assert_eq!(cpu.memory[0x0005_0ffe..0x0005_1000], [0, 0]);
+
+    assert!(matches!(cpu.read_virtual::<4>(0x0002_1ffe, Access::Read), Err(Interrupt::PageFault)));
+    assert_eq!(cpu.mmu.fault_address(), 0x0002_2000);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "alloc")]
+// Checks each CPU model only runs the extensions it has, and says which ones those are in the
+// specification block guests read from address 0.
+
+use vixen::core::instruction::Operation;
+use vixen::core::specification::Specification;
+use vixen::core::{CpuModel, Interrupt};
+use vixen::models::{DEFAULT_MODEL, JOHTO, KANTO, PALLET};
+use vixen::{CPU, CPU_SPECIFICATION, MEMORY_1M};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, IMMEDIATE, NONE, R0, R1, emit};
+
+fn load(model: &'static CpuModel, program: &[u8]) -> CPU<'static> {
+    let mut cpu = CPU::new_with_model(MEMORY_1M, model);
+    cpu.load_rom(program).unwrap();
+    cpu
+}
+
+// jge r0, #1, $300, never taken with r0 at zero
+fn advanced_flow() -> Vec<u8> {
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Jge, [(DIRECT, R0), (IMMEDIATE, 1), (ABSOLUTE, 0x300)]);
+    program
+}
+
+// cmovz r0, r1
+fn conditional_move() -> Vec<u8> {
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Cmovz, [(DIRECT, R0), (DIRECT, R1), NONE]);
+    program
+}
+
+fn read_word(memory: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(memory[offset..offset + 4].try_into().unwrap())
+}
+
+#[test]
+fn new_with_model() {
+    assert_eq!(CPU::new(MEMORY_1M).model.short_name, DEFAULT_MODEL.short_name);
+    for model in [&PALLET, &KANTO, &JOHTO] {
+        let cpu = CPU::new_with_model(MEMORY_1M, model);
+        assert_eq!(cpu.model.short_name, model.short_name);
+        assert_eq!(cpu.memory.len(), MEMORY_1M);
+    }
+}
+
+#[test]
+fn extension_opcodes_need_their_extension() {
+    let mut cpu = load(&PALLET, &advanced_flow());
+    assert!(matches!(cpu.tick(), Err(Interrupt::IllegalInstruction)));
+
+    let mut cpu = load(&KANTO, &advanced_flow());
+    assert!(cpu.tick().is_ok());
+    assert_eq!(cpu.program_counter, 0x200);
+
+    let mut cpu = load(&KANTO, &conditional_move());
+    assert!(matches!(cpu.tick(), Err(Interrupt::IllegalInstruction)));
+
+    let mut cpu = load(&JOHTO, &conditional_move());
+    cpu.registers.r1 = 7;
+    cpu.status_register.zero = true;
+    assert!(cpu.tick().is_ok());
+    assert_eq!(cpu.registers.r0, 7);
+}
+
+#[test]
+fn base_opcodes_run_everywhere() {
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (IMMEDIATE, 5), NONE]);
+    for model in [&PALLET, &KANTO, &JOHTO] {
+        let mut cpu = load(model, &program);
+        assert!(cpu.tick().is_ok());
+        assert_eq!(cpu.registers.r0, 5);
+    }
+}
+
+#[test]
+fn specification_block_lists_features() {
+    for model in [&PALLET, &KANTO, &JOHTO] {
+        let cpu = load(model, &[]);
+        let specification = model.specification;
+
+        // name, id, microarchitecture, microarchitecture name, two widths, then RAM, microcode
+        // and VM end before the features
+        let offset = 1 + specification.name.len() + 4
+            + 1 + specification.microarchitecture.len()
+            + 1 + specification.microarchitecture_name.len()
+            + 2 + 3 * 4;
+        assert_eq!(cpu.memory[0] as usize, specification.name.len());
+        assert_eq!(read_word(&cpu.memory, 1 + specification.name.len()), specification.id);
+        assert_eq!(read_word(&cpu.memory, offset), u32::from(model.extensions), "{}", model.short_name);
+        assert_eq!(read_word(&cpu.memory, offset + 4), 1);
+    }
+}
+
+#[test]
+fn default_specification() {
+    assert_eq!(CPU_SPECIFICATION.id, DEFAULT_MODEL.specification.id);
+    assert_eq!(CPU_SPECIFICATION.name, KANTO.specification.name);
+
+    let specification = Specification::new(CPU_SPECIFICATION, MEMORY_1M);
+    assert_eq!(specification.features, u32::from(KANTO.extensions));
+    assert_eq!(specification.cores, 1);
+
+    let specification = Specification::for_model(&JOHTO, MEMORY_1M, 4);
+    assert_eq!(specification.features, u32::from(JOHTO.extensions));
+    assert_eq!(specification.cores, 4);
+
+    // Without a model, the features come from the model the id belongs to
+    let from_id = Specification { cores: 4, ..Specification::new(JOHTO.specification, MEMORY_1M) };
+    assert_eq!(Vec::from(specification), Vec::from(from_id));
+}
@@ -0,0 +1,153 @@
+#![cfg(feature = "alloc")]
+// Runs small hand-assembled programs on several cores sharing the same memory.
+
+use vixen::core::instruction::{Addressing, Operation};
+use vixen::machine::Scheduling;
+use vixen::models::JOHTO;
+use vixen::{Machine, MEMORY_1M};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, IMMEDIATE, NONE, emit};
+
+const CID: (Addressing, u32) = (DIRECT, 0x2005);
+const R1: (Addressing, u32) = (DIRECT, common::R1);
+const R2: (Addressing, u32) = (DIRECT, common::R2);
+
+const LOCK: u32 = 0x0460_0000;
+const COUNTER: u32 = 0x0460_0004;
+const MAILBOX: u32 = 0x0460_0010;
+
+struct Rom(Vec<u8>);
+
+impl Rom {
+    fn new() -> Self {
+        Self(Vec::new())
+    }
+
+    fn here(&self) -> u32 {
+        0x200 + u32::try_from(self.0.len()).unwrap()
+    }
+
+    fn emit(&mut self, operation: Operation, operands: [(Addressing, u32); 3]) -> &mut Self {
+        emit(&mut self.0, operation, operands);
+        self
+    }
+}
+
+fn machine(cores: u32, rom: &Rom) -> Machine<'static> {
+    let mut machine = Machine::new(MEMORY_1M, cores, &JOHTO);
+    machine.load_rom(&rom.0).unwrap();
+    machine
+}
+
+fn read_word(machine: &Machine, address: u32) -> u32 {
+    let address = address as usize;
+    u32::from_le_bytes(machine.cpu.memory[address..address + 4].try_into().unwrap())
+}
+
+// Every core bumps the shared counter `iterations` times, then parks
+fn fetch_add_rom(iterations: u32) -> Rom {
+    let mut rom = Rom::new();
+    rom.emit(Operation::Mov, [R2, (IMMEDIATE, iterations), NONE]);
+    let top = rom.here();
+    rom.emit(Operation::Xadd, [R1, (ABSOLUTE, COUNTER), (IMMEDIATE, 1)])
+        .emit(Operation::Dec, [R2, NONE, NONE])
+        .emit(Operation::Jnz, [(ABSOLUTE, top), NONE, NONE]);
+    let park = rom.here();
+    rom.emit(Operation::Jmpl, [(ABSOLUTE, park), NONE, NONE]);
+    rom
+}
+
+#[test]
+fn fetch_add_is_atomic_under_every_schedule() {
+    let rom = fetch_add_rom(50);
+    let schedules = [
+        Scheduling::RoundRobin { quantum: 1 },
+        Scheduling::RoundRobin { quantum: 7 },
+        Scheduling::Random { seed: 0xdead_beef }
+    ];
+
+    for scheduling in schedules {
+        let mut machine = machine(4, &rom);
+        machine.set_scheduling(scheduling);
+        for _ in 0..4000 {
+            machine.step().unwrap();
+        }
+
+        assert_eq!(read_word(&machine, COUNTER), 200);
+        for core in 0..4 {
+            assert_eq!(machine.with_core(core, |cpu| cpu.registers.r2), 0);
+        }
+    }
+}
+
+#[test]
+fn compare_and_swap_protects_critical_sections() {
+    // A non-atomic increment guarded by a spinlock
+    let mut rom = Rom::new();
+    rom.emit(Operation::Mov, [R2, (IMMEDIATE, 40), NONE]);
+    let acquire = rom.here();
+    rom.emit(Operation::Cas, [(ABSOLUTE, LOCK), (IMMEDIATE, 0), (IMMEDIATE, 1)])
+        .emit(Operation::Jnz, [(ABSOLUTE, acquire), NONE, NONE])
+        .emit(Operation::Mov, [R1, (ABSOLUTE, COUNTER), NONE])
+        .emit(Operation::Inc, [R1, NONE, NONE])
+        .emit(Operation::Mov, [(ABSOLUTE, COUNTER), R1, NONE])
+        .emit(Operation::Mov, [(ABSOLUTE, LOCK), (IMMEDIATE, 0), NONE])
+        .emit(Operation::Dec, [R2, NONE, NONE])
+        .emit(Operation::Jnz, [(ABSOLUTE, acquire), NONE, NONE]);
+    let park = rom.here();
+    rom.emit(Operation::Jmpl, [(ABSOLUTE, park), NONE, NONE]);
+
+    let mut machine = machine(3, &rom);
+    for _ in 0..20_000 {
+        machine.step().unwrap();
+    }
+
+    assert_eq!(read_word(&machine, COUNTER), 120);
+    assert_eq!(read_word(&machine, LOCK), 0);
+}
+
+#[test]
+fn inter_processor_interrupt_reaches_target_core() {
+    let mut rom = Rom::new();
+    let handler = 0x200 + 15 * 5;
+    rom.emit(Operation::Mov, [(ABSOLUTE, 0x0450_0200), (IMMEDIATE, handler), NONE]);
+    let park = 0x200 + 15 * 4;
+    // Only the bootstrap core sends the interrupt
+    rom.emit(Operation::Ja, [CID, (IMMEDIATE, 0), (ABSOLUTE, park)])
+        .emit(Operation::Ipi, [(IMMEDIATE, 2), NONE, NONE])
+        .emit(Operation::Nop, [NONE, NONE, NONE])
+        .emit(Operation::Jmpl, [(ABSOLUTE, park), NONE, NONE]);
+    assert_eq!(rom.here(), handler);
+    rom.emit(Operation::Mov, [(ABSOLUTE, MAILBOX), CID, NONE])
+        .emit(Operation::Jmpl, [(ABSOLUTE, handler + 15), NONE, NONE]);
+
+    let mut machine = machine(3, &rom);
+    for _ in 0..60 {
+        machine.step().unwrap();
+    }
+
+    assert_eq!(read_word(&machine, MAILBOX), 2);
+    assert!(!machine.with_core(0, |cpu| cpu.status_register.interrupt));
+    assert!(!machine.with_core(1, |cpu| cpu.status_register.interrupt));
+    assert!(machine.with_core(2, |cpu| cpu.status_register.interrupt));
+}
+
+#[test]
+fn ipi_to_missing_core_faults() {
+    let mut rom = Rom::new();
+    rom.emit(Operation::Ipi, [(IMMEDIATE, 2), NONE, NONE]);
+
+    let mut machine = machine(2, &rom);
+    assert!(machine.step().is_err());
+}
+
+#[test]
+fn cores_get_separate_stacks() {
+    let machine_stacks: Vec<u32> = {
+        let mut machine = machine(2, &fetch_add_rom(1));
+        (0..2).map(|core| machine.with_core(core, |cpu| cpu.stack_pointer)).collect()
+    };
+
+    assert_ne!(machine_stacks[0], machine_stacks[1]);
+}
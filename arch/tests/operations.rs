@@ -0,0 +1,73 @@
+// Checks the ISA table tools share: every name resolves to the right operation, no name is taken
+// twice and each operation survives a trip through its opcode.
+
+use vixen::core::instruction::{OperandRole, Operation};
+
+// Names older programs were written with, before the table gave each operation one mnemonic
+const LEGACY_ALIASES: &[(&str, Operation)] = &[
+    ("jsr", Operation::Jmp), ("sqt", Operation::Sqrt), ("cbt", Operation::Cbrt), ("sqr", Operation::Sqre),
+    ("cbe", Operation::Cube), ("adc", Operation::Addc), ("sbc", Operation::Subc), ("asr", Operation::Sar),
+    ("asl", Operation::Sal), ("nad", Operation::Nand), ("irj", Operation::Irets), ("srz", Operation::Setz),
+    ("src", Operation::Setc), ("sro", Operation::Seto), ("swp", Operation::Xchg), ("sec", Operation::Stc),
+    ("sei", Operation::Sti), ("beq", Operation::Jz), ("bne", Operation::Jnz), ("bec", Operation::Jc),
+    ("bnc", Operation::Jnc), ("beo", Operation::Jo), ("bno", Operation::Jno), ("irt", Operation::Iret),
+    ("jam", Operation::Hlt), ("bpl", Operation::Js), ("bmi", Operation::Jns), ("psh", Operation::Push),
+    ("pll", Operation::Pop), ("php", Operation::Pushf), ("plp", Operation::Popf),
+];
+
+#[test]
+fn legacy_aliases_resolve() {
+    for (name, operation) in LEGACY_ALIASES {
+        assert_eq!(Operation::from_mnemonic(name), Some(*operation), "{name}");
+        assert!(operation.aliases().contains(name), "{name}");
+    }
+}
+
+#[test]
+fn every_name_resolves_to_its_operation() {
+    for operation in Operation::ALL.iter().copied() {
+        assert_eq!(Operation::from_mnemonic(operation.mnemonic()), Some(operation));
+        assert_eq!(Operation::from_mnemonic(&operation.mnemonic().to_uppercase()), Some(operation));
+        for alias in operation.aliases() {
+            assert_eq!(Operation::from_mnemonic(alias), Some(operation), "{alias}");
+        }
+    }
+
+    assert_eq!(Operation::from_mnemonic("frobnicate"), None);
+}
+
+#[test]
+fn names_are_unique() {
+    let mut names: Vec<&str> = Operation::ALL.iter()
+        .flat_map(|operation| core::iter::once(operation.mnemonic()).chain(operation.aliases().iter().copied()))
+        .collect();
+    let count = names.len();
+    names.sort_unstable();
+    names.dedup();
+    assert_eq!(names.len(), count);
+}
+
+#[test]
+fn opcodes_round_trip() {
+    for operation in Operation::ALL.iter().copied() {
+        let info = operation.info();
+        assert_eq!(Operation::try_from(info.opcode).ok(), Some(operation));
+        assert_eq!(info.mnemonic, operation.mnemonic());
+        assert_eq!(info.operands.len(), operation.operands().len());
+        assert!(info.operands.len() <= 3, "{operation}");
+    }
+}
+
+#[test]
+fn roles() {
+    assert_eq!(Operation::Add.operands(), [OperandRole::Write, OperandRole::Read, OperandRole::Read]);
+    assert_eq!(Operation::Xchg.operands(), [OperandRole::ReadWrite, OperandRole::ReadWrite]);
+    assert_eq!(Operation::Jmp.operands(), [OperandRole::Address]);
+    assert_eq!(Operation::Jge.operands(), [OperandRole::Read, OperandRole::Read, OperandRole::Address]);
+    assert!(Operation::Nop.operands().is_empty());
+
+    assert!(OperandRole::Write.is_written());
+    assert!(OperandRole::ReadWrite.is_written());
+    assert!(!OperandRole::Read.is_written());
+    assert!(!OperandRole::Address.is_written());
+}
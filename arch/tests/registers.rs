@@ -0,0 +1,150 @@
+#![cfg(feature = "alloc")]
+// Checks every register can be written and read back through `mov`, including the special ones
+// whose writes do more than store a value.
+
+use vixen::core::instruction::Operation;
+use vixen::core::registers::RegisterId;
+use vixen::core::Interrupt;
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{ABSOLUTE, DIRECT, IMMEDIATE, NONE, R0, R1, R2, emit, run_to_interrupt, step};
+
+const GENERAL: [(RegisterId, &str); 15] = [
+    (RegisterId::R0, "r0"), (RegisterId::R1, "r1"), (RegisterId::R2, "r2"), (RegisterId::R3, "r3"),
+    (RegisterId::R4, "r4"), (RegisterId::R5, "r5"), (RegisterId::R6, "r6"), (RegisterId::R7, "r7"),
+    (RegisterId::R8, "r8"), (RegisterId::R9, "r9"), (RegisterId::R10, "r10"), (RegisterId::R11, "r11"),
+    (RegisterId::R12, "r12"), (RegisterId::R13, "r13"), (RegisterId::R14, "r14"),
+];
+
+const SP: u32 = 0x2000;
+const PC: u32 = 0x2001;
+const SR: u32 = 0x2002;
+const CID: u32 = 0x2005;
+
+// Past the interrupt vectors at the start of RAM
+const SCRATCH: u32 = 0x0450_1000;
+
+fn load(program: &[u8]) -> CPU<'static> {
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_rom(program).unwrap();
+    cpu
+}
+
+fn word(cpu: &CPU, address: u32) -> u32 {
+    let address = address as usize;
+    u32::from_le_bytes(cpu.memory[address..address + 4].try_into().unwrap())
+}
+
+#[test]
+fn general_registers() {
+    // mov rN, #value, then mov [SCRATCH + 4N], rN, for each of them
+    let mut program = Vec::new();
+    for (index, (id, _)) in (0u32..).zip(GENERAL) {
+        emit(&mut program, Operation::Mov, [(DIRECT, u32::from(id)), (IMMEDIATE, 0x0101_0101 * (index + 1)), NONE]);
+        emit(&mut program, Operation::Mov, [(ABSOLUTE, SCRATCH + index * 4), (DIRECT, u32::from(id)), NONE]);
+    }
+    let mut cpu = load(&program);
+    step(&mut cpu, GENERAL.len() * 2);
+
+    let registers = &cpu.registers;
+    assert_eq!([registers.r0, registers.r7, registers.r14], [0x0101_0101, 0x0808_0808, 0x0f0f_0f0f]);
+    for index in 0..15 {
+        assert_eq!(word(&cpu, SCRATCH + index * 4), 0x0101_0101 * (index + 1), "register {index}");
+    }
+}
+
+#[test]
+fn register_names() {
+    for (id, name) in GENERAL {
+        assert_eq!(RegisterId::try_from(&name.to_string()).ok(), Some(id));
+        assert_eq!(RegisterId::try_from(&name.to_uppercase()).ok(), Some(id));
+        assert_eq!(RegisterId::try_from(u32::from(id)).ok(), Some(id));
+    }
+    for (id, name) in [(RegisterId::Sp, "sp"), (RegisterId::Pc, "pc"), (RegisterId::Sr, "sr"),
+                       (RegisterId::Ptbr, "ptbr"), (RegisterId::Pfar, "pfar"), (RegisterId::Cid, "cid")] {
+        assert_eq!(RegisterId::try_from(&name.to_string()).ok(), Some(id));
+    }
+
+    assert!(RegisterId::try_from(&"r15".to_string()).is_err());
+    assert!(RegisterId::try_from(0x100C).is_err());
+}
+
+#[test]
+fn writing_pc_jumps() {
+    // 200: mov pc, #$23c
+    // 20f: mov r1, #1 (x3, skipped)
+    // 23c: mov r2, pc
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, PC), (IMMEDIATE, 0x23c), NONE]);
+    for _ in 0..3 {
+        emit(&mut program, Operation::Mov, [(DIRECT, R1), (IMMEDIATE, 1), NONE]);
+    }
+    emit(&mut program, Operation::Mov, [(DIRECT, R2), (DIRECT, PC), NONE]);
+    let mut cpu = load(&program);
+
+    // Lands on the target itself, not the instruction after it
+    step(&mut cpu, 1);
+    assert_eq!(cpu.program_counter, 0x23c);
+
+    // Reading PC gives the address of the instruction doing the reading
+    step(&mut cpu, 1);
+    assert_eq!(cpu.registers.r1, 0);
+    assert_eq!(cpu.registers.r2, 0x23c);
+}
+
+#[test]
+fn writing_sr_keeps_interrupt_state() {
+    // mov sr, #$ff
+    // mov r0, sr
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, SR), (IMMEDIATE, 0xff), NONE]);
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (DIRECT, SR), NONE]);
+
+    let mut cpu = load(&program);
+    step(&mut cpu, 2);
+    let status = cpu.status_register;
+    assert!(status.negative && status.overflow && status.interrupt_disable && status.zero && status.carry);
+    assert!(!status.interrupt && !status.double_fault);
+    assert_eq!(cpu.registers.r0, 0b1100_0111);
+
+    // mov sr, #0, from inside a handler after a double fault
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, SR), (IMMEDIATE, 0), NONE]);
+    let mut cpu = load(&program);
+    cpu.status_register.interrupt = true;
+    cpu.status_register.double_fault = true;
+    cpu.status_register.carry = true;
+    step(&mut cpu, 1);
+    assert!(cpu.status_register.interrupt && cpu.status_register.double_fault);
+    assert!(!cpu.status_register.carry);
+}
+
+#[test]
+fn writing_sp() {
+    // mov sp, #$00012340
+    // mov r0, sp
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, SP), (IMMEDIATE, 0x0001_2340), NONE]);
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (DIRECT, SP), NONE]);
+    let mut cpu = load(&program);
+    step(&mut cpu, 2);
+
+    assert_eq!(cpu.stack_pointer, 0x0001_2340);
+    assert_eq!(cpu.registers.r0, 0x0001_2340);
+}
+
+#[test]
+fn cid_is_read_only() {
+    // mov r0, cid
+    // mov cid, #1
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (DIRECT, CID), NONE]);
+    emit(&mut program, Operation::Mov, [(DIRECT, CID), (IMMEDIATE, 1), NONE]);
+    let mut cpu = load(&program);
+    cpu.registers.r0 = 0xffff_ffff;
+
+    assert!(matches!(run_to_interrupt(&mut cpu), Interrupt::IllegalMemory));
+    assert_eq!(cpu.registers.r0, 0);
+    assert_eq!(cpu.core_id, 0);
+}
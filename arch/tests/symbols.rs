@@ -0,0 +1,88 @@
+#![cfg(feature = "alloc")]
+// Checks symbol map round trips and that stack traces name addresses after the labels they fall under.
+
+use vixen::core::{Interrupt, StackTrace, SymbolLookup, SymbolTable};
+use vixen::cpu::SystemStack;
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{divide_by_zero, run_to_interrupt};
+
+fn table() -> SymbolTable {
+    let mut symbols = SymbolTable::new();
+    symbols.insert(0x21e, "divide");
+    symbols.insert(0x200, "start");
+    symbols.insert(0x200, "main");
+    symbols
+}
+
+#[test]
+fn resolves_nearest_label_below() {
+    let symbols = table();
+
+    assert_eq!(symbols.lookup(0x1ff), None);
+    assert_eq!(symbols.lookup(0x200).unwrap().to_string(), "main");
+    assert_eq!(symbols.lookup(0x20f).unwrap().to_string(), "main+0xf");
+    assert_eq!(symbols.lookup(0x22d).unwrap().to_string(), "divide+0xf");
+
+    let firmware = [(0x200, "reset"), (0x400, "idle")];
+    assert_eq!(firmware.as_slice().lookup(0x410).unwrap().to_string(), "idle+0x10");
+}
+
+#[test]
+fn stops_at_the_end_of_the_image() {
+    let mut symbols = table();
+    symbols.insert_end(0x23c);
+
+    assert_eq!(symbols.lookup(0x22d).unwrap().to_string(), "divide+0xf");
+    assert_eq!(symbols.lookup(0x23b).unwrap().to_string(), "divide+0x1d");
+    assert_eq!(symbols.lookup(0x23c), None);
+    assert_eq!(symbols.lookup(0xffff_ffff), None);
+
+    // A second image further up keeps its own labels
+    let mut program = SymbolTable::new();
+    program.insert(0x0010_0000, "entry");
+    program.insert_end(0x0010_0010);
+    symbols.merge(&program);
+
+    assert_eq!(symbols.lookup(0x0010_000f).unwrap().to_string(), "entry+0xf");
+    assert_eq!(symbols.lookup(0x0010_0010), None);
+    assert_eq!(symbols.lookup(0x0008_0000), None);
+    assert_eq!(symbols.lookup(0x200).unwrap().to_string(), "main");
+}
+
+#[test]
+fn map_round_trips() {
+    let map = table().to_map();
+    assert_eq!(map, "00000200 main\n00000200 start\n0000021e divide\n");
+
+    let parsed = SymbolTable::parse_map(&map).unwrap();
+    assert_eq!(parsed.iter().collect::<Vec<_>>(), table().iter().collect::<Vec<_>>());
+
+    let error = SymbolTable::parse_map("00000200 main\nnot-an-address main\n").unwrap_err();
+    assert_eq!(error.line, 2);
+}
+
+#[test]
+fn stack_trace_is_symbolicated() {
+    // main: mov r0, #1
+    //       div r0, r0, #0
+    let mut cpu = CPU::new(MEMORY_1M);
+    cpu.load_rom(&divide_by_zero()).unwrap();
+    cpu.system_stack.clear();
+    // A frame left behind by an earlier call out of main
+    cpu.system_stack_push_word(0).unwrap();
+    cpu.system_stack_push_word(0x200).unwrap();
+
+    let interrupt = run_to_interrupt(&mut cpu);
+    assert!(matches!(interrupt, Interrupt::DivideByZero));
+
+    let symbols = table();
+    let trace = StackTrace::new(interrupt, &cpu).with_symbols(&symbols).to_string();
+    assert!(trace.contains("At 0000020f <main+0xf> ("), "{trace}");
+    assert!(trace.lines().any(|line| line.starts_with("->  00000200") && line.ends_with("main")), "{trace}");
+
+    let plain = StackTrace::new(interrupt, &cpu).to_string();
+    assert!(plain.contains("At 0000020f ("), "{plain}");
+    assert!(plain.lines().any(|line| line.starts_with("->  00000200") && line.ends_with("??")), "{plain}");
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "alloc")]
+// Checks that the trapping arithmetic instructions fault precisely on signed overflow.
+
+use vixen::core::instruction::Operation;
+use vixen::core::{Interrupt, StackTrace};
+use vixen::models::JOHTO;
+use vixen::{CPU, MEMORY_1M};
+
+mod common;
+use common::{DIRECT, IMMEDIATE, NONE, R0, R1, emit};
+
+// mov r1, #lhs
+// <operation> r0, r1, #rhs
+// Runs the pair and returns the interrupt it ended with, if any
+fn run(operation: Operation, lhs: u32, rhs: u32) -> (CPU<'static>, Option<Interrupt>) {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (IMMEDIATE, lhs), NONE]);
+    emit(&mut rom, operation, [(DIRECT, R0), (DIRECT, R1), (IMMEDIATE, rhs)]);
+
+    let mut cpu = CPU::new_with_model(MEMORY_1M, &JOHTO);
+    cpu.load_rom(&rom).unwrap();
+    cpu.registers.r0 = 0x5555_5555;
+
+    for _ in 0..2 {
+        if let Err(interrupt) = cpu.tick() {
+            return (cpu, Some(interrupt));
+        }
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+    }
+
+    (cpu, None)
+}
+
+#[test]
+fn signed_overflow_traps_without_writing_back() {
+    let cases = [
+        (Operation::Addv, 0x7fff_ffff, 1),
+        (Operation::Subv, 0x8000_0000, 1),
+        (Operation::Mulv, 0x0001_0000, 0x0001_0000)
+    ];
+
+    for (operation, lhs, rhs) in cases {
+        let (cpu, interrupt) = run(operation, lhs, rhs);
+        let interrupt = interrupt.unwrap_or_else(|| panic!("{operation} did not trap"));
+
+        assert_eq!(u32::from(interrupt), 0x15, "{operation}");
+        assert_eq!(cpu.program_counter, 0x20f, "{operation} should fault at itself");
+        assert_eq!(cpu.registers.r0, 0x5555_5555, "{operation} wrote its result");
+        assert!(!cpu.status_register.overflow, "{operation} changed flags");
+
+        let trace = StackTrace::new(interrupt, &cpu).to_string();
+        assert!(trace.contains("15 (Arithmetic overflow)"), "{trace}");
+        assert!(trace.contains("At 0000020f ("), "{trace}");
+        assert!(trace.contains(&format!("{operation} r0, r1")), "{trace}");
+    }
+}
+
+#[test]
+fn unsigned_carry_does_not_trap() {
+    let cases = [
+        (Operation::Addv, 0xffff_ffff, 1, 0),
+        (Operation::Subv, 0, 1, 0xffff_ffff),
+        (Operation::Mulv, 0xffff_ffff, 0xffff_ffff, 1)
+    ];
+
+    for (operation, lhs, rhs, result) in cases {
+        let (cpu, interrupt) = run(operation, lhs, rhs);
+
+        assert!(interrupt.is_none(), "{operation} trapped");
+        assert_eq!(cpu.registers.r0, result, "{operation}");
+        assert!(cpu.status_register.carry, "{operation}");
+        assert!(!cpu.status_register.overflow, "{operation}");
+    }
+}
@@ -1,5 +1,7 @@
 use std::collections::VecDeque;
 
+use vixen::core::instruction::Operation;
+
 use crate::models::Instruction;
 
 mod instruction;
@@ -12,7 +14,11 @@ pub trait Compile {
 #[derive(Debug)]
 #[allow(clippy::module_name_repetitions)]
 pub enum CompilerError {
-    TooManyOperands,
+    // Operation, number of operands given
+    WrongOperandCount(Operation, usize),
+    // Operation, index of the operand
+    ImmediateDestination(Operation, usize),
+    NotAnAddress(Operation, usize),
 }
 
 #[derive(Default)]
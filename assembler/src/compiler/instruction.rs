@@ -1,4 +1,4 @@
-use vixen::core::instruction::Addressing;
+use vixen::core::instruction::{Addressing, OperandRole};
 
 use crate::models::{Instruction, Operand};
 
@@ -6,8 +6,21 @@ use super::{Compile, Compiler, CompilerError};
 
 impl Compile for Instruction {
     fn compile(self, compiler: &mut Compiler) -> Result<(), CompilerError> {
-        if self.operands.len() > 3 {
-            return Err(CompilerError::TooManyOperands);
+        let roles = self.operation.operands();
+        if self.operands.len() != roles.len() {
+            return Err(CompilerError::WrongOperandCount(self.operation, self.operands.len()));
+        }
+
+        for (i, (operand, role)) in self.operands.iter().zip(roles).enumerate() {
+            let addressing = operand.get_addressing();
+
+            if role.is_written() && addressing == Addressing::Immediate {
+                return Err(CompilerError::ImmediateDestination(self.operation, i));
+            }
+
+            if *role == OperandRole::Address && matches!(addressing, Addressing::Immediate | Addressing::Direct) {
+                return Err(CompilerError::NotAnAddress(self.operation, i));
+            }
         }
 
         // Move by 3 nibbles to leave room for addressing modes
@@ -57,7 +70,7 @@ impl Compile for Instruction {
 mod test {
     use vixen::core::{instruction::Operation, registers::RegisterId};
 
-    use crate::{models::Operand, parser::Program};
+    use crate::{models::{Address, Operand}, parser::Program};
 
     use super::*;
 
@@ -94,4 +107,40 @@ mod test {
 
         assert_eq!(result, expected);
     }
+
+    fn compile(operation: Operation, operands: Vec<Operand>) -> Result<Vec<u8>, CompilerError> {
+        let mut program = Program::default();
+        program.instructions.push_back(Instruction { operation, operands });
+        Compiler::default().compile(program.instructions)
+    }
+
+    #[test]
+    fn test_wrong_operand_count() {
+        let result = compile(Operation::Add, vec![Operand::Register(RegisterId::R0), Operand::Literal(1)]);
+        assert!(matches!(result, Err(CompilerError::WrongOperandCount(Operation::Add, 2))));
+
+        let result = compile(Operation::Nop, vec![Operand::Literal(1)]);
+        assert!(matches!(result, Err(CompilerError::WrongOperandCount(Operation::Nop, 1))));
+    }
+
+    #[test]
+    fn test_immediate_destination() {
+        let result = compile(Operation::Mov, vec![Operand::Literal(1), Operand::Register(RegisterId::R0)]);
+        assert!(matches!(result, Err(CompilerError::ImmediateDestination(Operation::Mov, 0))));
+
+        let result = compile(Operation::Xchg, vec![Operand::Register(RegisterId::R0), Operand::Literal(1)]);
+        assert!(matches!(result, Err(CompilerError::ImmediateDestination(Operation::Xchg, 1))));
+    }
+
+    #[test]
+    fn test_not_an_address() {
+        let result = compile(Operation::Jmp, vec![Operand::LabelLiteral("main".into())]);
+        assert!(matches!(result, Err(CompilerError::NotAnAddress(Operation::Jmp, 0))));
+
+        let result = compile(Operation::Jz, vec![Operand::Register(RegisterId::R0)]);
+        assert!(matches!(result, Err(CompilerError::NotAnAddress(Operation::Jz, 0))));
+
+        let result = compile(Operation::Jmp, vec![Operand::Address(Address::Absolute(0x200))]);
+        assert!(result.is_ok());
+    }
 }
@@ -8,21 +8,61 @@ pub mod preprocessor;
 use std::path::Path;
 
 use error::Error;
-use preprocessor::ProcessedProgram;
+use preprocessor::{Preprocessor, ProcessedProgram};
+use vixen::core::{Executable, ExtensionSet, SymbolTable};
 
 pub fn assemble(source_path: &Path, source: &str) -> Result<Vec<u8>, Error> {
+    assemble_with_symbols(source_path, source).map(|(compiled, _)| compiled)
+}
+
+// Also returns every label with its final address, for symbolicated stack traces
+pub fn assemble_with_symbols(source_path: &Path, source: &str) -> Result<(Vec<u8>, SymbolTable), Error> {
+    assemble_at(source_path, source, Preprocessor::START_OF_BOOT_ROM)
+}
+
+// For programs loaded somewhere other than the start of the boot ROM, like one started by a BIOS
+pub fn assemble_at(source_path: &Path, source: &str, origin: u32) -> Result<(Vec<u8>, SymbolTable), Error> {
+    assemble_program(source_path, source, origin).map(|(compiled, symbols, _)| (compiled, symbols))
+}
+
+// The program as an executable starting at `origin`, carrying its symbols and the extensions it uses
+pub fn assemble_executable(source_path: &Path, source: &str, origin: u32) -> Result<Executable, Error> {
+    let (compiled, symbols, extensions) = assemble_program(source_path, source, origin)?;
+
+    Ok(Executable {
+        extensions,
+        symbols: Some(symbols),
+        debug: Some(format!("source {}", source_path.display())),
+        ..Executable::new(origin, compiled)
+    })
+}
+
+fn assemble_program(source_path: &Path, source: &str, origin: u32) -> Result<(Vec<u8>, SymbolTable, ExtensionSet), Error> {
     let tokens = scanner::Scanner::new(source).scan()?;
     let program = parser::Parser::new(tokens).parse()?;
-    let program = preprocessor::Preprocessor::process(source_path, program, false)?;
+    let program = preprocessor::Preprocessor::process(source_path, program, false, origin)?;
+
+    let mut symbols = SymbolTable::new();
+    for (label, offset) in &program.labels {
+        let offset = u32::try_from(*offset).expect("Label address is too high for ROM");
+        symbols.insert(Preprocessor::offset_label(origin, offset), label);
+    }
+
+    let extensions = program.instructions.iter()
+        .filter_map(|instruction| instruction.operation.extension())
+        .fold(ExtensionSet::empty(), ExtensionSet::with);
+
     let compiled = compiler::Compiler::default().compile(program.instructions)?;
+    let length = u32::try_from(compiled.len()).expect("ROM is too large for the address space");
+    symbols.insert_end(origin.saturating_add(length));
 
-    Ok(compiled)
+    Ok((compiled, symbols, extensions))
 }
 
-pub fn compile_for_include(source_path: &Path, source: &str) -> Result<ProcessedProgram, Error> {
+pub fn compile_for_include(source_path: &Path, source: &str, origin: u32) -> Result<ProcessedProgram, Error> {
     let tokens = scanner::Scanner::new(source).scan()?;
     let program = parser::Parser::new(tokens).parse()?;
-    let program = preprocessor::Preprocessor::process(source_path, program, true)?;
+    let program = preprocessor::Preprocessor::process(source_path, program, true, origin)?;
 
     Ok(program)
 }
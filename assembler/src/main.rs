@@ -1,23 +1,62 @@
 use std::fs;
 use std::path::PathBuf;
 use std::process::exit;
-use vasm::assemble;
+use vasm::{assemble_at, assemble_executable};
+use vixen::core::CpuModel;
+use vixen::models::find_model;
 use clap::Parser;
 use vasm::error::Result;
 
 /// A code assembler for Vixen processors
+///
+/// Writes a Vixen executable carrying its load address, entry point, required CPU extensions and
+/// symbols, which vemu, vdbg and vdas all load. `--raw` writes a headerless ROM instead.
 #[derive(Parser, Debug)]
 #[command(about)]
 struct Args {
     pub source: PathBuf,
     pub destination: PathBuf,
+    /// Write a symbol map of label addresses, loaded by vemu and vdbg for stack traces
+    #[arg(long)]
+    pub symbols: Option<PathBuf>,
+    /// Hexadecimal address the program is loaded at, for programs started by a BIOS
+    #[arg(long, value_name = "ADDRESS", default_value = "200", value_parser = parse_address)]
+    pub origin: u32,
+    /// Write the bare machine code, e.g. for firmware loaded by something that doesn't know the
+    /// executable format
+    #[arg(long)]
+    pub raw: bool,
+    /// Only let the executable run on this CPU model, e.g. `kanto`
+    #[arg(long, conflicts_with = "raw", value_parser = parse_model)]
+    pub model: Option<&'static CpuModel>,
+}
+
+fn parse_model(value: &str) -> std::result::Result<&'static CpuModel, String> {
+    find_model(value).ok_or_else(|| format!("unknown CPU model `{value}`"))
+}
+
+fn parse_address(value: &str) -> std::result::Result<u32, String> {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("`{value}` is not a hexadecimal address"))
 }
 
 fn run_assembler(args: &Args) -> Result<()> {
     let mut source = fs::read_to_string(&args.source)?;
     source.push('\n');
-    let program = assemble(&args.source, &source)?;
-    fs::write(&args.destination, program)?;
+    let symbols = if args.raw {
+        let (program, symbols) = assemble_at(&args.source, &source, args.origin)?;
+        fs::write(&args.destination, program)?;
+        symbols
+    } else {
+        let mut executable = assemble_executable(&args.source, &source, args.origin)?;
+        executable.model = args.model;
+        fs::write(&args.destination, executable.to_bytes())?;
+        executable.symbols.unwrap_or_default()
+    };
+
+    if let Some(path) = &args.symbols {
+        fs::write(path, symbols.to_map())?;
+    }
 
     println!("Compiled program {} to {}", args.source.display(), args.destination.display());
     Ok(())
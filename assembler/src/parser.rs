@@ -10,7 +10,6 @@ mod instruction;
 mod label;
 mod r#macro;
 mod operand;
-mod operation;
 mod program;
 
 pub use constant::Constant;
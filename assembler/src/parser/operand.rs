@@ -105,6 +105,12 @@ fn get_register(register: &str) -> Option<RegisterId> {
         "R12" => Some(RegisterId::R12),
         "R13" => Some(RegisterId::R13),
         "R14" => Some(RegisterId::R14),
+        "SP" => Some(RegisterId::Sp),
+        "PC" => Some(RegisterId::Pc),
+        "SR" => Some(RegisterId::Sr),
+        "PTBR" => Some(RegisterId::Ptbr),
+        "PFAR" => Some(RegisterId::Pfar),
+        "CID" => Some(RegisterId::Cid),
         _ => None
     }
 }
@@ -6,7 +6,6 @@ use crate::models::token::Literal;
 use crate::models::{Instruction, Token};
 
 use super::label::Label;
-use super::operation::OperationExt;
 use super::r#macro::MacroDefinition;
 use super::{Constant, FromTokenStream, ParseError, Parser};
 
@@ -93,7 +92,8 @@ fn identifier(
         }
 
         _ => {
-            let operation = Operation::parse(identifier)?;
+            let operation = Operation::from_mnemonic(&identifier)
+                .ok_or(ParseError::InvalidInstruction(identifier))?;
             let instruction = Instruction::parse(operation, parser)?;
 
             instructions.push_back(instruction);
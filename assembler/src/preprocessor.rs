@@ -30,10 +30,12 @@ pub struct ProcessedProgram {
 
 impl Preprocessor {
     // See: https://github.com/floofi-org/vixen/wiki/Memory-management-and-registers#memory-map
-    const START_OF_BOOT_ROM: u32 = 0x0000_0200;
+    pub const START_OF_BOOT_ROM: u32 = 0x0000_0200;
     const INSTRUCTION_SIZE: u32 = 15;
 
-    pub fn process(source_path: &Path, program: Program, included: bool) -> Result<ProcessedProgram, PreprocessorError> {
+    // `origin` is the address the program gets loaded at, the start of the boot ROM unless it is
+    // meant to be started by something else
+    pub fn process(source_path: &Path, program: Program, included: bool, origin: u32) -> Result<ProcessedProgram, PreprocessorError> {
         let Program { constants, labels, macros, instructions } = program;
         let mut processed = ProcessedProgram {
             constants,
@@ -43,7 +45,7 @@ impl Preprocessor {
 
         for (definition, offset) in macros {
             let r#macro: Macro = definition.try_into()?;
-            r#macro.apply(source_path, &mut processed, offset)?;
+            r#macro.apply(source_path, &mut processed, offset, origin)?;
         }
 
         // If we're getting included, stop here and don't substitute anything
@@ -60,8 +62,8 @@ impl Preprocessor {
             match operand {
                 Operand::ConstantLiteral(c) => *operand = Self::transform_constant(&processed.constants, c, true)?,
                 Operand::ConstantAddress(c) => *operand = Self::transform_constant(&processed.constants, c, false)?,
-                Operand::Label(label) => *operand = Self::transform_label(&processed.labels, label)?,
-                Operand::LabelLiteral(label) => *operand = Self::transform_label_literal(&processed.labels, label)?,
+                Operand::Label(label) => *operand = Self::transform_label(&processed.labels, label, origin)?,
+                Operand::LabelLiteral(label) => *operand = Self::transform_label_literal(&processed.labels, label, origin)?,
                 _ => {}
             }
         }
@@ -83,7 +85,7 @@ impl Preprocessor {
         Ok(operand)
     }
 
-    fn transform_label(labels: &HashMap<String, usize>, label: &str) -> Result<Operand, PreprocessorError> {
+    fn transform_label(labels: &HashMap<String, usize>, label: &str, origin: u32) -> Result<Operand, PreprocessorError> {
         let address = *labels
             .get(label)
             .ok_or_else(|| PreprocessorError::NoSuchLabel(label.to_owned()))?;
@@ -91,28 +93,28 @@ impl Preprocessor {
         let address: u32 = address.try_into()
             .expect("Label address is too high for ROM");
 
-        Ok(Self::get_label_address(address))
+        Ok(Self::get_label_address(origin, address))
     }
 
 
-    fn transform_label_literal(labels: &HashMap<String, usize>, label: &str) -> Result<Operand, PreprocessorError> {
+    fn transform_label_literal(labels: &HashMap<String, usize>, label: &str, origin: u32) -> Result<Operand, PreprocessorError> {
         let address = *labels
             .get(label)
             .ok_or_else(|| PreprocessorError::NoSuchLabel(label.to_owned()))?;
 
         let address: u32 = address.try_into()
-            .map(Self::offset_label)
+            .map(|offset| Self::offset_label(origin, offset))
             .expect("Label address is too high for ROM");
 
         Ok(Operand::Literal(address))
     }
 
-    fn get_label_address(offset: u32) -> Operand {
-        let address = Self::offset_label(offset);
+    fn get_label_address(origin: u32, offset: u32) -> Operand {
+        let address = Self::offset_label(origin, offset);
         Operand::Address(Address::Absolute(address))
     }
 
-    fn offset_label(offset: u32) -> u32 {
-        Self::START_OF_BOOT_ROM + offset * Self::INSTRUCTION_SIZE
+    pub(crate) fn offset_label(origin: u32, offset: u32) -> u32 {
+        origin + offset * Self::INSTRUCTION_SIZE
     }
 }
@@ -14,31 +14,30 @@ pub enum Macro {
 }
 
 impl Macro {
-    // See: https://github.com/floofi-org/vixen/wiki/Memory-management-and-registers#memory-map
-    const START_OF_BOOT_ROM: u32 = 0x0000_0200;
-
     // See: https://github.com/floofi-org/vixen/wiki/Interrupts-and-faults
     const INTERRUPT_HANDLER_ADDRESS: u32 = 0x0450_0200;
     const DOUBLE_FAULT_HANDLER_ADDRESS: u32 = 0x0450_0204;
 
     #[allow(clippy::unit_arg)]
-    pub fn apply(self, source_path: &Path, program: &mut ProcessedProgram, instruction_offset: usize) -> Result<(), PreprocessorError> {
+    pub fn apply(self, source_path: &Path, program: &mut ProcessedProgram, instruction_offset: usize,
+                 origin: u32) -> Result<(), PreprocessorError> {
         match self {
-            Self::Interrupt => Ok(Self::interrupt(program, instruction_offset)),
-            Self::DoubleFault => Ok(Self::double_fault(program, instruction_offset)),
-            Self::Include(path) => Self::include(program, instruction_offset, source_path, path),
+            Self::Interrupt => Ok(Self::interrupt(program, instruction_offset, origin)),
+            Self::DoubleFault => Ok(Self::double_fault(program, instruction_offset, origin)),
+            Self::Include(path) => Self::include(program, instruction_offset, source_path, path, origin),
         }
     }
 
-    fn interrupt(program: &mut ProcessedProgram, instruction_offset: usize) {
-        Self::define_handler(program, Self::INTERRUPT_HANDLER_ADDRESS, instruction_offset);
+    fn interrupt(program: &mut ProcessedProgram, instruction_offset: usize, origin: u32) {
+        Self::define_handler(program, Self::INTERRUPT_HANDLER_ADDRESS, instruction_offset, origin);
     }
 
-    fn double_fault(program: &mut ProcessedProgram, instruction_offset: usize) {
-        Self::define_handler(program, Self::DOUBLE_FAULT_HANDLER_ADDRESS, instruction_offset);
+    fn double_fault(program: &mut ProcessedProgram, instruction_offset: usize, origin: u32) {
+        Self::define_handler(program, Self::DOUBLE_FAULT_HANDLER_ADDRESS, instruction_offset, origin);
     }
 
-    fn include(program: &mut ProcessedProgram, instruction_offset: usize, source_path: &Path, path: PathBuf) -> Result<(), PreprocessorError> {
+    fn include(program: &mut ProcessedProgram, instruction_offset: usize, source_path: &Path, path: PathBuf,
+               origin: u32) -> Result<(), PreprocessorError> {
         let source_path = source_path.parent().unwrap().join(path);
         let source = match std::fs::read_to_string(&source_path) {
             Ok(s) => s,
@@ -47,7 +46,7 @@ impl Macro {
             }
         };
 
-        let mut included = match crate::compile_for_include(&source_path, &source) {
+        let mut included = match crate::compile_for_include(&source_path, &source, origin) {
             Ok(s) => s,
             Err(e) => {
                 return Err(PreprocessorError::IncludeCompileError(source_path, Box::new(e)));
@@ -74,12 +73,12 @@ impl Macro {
     }
 
     #[allow(clippy::cast_possible_truncation)]
-    fn define_handler(program: &mut ProcessedProgram, setup_address: u32, handler_offset: usize) {
+    fn define_handler(program: &mut ProcessedProgram, setup_address: u32, handler_offset: usize, origin: u32) {
         let mov = Instruction {
             operation: Operation::Mov,
             operands: vec![
                 Operand::Address(Address::Absolute(setup_address)),
-                Operand::Literal(Self::START_OF_BOOT_ROM + handler_offset as u32),
+                Operand::Literal(origin + handler_offset as u32),
             ],
         };
 
@@ -0,0 +1,33 @@
+// Every program shipped with the repository must keep assembling as the instruction table changes.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn sources(directory: &Path, found: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(directory).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            sources(&path, found);
+        } else if path.extension().is_some_and(|extension| extension == "asm") {
+            found.push(path);
+        }
+    }
+}
+
+#[test]
+fn samples_and_bios_assemble() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let mut found = Vec::new();
+    sources(&root.join("samples"), &mut found);
+    sources(&root.join("bios"), &mut found);
+    assert!(!found.is_empty());
+
+    for path in found {
+        // As vasm reads them, since a file may end without a newline
+        let mut source = fs::read_to_string(&path).unwrap();
+        source.push('\n');
+        if let Err(error) = vasm::assemble(&path, &source) {
+            panic!("{}: {error}", path.display());
+        }
+    }
+}
@@ -3,4 +3,6 @@ CONFIG_TTY_STDIN  = $04000204          ; Memory address of terminal stdin
 CONFIG_TTY_BUFFER = $04000208          ; Memory address of terminal buffer
 
 CONFIG_CPUNAME_LENGTH = $00000000      ; Memory address of CPU name length
-CONFIG_CPUNAME_START = $00000001       ; Memory address of CPU name block
\ No newline at end of file
+CONFIG_CPUNAME_START = $00000001       ; Memory address of CPU name block
+
+CONFIG_PROGRAM_START = $00100000       ; Memory address the program image is loaded at
\ No newline at end of file
@@ -1,3 +1,5 @@
+.include "config.asm"
+
 bios_cpuname:
     and r1, {CONFIG_CPUNAME_LENGTH}, #$FF ; CPU name length, AND to take only one byte
     mov r2, #{CONFIG_CPUNAME_START} ; Start of the CPU name
@@ -1,3 +1,5 @@
+.include "config.asm"
+
 bios_header:
     mov {CONFIG_TTY_STDOUT}, #'V'
     mov {CONFIG_TTY_STDOUT}, #'i'
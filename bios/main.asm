@@ -5,6 +5,8 @@ CONFIG_TTY_BUFFER = $04000208          ; Memory address of terminal buffer
 CONFIG_CPUNAME_LENGTH = $00000000      ; Memory address of CPU name length
 CONFIG_CPUNAME_START = $00000001       ; Memory address of CPU name block
 
+CONFIG_PROGRAM_START = $00100000       ; Memory address the program image is loaded at
+
 main:
     jmp bios_header
     jmp bios_cpuname
@@ -104,6 +106,12 @@ bios_memorycheck:
     ret
 
 bios_enter:
+    ; Boot the program image if there is one, otherwise wait here
+    cmp {CONFIG_PROGRAM_START}, #0
+    jz bios_enter_prompt
+    jmpl {CONFIG_PROGRAM_START}
+
+bios_enter_prompt:
     mov {CONFIG_TTY_STDOUT}, #'P'
     mov {CONFIG_TTY_STDOUT}, #'r'
     mov {CONFIG_TTY_STDOUT}, #'e'
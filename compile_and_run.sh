@@ -2,7 +2,7 @@
 
 pushd $(dirname $0) > /dev/null
 
-cargo run -p vasm $1 rom.bin &&
+cargo run -p vasm -- $1 rom.bin --symbols rom.sym &&
 cargo run -p vemu rom.bin
 
 popd
@@ -1,9 +1,9 @@
 use std::process::exit;
 use vdbg::dump_memory;
 use vixen::{CPUResult, CPU};
-use vixen::core::instruction::{Addressing, Operation};
+use vixen::core::instruction::{Addressing, FlagEffect, Operation};
 use vixen::core::{Instruction, Interrupt, MemoryCell, Operand, StackTrace};
-use vixen::cpu::Decoder;
+use vixen::cpu::{CacheConfig, CacheStatistics, Decoder};
 use crate::DebuggerState;
 
 pub fn help() {
@@ -18,7 +18,10 @@ pub fn help() {
     println!("  registers    -- Display current system registers (shorthand: g)");
     println!("  location     -- Show program location in memory (shorthand: l)");
     println!("  expand       -- Expand a binary instruction (shorthand: e)");
+    println!("  devices      -- List attached devices and their state (shorthand: d)");
     println!("  input        -- Write to stdin (shorthand: >)");
+    println!("  cache        -- Show cache statistics, 'cache on|off' to toggle (shorthand: c)");
+    println!("  opcode <op>  -- Describe an instruction by mnemonic (shorthand: o)");
     println!("  <hex addr>   -- Display memory address");
 }
 
@@ -28,7 +31,7 @@ pub fn step(state: &mut DebuggerState, cpu: &mut CPU) -> CPUResult<()> {
         println!("\u{1b}[33mSystem blocked on interrupt. 'i' for stack trace, 'b' to resume.\u{1b}[0m");
     } else {
         cpu.tick()?;
-        cpu.program_counter += 15;
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
         println!("\u{1b}[33mProgram at {:0>8x}: {}\u{1b}[0m",
                  cpu.program_counter, cpu.read_instruction_string(cpu.program_counter));
     }
@@ -42,7 +45,7 @@ pub fn unblock(state: &mut DebuggerState) {
 }
 
 pub fn jump(cpu: &mut CPU) {
-    cpu.program_counter -= 15;
+    cpu.program_counter = cpu.program_counter.wrapping_sub(15);
     println!("\u{1b}[33mProgram at {:0>8x}: {}\u{1b}[0m",
              cpu.program_counter, cpu.read_instruction_string(cpu.program_counter));
 }
@@ -77,14 +80,40 @@ pub fn registers(cpu: &mut CPU) {
              register1 = cpu.registers.r12, register2 = cpu.registers.r13, register3 = cpu.registers.r14);
 }
 
+#[allow(clippy::unnecessary_unwrap)]
 pub fn interrupt(state: &mut DebuggerState, cpu: &mut CPU) {
     if state.interrupt.is_some() {
-        println!("{}", StackTrace::new(state.interrupt.unwrap(), cpu));
+        let mut trace = StackTrace::new(state.interrupt.unwrap(), cpu);
+        if let Some(symbols) = &state.symbols {
+            trace = trace.with_symbols(symbols);
+        }
+        println!("{trace}");
     } else {
         println!("\u{1b}[33mSystem is not blocked.\u{1b}[0m");
     }
 }
 
+pub fn devices(state: &DebuggerState, cpu: &CPU) {
+    if let Some(core) = &state.core {
+        for device in &core.devices {
+            println!("{:0>8x}-{:0>8x}: {}", device.ports.start, device.ports.end, device.state);
+        }
+    } else {
+        for (ports, device) in cpu.io.devices() {
+            println!("{:0>8x}-{:0>8x}: {device:?}", ports.start, ports.end);
+        }
+    }
+}
+
+// Commands that would run the program or change the machine, refused on core dumps
+pub fn changes_state(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+    let command = words.next().unwrap_or_default();
+    matches!(command, "s" | "step" | "j" | "jump" | "r" | "run" | "b" | "unblock" | "input")
+        || line.starts_with('>')
+        || matches!(command, "c" | "cache") && words.next().is_some()
+}
+
 #[allow(clippy::cast_possible_truncation)]
 pub fn expand(cpu: &mut CPU) {
 
@@ -100,9 +129,13 @@ pub fn expand(cpu: &mut CPU) {
         }
         println!();
 
+        let roles = Operation::try_from(info.operation as u16)
+            .map_or(&[][..], Operation::operands);
+
         for (i, operand) in info.operands.iter().enumerate() {
             let mode = info.modes[i];
-            print!("    mem {mode:x}: ");
+            let role = roles.get(i).map_or("unused", |role| role.name());
+            print!("    mem {mode:x} ({role}): ");
 
             if let Ok(decoded_mode) = Addressing::try_from(mode as u8) {
                 println!("{decoded_mode:?}");
@@ -131,6 +164,80 @@ pub fn expand(cpu: &mut CPU) {
     }
 }
 
+pub fn cache(cpu: &mut CPU, line: &str) {
+    let argument = line.split_whitespace().nth(1);
+
+    match argument {
+        Some("on") => {
+            let config = Some(CacheConfig::default());
+            // The default configuration is always valid
+            let _ = cpu.caches.set_instruction_cache(config);
+            let _ = cpu.caches.set_data_cache(config);
+            println!("\u{1b}[33mCaches enabled.\u{1b}[0m");
+        },
+        Some("off") => {
+            let _ = cpu.caches.set_instruction_cache(None);
+            let _ = cpu.caches.set_data_cache(None);
+            println!("\u{1b}[33mCaches disabled.\u{1b}[0m");
+        },
+        Some(_) => println!("\u{1b}[33mUsage: cache [on|off]\u{1b}[0m"),
+        None => {
+            println!("cycles = {}", cpu.cycles);
+            print_cache_statistics("icache", cpu.caches.instruction_statistics());
+            print_cache_statistics("dcache", cpu.caches.data_statistics());
+        }
+    }
+}
+
+fn print_cache_statistics(name: &str, statistics: Option<CacheStatistics>) {
+    if let Some(statistics) = statistics {
+        println!("{name}: {} hits, {} misses ({:.2}% hit rate), {} writebacks, {} memory writes",
+                 statistics.hits, statistics.misses, statistics.hit_rate() * 100.0,
+                 statistics.writebacks, statistics.memory_writes);
+    } else {
+        println!("{name}: disabled");
+    }
+}
+
+pub fn opcode(cpu: &CPU, line: &str) {
+    let Some(name) = line.split_whitespace().nth(1) else {
+        println!("\u{1b}[33mUsage: opcode <mnemonic>\u{1b}[0m");
+        return;
+    };
+
+    let Some(operation) = Operation::from_mnemonic(name) else {
+        println!("\u{1b}[33mUnknown instruction '{name}'.\u{1b}[0m");
+        return;
+    };
+
+    let info = operation.info();
+    let extension = info.extension.map_or("<none>", |extension| extension.name());
+    let roles: Vec<&str> = info.operands.iter().map(|role| role.name()).collect();
+    let supported = if cpu.model.supports(operation) { "yes" } else { "no" };
+
+    println!("{} ({:0>3x}) -- {extension}", info.mnemonic, info.opcode);
+    if !info.aliases.is_empty() {
+        println!("  aliases:   {}", info.aliases.join(", "));
+    }
+    println!("  operands:  {}", if roles.is_empty() { "<none>".to_owned() } else { roles.join(", ") });
+    println!("  flags:     n={} o={} i={} z={} c={}",
+             flag_effect(info.flags.negative), flag_effect(info.flags.overflow),
+             flag_effect(info.flags.interrupt_disable), flag_effect(info.flags.zero),
+             flag_effect(info.flags.carry));
+    println!("  supported: {supported}");
+}
+
+fn flag_effect(effect: FlagEffect) -> &'static str {
+    match effect {
+        FlagEffect::Unaffected => "-",
+        FlagEffect::Set => "1",
+        FlagEffect::Cleared => "0",
+        FlagEffect::Result => "result",
+        FlagEffect::Computed => "computed",
+        FlagEffect::Restored => "restored"
+    }
+}
+
 pub fn input(state: &mut DebuggerState, line: &str) {
     let line = line.trim_start_matches('>').trim();
     state.stdin.write(line);
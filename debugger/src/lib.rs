@@ -1,5 +1,6 @@
 use vixen::CPU;
 
+#[allow(clippy::unnecessary_min_or_max)]
 pub fn dump_memory(cpu: &mut CPU, start: usize, end: usize, focus: Option<usize>) {
     let start = start.max(0);
     let end = end.min(0xffff_ffff);
@@ -10,11 +11,12 @@ pub fn dump_memory(cpu: &mut CPU, start: usize, end: usize, focus: Option<usize>
         for _ in 0..16 {
             let focus_start = focus.unwrap_or(cpu.program_counter as usize);
             let focus_end = focus_start + if focus.is_some() { 4 } else { 15 };
-            match position {
-                x if x > 0xffff_fffe => (),
-                x if x == focus_end - 1 => print!("\u{1b}[43m{:0>2x}\u{1b}[0m ", cpu.memory[position]),
-                x if (focus_start..focus_end).contains(&x) => print!("\u{1b}[43m{:0>2x} ", cpu.memory[position]),
-                _ => print!("{:0>2x} ", cpu.memory[position])
+            match (position, cpu.memory.get(position)) {
+                (x, _) if x > 0xffff_fffe => (),
+                (_, None) => print!("?? "),
+                (x, Some(byte)) if x == focus_end - 1 => print!("\u{1b}[43m{byte:0>2x}\u{1b}[0m "),
+                (x, Some(byte)) if (focus_start..focus_end).contains(&x) => print!("\u{1b}[43m{byte:0>2x} "),
+                (_, Some(byte)) => print!("{byte:0>2x} ")
             }
             position += 1;
         }
@@ -1,14 +1,18 @@
 mod commands;
 
-use std::ffi::OsString;
+use std::path::{Path, PathBuf};
 use std::process::exit;
-use std::{env, fs, io};
+use std::{fs, io};
 use std::io::Write;
-use vixen::core::Interrupt;
-use vixen::{BusDevice, CPU, MEMORY_512M};
+use vixen::core::{CoreDump, Interrupt, SymbolTable};
+use vixen::{CPU, MEMORY_512M};
 use vixen::cpu::Decoder;
 use vixen::CPUResult;
-use vixen_devices::{RealTimeClock, Terminal};
+use vixen_devices::host;
+use vixen_devices::replay::Replayer;
+use vixen_devices::board::RomImage;
+use vixen_devices::{Board, PowerRequest, PowerSignal, Terminal};
+use clap::Parser;
 
 use stdin::DebuggerStdin;
 
@@ -18,17 +22,56 @@ struct DebuggerState {
     pub running: bool,
     pub interrupt: Option<Interrupt>,
     pub stdin: DebuggerStdin,
+    pub symbols: Option<SymbolTable>,
+    pub power: PowerSignal,
+    // Set when inspecting a core dump, which can be looked at but not run
+    pub core: Option<CoreDump>,
+    // Set while replaying a recording that the run hasn't left yet
+    pub replayer: Option<Replayer>,
+}
+
+/// A debugger for Vixen processors
+#[derive(Parser, Debug)]
+#[command(about)]
+struct Args {
+    /// Program loaded at 0x200, or at `--program-address` when booting a BIOS, unless it is a Vixen
+    /// executable saying where it goes. Optional when the machine file loads ROM images itself.
+    /// With `--core` only its symbols are used.
+    #[arg(required_unless_present_any = ["machine", "core", "bios"])]
+    pub rom: Option<PathBuf>,
+    /// Firmware loaded at 0x200 and started instead of the program, which it jumps to once done
+    #[arg(long, value_name = "FILE", conflicts_with = "core")]
+    pub bios: Option<PathBuf>,
+    /// Hexadecimal address the program is loaded at when booting a BIOS
+    #[arg(long, value_name = "ADDRESS", requires = "bios", default_value = "100000", value_parser = parse_address)]
+    pub program_address: u32,
+    /// Machine description file setting up memory, CPU model, ROM images and devices
+    #[arg(long, value_name = "FILE")]
+    pub machine: Option<PathBuf>,
+    /// Inspect a core file written by `vemu --core-dump` instead of running a program
+    #[arg(long, value_name = "FILE", conflicts_with = "machine")]
+    pub core: Option<PathBuf>,
+    /// Feed the devices from a file written by `vemu --record`
+    #[arg(long, value_name = "FILE", conflicts_with = "core")]
+    pub replay: Option<PathBuf>,
+}
+
+fn parse_address(value: &str) -> Result<u32, String> {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("`{value}` is not a hexadecimal address"))
 }
 
 fn main() {
-    let path = get_rom_path().unwrap_or_else(|| {
-        eprintln!("\u{1b}[33mUsage: vdbg {{rom}}\u{1b}[0m");
-        eprintln!("\u{1b}[33mPlease provide path to ROM.\u{1b}[0m");
+    let args = Args::parse();
+    let symbols = host::load_all_symbols(args.bios.as_deref(), args.rom.as_deref(), args.program_address);
 
-        exit(-1);
-    });
+    if let Some(path) = &args.core {
+        debug_core(path, symbols);
+        return;
+    }
 
-    let rom = fs::read(path);
+    // With a BIOS, the BIOS takes the program's place at 0x200 and the program becomes an image
+    let rom = args.bios.as_ref().or(args.rom.as_ref()).map_or(Ok(Vec::new()), fs::read);
     let rom = rom.unwrap_or_else(|e| {
         eprintln!("\u{1b}[33mFailed to read ROM file: {e}\u{1b}[0m");
         exit(-1);
@@ -40,42 +83,85 @@ fn main() {
         exit(2);
     }
 
-    let mut cpu = CPU::new(MEMORY_512M);
-    if let Err(e) = cpu.load_rom(&rom) {
-        eprintln!("\u{1b}[33mFailed to load ROM into CPU: {e}\u{1b}[0m");
-        exit(2);
+    let mut board = match &args.machine {
+        Some(path) => Board::load(path).unwrap_or_else(|e| {
+            eprintln!("\u{1b}[33mFailed to read machine description: {e}\u{1b}[0m");
+            exit(2);
+        }),
+        None => Board {
+            memory: MEMORY_512M,
+            ..Board::default()
+        }
+    };
+    if let (Some(_), Some(path)) = (&args.bios, &args.rom) {
+        board.roms.push(RomImage { path: path.clone(), address: args.program_address });
     }
 
     let stdin = DebuggerStdin::new();
-
-    let devices: Vec<Box<dyn BusDevice>> = vec![
-        Box::new(Terminal::new(stdin.clone())),
-        Box::new(RealTimeClock::now())
-    ];
-
-    if let Err(e) = cpu.register_devices(devices) {
-        eprintln!("\u{1b}[33mFailed to start up devices: {e}\u{1b}[0m");
+    let power = PowerSignal::new();
+    let mut replayer = args.replay.as_deref().map(load_recording);
+    let wrap = |device| match &mut replayer {
+        Some(replayer) => replayer.wrap(device),
+        None => device,
+    };
+    let mut cpu = board.build_with(&rom, || Terminal::new(stdin.clone()), &power, wrap).unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to set up the machine: {e}\u{1b}[0m");
         exit(2);
-    }
+    });
 
     let mut state = DebuggerState {
         running: false,
         interrupt: None,
         stdin,
+        symbols,
+        power,
+        core: None,
+        replayer,
     };
 
-    debug_cpu(&mut state, &mut cpu, rom.len());
+    println!("\u{1b}[33mLoaded {} bytes of system ROM.\u{1b}[0m", rom.len());
+    debug_cpu(&mut state, &mut cpu, &board, &rom);
 }
 
-fn get_rom_path() -> Option<OsString> {
-    // Skip binary path
-    env::args_os().nth(1)
+fn debug_core(path: &Path, symbols: Option<SymbolTable>) {
+    let dump = fs::read(path).map_err(|e| e.to_string())
+        .and_then(|bytes| CoreDump::parse(&bytes).map_err(|e| e.to_string()))
+        .unwrap_or_else(|e| {
+            eprintln!("\u{1b}[33mFailed to read core dump {}: {e}\u{1b}[0m", path.display());
+            exit(2);
+        });
+
+    let mut cpu = dump.restore();
+    println!("\u{1b}[33mCore dump of a {} CPU with {} bytes of memory, stopped by interrupt {} after {} cycles. \
+    Read-only.\u{1b}[0m", dump.model.short_name, dump.memory_size, dump.interrupt, dump.cycles);
+
+    let mut state = DebuggerState {
+        running: false,
+        interrupt: Some(dump.interrupt),
+        stdin: DebuggerStdin::new(),
+        symbols,
+        power: PowerSignal::new(),
+        core: Some(dump),
+        replayer: None,
+    };
+
+    // Nothing runs, so there is never a reboot to carry out
+    debug_cpu(&mut state, &mut cpu, &Board::default(), &[]);
+}
+
+fn load_recording(path: &Path) -> Replayer {
+    let recording = fs::read_to_string(path).map_err(|e| e.to_string())
+        .and_then(|recording| Replayer::parse(&recording).map_err(|e| e.to_string()));
+    recording.unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to read recording {}: {e}\u{1b}[0m", path.display());
+        exit(2);
+    })
 }
 
 fn debugger_prompt(cpu: &mut CPU, state: &mut DebuggerState) -> CPUResult<()> {
     if state.running {
         cpu.tick()?;
-        cpu.program_counter += 15;
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
         return Ok(());
     }
 
@@ -86,7 +172,13 @@ fn debugger_prompt(cpu: &mut CPU, state: &mut DebuggerState) -> CPUResult<()> {
     let _ = io::stdin().read_line(&mut line);
     let line = line.trim();
 
-    match line {
+    if state.core.is_some() && commands::changes_state(line) {
+        println!("\u{1b}[33mCore dumps are read-only.\u{1b}[0m");
+        return Ok(());
+    }
+
+    let command = line.split_whitespace().next().unwrap_or_default();
+    match command {
         "?" | "help" => commands::help(),
         "s" | "step" => commands::step(state, cpu)?,
         "b" | "unblock" => commands::unblock(state),
@@ -96,16 +188,19 @@ fn debugger_prompt(cpu: &mut CPU, state: &mut DebuggerState) -> CPUResult<()> {
         "g" | "registers" => commands::registers(cpu),
         "i" | "interrupt" => commands::interrupt(state, cpu),
         "e" | "expand" => commands::expand(cpu),
+        "d" | "devices" => commands::devices(state, cpu),
         "q" | "quit" => commands::quit(),
-        line if line.starts_with('>') || line.starts_with("input") => commands::input(state, line),
+        "c" | "cache" => commands::cache(cpu, line),
+        "o" | "opcode" => commands::opcode(cpu, line),
+        "input" => commands::input(state, line),
+        _ if line.starts_with('>') => commands::input(state, line),
         _ => commands::default(cpu, line)
     }
 
     Ok(())
 }
 
-fn debug_cpu(state: &mut DebuggerState, cpu: &mut CPU, rom_size: usize) {
-    println!("\u{1b}[33mLoaded {rom_size} bytes of system ROM.\u{1b}[0m");
+fn debug_cpu(state: &mut DebuggerState, cpu: &mut CPU, board: &Board, rom: &[u8]) {
     println!("\u{1b}[33mProgram at {:0>8x}: {}\u{1b}[0m",
              cpu.program_counter, cpu.read_instruction_string(cpu.program_counter));
 
@@ -116,5 +211,30 @@ fn debug_cpu(state: &mut DebuggerState, cpu: &mut CPU, rom_size: usize) {
             println!("\u{1b}[33mUnhandled interrupt {interrupt} at {:0>8x}: {}\u{1b}[0m",
                      cpu.program_counter, cpu.read_instruction_string(cpu.program_counter));
         }
+
+        match state.power.take() {
+            Some(PowerRequest::Shutdown(code)) => {
+                state.running = false;
+                println!("\u{1b}[33mGuest shut down with exit code {code} at {:0>8x}\u{1b}[0m", cpu.program_counter);
+            }
+            Some(PowerRequest::OutputClosed) => {
+                state.running = false;
+                println!("\u{1b}[33mTerminal output closed at {:0>8x}, further output is dropped\u{1b}[0m", cpu.program_counter);
+            }
+            Some(PowerRequest::Reboot) => {
+                if let Err(e) = board.reboot(cpu, rom) {
+                    state.running = false;
+                    println!("\u{1b}[33mFailed to reboot: {e}\u{1b}[0m");
+                } else {
+                    println!("\u{1b}[33mGuest rebooted\u{1b}[0m");
+                }
+            }
+            None => {}
+        }
+
+        if let Some(tick) = state.replayer.as_ref().and_then(Replayer::divergence) {
+            state.replayer = None;
+            println!("\u{1b}[33mRun left the recording after {tick} device ticks, devices run live from here\u{1b}[0m");
+        }
     }
 }
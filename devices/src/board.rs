@@ -0,0 +1,396 @@
+// A whole machine described in one TOML file, shared by the emulator and the debugger:
+//
+//     memory = "64M"
+//     model = "kanto"
+//     entry = 0x0000_0200
+//
+//     [[rom]]
+//     path = "firmware.bin"
+//     address = 0x0010_0000
+//
+//     [[device]]
+//     type = "terminal"
+//     base_address = 0x0400_0200
+//
+//     [[device]]
+//     type = "rtc"
+//     epoch = 0
+//
+//     [[device]]
+//     type = "power"
+//
+//     [virtual_time]
+//     instruction_time = 1000
+//
+// Memory and model fall back to the defaults, but only the devices listed are attached. ROM paths
+// are relative to the file, and devices without a base address sit where they always have. Devices
+// have to fit between 0x0400_0200 and 0x0410_0200 without overlapping. Cores start at `entry`, the
+// usual 0x200 when left out.
+//
+// The program and ROM images can be raw ROMs or Vixen executables. Executables go where their
+// segments say instead of at their usual address, and the program's entry point wins over
+// `entry`.
+//
+// With `virtual_time`, devices follow the instruction count instead of the host clock: every
+// instruction takes `instruction_time` nanoseconds and clocks without an epoch start at 0, so runs
+// of the same program are reproducible.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use std::{fs, io};
+
+use serde::Deserialize;
+use vixen::core::executable::ExecutableError;
+use vixen::core::{CpuModel, Executable, Interrupt};
+use vixen::models::{find_model, DEFAULT_MODEL};
+use vixen::cpu::{LoadSegment, DEFAULT_ENTRY_POINT};
+use vixen::{find_memory_preset, BusDevice, BASE_SYSTEM_SIZE, CPU, DEVICE_SPACE_START, MEMORY_64M, MEMORY_MAX};
+
+use crate::power::POWER_PORT_COUNT;
+use crate::rtc::{Time, RTC_PORT_COUNT};
+use crate::terminal::TERMINAL_PORT_COUNT;
+use crate::{PowerController, PowerSignal, RealTimeClock, StdinReader, Terminal};
+use crate::{POWER_BASE_ADDRESS, RTC_BASE_ADDRESS, TERMINAL_BASE_ADDRESS};
+
+// Addresses from here on are RAM again, the last port of a device has to start before it
+const DEVICE_SPACE_END: u32 = 0x0410_0200;
+
+// One instruction per microsecond, a 1 MHz machine
+pub const DEFAULT_INSTRUCTION_TIME: Duration = Duration::from_micros(1);
+
+#[derive(Debug)]
+pub struct Board {
+    pub memory: usize,
+    pub model: &'static CpuModel,
+    pub roms: Vec<RomImage>,
+    // Where every core starts, and starts over on reboot
+    pub entry_point: u32,
+    pub devices: Vec<DeviceDescription>,
+    // How long one instruction takes in virtual time, the host clock is used when unset
+    pub virtual_time: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RomImage {
+    pub path: PathBuf,
+    pub address: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum DeviceDescription {
+    Terminal {
+        base_address: Option<u32>,
+    },
+    Rtc {
+        base_address: Option<u32>,
+        // Seconds since the Unix epoch the clock starts at, the host time when left out
+        epoch: Option<u32>,
+    },
+    Power {
+        base_address: Option<u32>,
+    },
+}
+
+#[derive(Debug)]
+pub enum BoardError {
+    Io(PathBuf, io::Error),
+    Syntax(toml::de::Error),
+    Invalid(String),
+    Load(Interrupt),
+    // The image's path, none for the program
+    Executable(Option<PathBuf>, ExecutableError),
+}
+
+// A file to be loaded, either as a whole at some address or as the executable it contains
+enum Image<'a> {
+    Raw(u32, &'a [u8]),
+    Executable(Executable),
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MemoryValue {
+    Bytes(u64),
+    Text(String),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BoardFile {
+    memory: Option<MemoryValue>,
+    model: Option<String>,
+    entry: Option<u32>,
+    #[serde(default)]
+    rom: Vec<RomFile>,
+    #[serde(default)]
+    device: Vec<DeviceDescription>,
+    virtual_time: Option<VirtualTimeFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VirtualTimeFile {
+    // Nanoseconds
+    instruction_time: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RomFile {
+    path: PathBuf,
+    address: u32,
+}
+
+// A preset such as `64M` or `MEMORY_64M`, or a number of bytes on top of the base system
+pub fn parse_memory(value: &str) -> Result<usize, String> {
+    if let Some(size) = find_memory_preset(value) {
+        return Ok(size);
+    }
+
+    let extra = match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse()
+    }.map_err(|_| format!("`{value}` is neither a memory preset nor a number of bytes"))?;
+
+    memory_from_bytes(extra)
+}
+
+fn memory_from_bytes(extra: u64) -> Result<usize, String> {
+    usize::try_from(extra).ok()
+        .and_then(|extra| BASE_SYSTEM_SIZE.checked_add(extra))
+        .filter(|size| *size <= MEMORY_MAX)
+        .ok_or_else(|| format!("at most {} bytes can be installed", MEMORY_MAX - BASE_SYSTEM_SIZE))
+}
+
+impl Default for Board {
+    // What vemu emulates without a machine file
+    fn default() -> Self {
+        Self {
+            memory: MEMORY_64M,
+            model: DEFAULT_MODEL,
+            roms: Vec::new(),
+            entry_point: DEFAULT_ENTRY_POINT,
+            devices: vec![
+                DeviceDescription::Terminal { base_address: None },
+                DeviceDescription::Rtc { base_address: None, epoch: None },
+                DeviceDescription::Power { base_address: None },
+            ],
+            virtual_time: None,
+        }
+    }
+}
+
+impl Board {
+    pub fn load(path: &Path) -> Result<Self, BoardError> {
+        let text = fs::read_to_string(path).map_err(|e| BoardError::Io(path.to_path_buf(), e))?;
+        let directory = path.parent().unwrap_or(Path::new(""));
+        Self::parse(&text, directory)
+    }
+
+    pub fn parse(text: &str, directory: &Path) -> Result<Self, BoardError> {
+        let file: BoardFile = toml::from_str(text).map_err(BoardError::Syntax)?;
+        let defaults = Self::default();
+
+        let memory = match file.memory {
+            None => defaults.memory,
+            Some(MemoryValue::Bytes(bytes)) => memory_from_bytes(bytes).map_err(BoardError::Invalid)?,
+            Some(MemoryValue::Text(text)) => parse_memory(&text).map_err(BoardError::Invalid)?,
+        };
+
+        let model = match file.model {
+            None => defaults.model,
+            Some(name) => find_model(&name).ok_or_else(|| BoardError::Invalid(format!("unknown CPU model `{name}`")))?,
+        };
+
+        check_devices(&file.device)?;
+
+        let roms = file.rom.into_iter()
+            .map(|rom| RomImage { path: directory.join(rom.path), address: rom.address })
+            .collect();
+
+        let virtual_time = file.virtual_time.map(|time| match time.instruction_time {
+            Some(nanos) => Duration::from_nanos(nanos),
+            None => DEFAULT_INSTRUCTION_TIME,
+        });
+
+        Ok(Self {
+            memory,
+            model,
+            roms,
+            entry_point: file.entry.unwrap_or(defaults.entry_point),
+            devices: file.device,
+            virtual_time,
+        })
+    }
+
+    // Loads `program` at the usual 0x200 and every ROM image at its own address, points the CPU at
+    // the entry point, then attaches the devices. Terminals start out as whatever `terminal` hands
+    // out, power controllers report to `power`.
+    pub fn build<S: StdinReader + 'static>(&self, program: &[u8], terminal: impl FnMut() -> Terminal<S>,
+                                           power: &PowerSignal) -> Result<CPU<'static>, BoardError> {
+        self.build_with(program, terminal, power, |device| device)
+    }
+
+    // Like `build`, with every device passed through `wrap` before it is attached, e.g. to record
+    // or replay what it does
+    pub fn build_with<S: StdinReader + 'static>(&self, program: &[u8], mut terminal: impl FnMut() -> Terminal<S>,
+                                                power: &PowerSignal,
+                                                mut wrap: impl FnMut(Box<dyn BusDevice>) -> Box<dyn BusDevice>)
+                                                -> Result<CPU<'static>, BoardError> {
+        let mut cpu = CPU::new_with_model(self.memory, self.model);
+        self.load_images(&mut cpu, program)?;
+
+        let devices = self.devices.iter()
+            .map(|device| wrap(device.create(&mut terminal, power, self.virtual_time)))
+            .collect();
+        cpu.register_devices(devices).map_err(BoardError::Load)?;
+
+        Ok(cpu)
+    }
+
+    // Reboots a machine made by `build`, putting the ROM contents back and keeping the devices
+    pub fn reboot(&self, cpu: &mut CPU, program: &[u8]) -> Result<(), BoardError> {
+        cpu.reset().map_err(BoardError::Load)?;
+        self.load_images(cpu, program)
+    }
+
+    fn load_images(&self, cpu: &mut CPU, program: &[u8]) -> Result<(), BoardError> {
+        let files = self.roms.iter()
+            .map(|rom| fs::read(&rom.path).map_err(|e| BoardError::Io(rom.path.clone(), e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let program = self.image(program, DEFAULT_ENTRY_POINT, None)?;
+        let entry_point = match &program {
+            Image::Executable(executable) => executable.entry_point,
+            Image::Raw(..) => self.entry_point,
+        };
+
+        let mut images = Vec::new();
+        for (rom, file) in self.roms.iter().zip(&files) {
+            let image = self.image(file, rom.address, Some(&rom.path))?;
+            if let Image::Raw(address, data) = image {
+                let start = address as usize;
+                if start.checked_add(data.len()).is_none_or(|end| end > cpu.memory.len()) {
+                    return Err(BoardError::Invalid(format!("{} does not fit at {address:0>8x}", rom.path.display())));
+                }
+            }
+            images.push(image);
+        }
+
+        let segments: Vec<LoadSegment> = std::iter::once(&program).chain(&images)
+            .flat_map(Image::segments)
+            .collect();
+        cpu.load_segments(&segments, entry_point).map_err(BoardError::Load)
+    }
+
+    fn image<'a>(&self, bytes: &'a [u8], address: u32, path: Option<&Path>) -> Result<Image<'a>, BoardError> {
+        if !Executable::is_executable(bytes) {
+            return Ok(Image::Raw(address, bytes));
+        }
+
+        let error = |e| BoardError::Executable(path.map(Path::to_path_buf), e);
+        let executable = Executable::parse(bytes).map_err(error)?;
+        executable.check(self.model).map_err(error)?;
+        Ok(Image::Executable(executable))
+    }
+}
+
+// Every device has to sit inside the device space without sharing an address with another
+fn check_devices(devices: &[DeviceDescription]) -> Result<(), BoardError> {
+    let mut taken: Vec<(&DeviceDescription, Range<u32>)> = Vec::new();
+    for device in devices {
+        let ports = device.ports();
+        if (ports.start as usize) < DEVICE_SPACE_START || ports.end > DEVICE_SPACE_END {
+            let message = format!("{} at {:0>8x} is outside the device space, {DEVICE_SPACE_START:0>8x} to {DEVICE_SPACE_END:0>8x}",
+                                  device.name(), ports.start);
+            return Err(BoardError::Invalid(message));
+        }
+        if let Some((other, _)) = taken.iter().find(|(_, other)| ports.start < other.end && other.start < ports.end) {
+            return Err(BoardError::Invalid(format!("{} at {:0>8x} overlaps the {}", device.name(), ports.start, other.name())));
+        }
+        taken.push((device, ports));
+    }
+    Ok(())
+}
+
+impl DeviceDescription {
+    // The `type` it is described with
+    fn name(&self) -> &'static str {
+        match self {
+            DeviceDescription::Terminal { .. } => "terminal",
+            DeviceDescription::Rtc { .. } => "rtc",
+            DeviceDescription::Power { .. } => "power",
+        }
+    }
+
+    // The addresses its ports take up once attached
+    fn ports(&self) -> Range<u32> {
+        let (base_address, count) = match *self {
+            DeviceDescription::Terminal { base_address } => (base_address.unwrap_or(TERMINAL_BASE_ADDRESS), TERMINAL_PORT_COUNT),
+            DeviceDescription::Rtc { base_address, .. } => (base_address.unwrap_or(RTC_BASE_ADDRESS), RTC_PORT_COUNT),
+            DeviceDescription::Power { base_address } => (base_address.unwrap_or(POWER_BASE_ADDRESS), POWER_PORT_COUNT),
+        };
+        base_address..base_address.saturating_add(count * 4)
+    }
+
+    pub fn create<S: StdinReader + 'static>(&self, terminal: &mut impl FnMut() -> Terminal<S>, power: &PowerSignal,
+                                            virtual_time: Option<Duration>) -> Box<dyn BusDevice> {
+        match *self {
+            DeviceDescription::Terminal { base_address } => {
+                let mut terminal = terminal().with_power_signal(power.clone());
+                if let Some(base_address) = base_address {
+                    terminal = terminal.with_base_address(base_address);
+                }
+                Box::new(terminal)
+            }
+            DeviceDescription::Rtc { base_address, epoch } => {
+                let mut rtc = match (epoch, virtual_time) {
+                    (Some(secs), _) => RealTimeClock::new(Time::new(secs, 0)),
+                    (None, Some(_)) => RealTimeClock::new(Time::default()),
+                    (None, None) => RealTimeClock::now(),
+                };
+                if let Some(duration) = virtual_time {
+                    rtc = rtc.with_virtual_time(duration);
+                }
+                if let Some(base_address) = base_address {
+                    rtc = rtc.with_base_address(base_address);
+                }
+                Box::new(rtc)
+            }
+            DeviceDescription::Power { base_address } => {
+                let mut controller = PowerController::new(power.clone());
+                if let Some(base_address) = base_address {
+                    controller = controller.with_base_address(base_address);
+                }
+                Box::new(controller)
+            }
+        }
+    }
+}
+
+impl Image<'_> {
+    fn segments(&self) -> Vec<LoadSegment<'_>> {
+        match self {
+            Image::Raw(address, data) => vec![LoadSegment { address: *address, data }],
+            Image::Executable(executable) => executable.load_segments(),
+        }
+    }
+}
+
+impl Display for BoardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BoardError::Io(path, e) => write!(f, "{}: {e}", path.display()),
+            BoardError::Syntax(e) => write!(f, "{e}"),
+            BoardError::Invalid(message) => write!(f, "{message}"),
+            BoardError::Load(interrupt) => write!(f, "{interrupt}"),
+            BoardError::Executable(Some(path), e) => write!(f, "{}: {e}", path.display()),
+            BoardError::Executable(None, e) => write!(f, "program: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
@@ -0,0 +1,46 @@
+// Loading what the front ends need from the host file system alongside the images they run.
+
+use std::fs;
+use std::path::Path;
+
+use vixen::core::{Executable, SymbolTable};
+use vixen::cpu::DEFAULT_ENTRY_POINT;
+
+// Executables carry their own symbols, for raw images vasm --symbols writes the map next to them,
+// e.g. rom.bin and rom.sym. A raw image loaded at `address` only covers as many bytes as the file
+// holds, labels don't extend past it
+#[must_use]
+pub fn load_symbols(image_path: &Path, address: u32) -> Option<SymbolTable> {
+    let image = fs::read(image_path).ok();
+    let embedded = image.as_deref()
+        .filter(|bytes| Executable::is_executable(bytes))
+        .and_then(|bytes| Executable::parse(bytes).ok())
+        .and_then(|executable| executable.symbols);
+    if embedded.is_some() {
+        return embedded;
+    }
+
+    let map = fs::read_to_string(image_path.with_extension("sym")).ok()?;
+    let mut symbols = SymbolTable::parse_map(&map).inspect_err(|e| {
+        eprintln!("\u{1b}[33mIgnoring symbol map: {e}\u{1b}[0m");
+    }).ok()?;
+    if let Some(image) = image {
+        let length = u32::try_from(image.len()).unwrap_or(u32::MAX);
+        symbols.insert_end(address.saturating_add(length));
+    }
+    Some(symbols)
+}
+
+// The BIOS and the program both bring their own map, their addresses don't overlap. Without a
+// BIOS the program takes its place at the entry point
+#[must_use]
+pub fn load_all_symbols(bios: Option<&Path>, program: Option<&Path>, program_address: u32) -> Option<SymbolTable> {
+    let program_address = if bios.is_some() { program_address } else { DEFAULT_ENTRY_POINT };
+
+    [(bios, DEFAULT_ENTRY_POINT), (program, program_address)].into_iter()
+        .filter_map(|(path, address)| load_symbols(path?, address))
+        .reduce(|mut symbols, other| {
+            symbols.merge(&other);
+            symbols
+        })
+}
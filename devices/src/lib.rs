@@ -1,5 +1,11 @@
 mod terminal;
 mod rtc;
+mod power;
+pub mod board;
+pub mod replay;
+pub mod host;
 
-pub use terminal::{Terminal, StdinReader};
-pub use rtc::RealTimeClock;
+pub use terminal::{Terminal, StdinReader, TerminalStdin, ScheduledInput, ScheduleError, TERMINAL_BASE_ADDRESS};
+pub use rtc::{RealTimeClock, Time, RTC_BASE_ADDRESS};
+pub use power::{PowerController, PowerRequest, PowerSignal, POWER_BASE_ADDRESS};
+pub use board::Board;
@@ -0,0 +1,94 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use vixen::BusDevice;
+use vixen::devices::errors::{BusError, BusResult};
+
+pub const POWER_BASE_ADDRESS: u32 = 0x0400_0220;
+pub(crate) const POWER_PORT_COUNT: u32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PowerRequest {
+    Shutdown(u32),
+    Reboot,
+    // The terminal could no longer write its output, e.g. into a closed pipe
+    OutputClosed,
+}
+
+// Shared between the device and the host, which checks it after every instruction
+#[derive(Debug, Clone, Default)]
+pub struct PowerSignal {
+    request: Rc<Cell<Option<PowerRequest>>>,
+}
+
+impl PowerSignal {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn take(&self) -> Option<PowerRequest> {
+        self.request.take()
+    }
+
+    pub(crate) fn request(&self, request: PowerRequest) {
+        self.request.set(Some(request));
+    }
+}
+
+// Lets the guest stop the machine: writing port 0 shuts down with that exit code, writing
+// anything to port 1 reboots
+#[derive(Debug)]
+pub struct PowerController {
+    signal: PowerSignal,
+    base_address: u32,
+}
+
+impl PowerController {
+    #[must_use]
+    pub fn new(signal: PowerSignal) -> Self {
+        Self {
+            signal,
+            base_address: POWER_BASE_ADDRESS,
+        }
+    }
+
+    #[must_use]
+    pub fn with_base_address(mut self, base_address: u32) -> Self {
+        self.base_address = base_address;
+        self
+    }
+}
+
+impl BusDevice for PowerController {
+    fn get_port_count(&self) -> u32 {
+        POWER_PORT_COUNT
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.base_address
+    }
+
+    fn read_port(&mut self, index: u32) -> BusResult<u32> {
+        match index {
+            0 | 1 => Err(BusError::WriteOnly),
+            _ => Err(BusError::PortOutOfRange),
+        }
+    }
+
+    fn write_port(&mut self, index: u32, data: u32) -> BusResult<()> {
+        let request = match index {
+            0 => PowerRequest::Shutdown(data),
+            1 => PowerRequest::Reboot,
+            _ => return Err(BusError::PortOutOfRange),
+        };
+
+        self.signal.request(request);
+        Ok(())
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,308 @@
+// Recording and replaying what devices tell the CPU, so a run that depended on keyboard timing or
+// the host clock can be repeated exactly. Devices are wrapped one by one in the order they are
+// attached. Recording logs every port read and every event a device raises on tick, replaying
+// answers reads and raises events from the log instead of asking the device. Writes always reach
+// the real device, so output still shows up on replay.
+//
+// A recording is a text file, one entry per line after the header:
+//
+//     vixen-recording 1
+//     0 1042 tick device-event
+//     0 1043 read 1 00000068
+//     1 2000 read 0 65f1a2b3
+//
+// That is the device index, the number of ticks the device had seen (one per instruction), and
+// either the event the tick raised or the port read with its value or error.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::rc::Rc;
+
+use vixen::BusDevice;
+use vixen::devices::errors::{BusError, BusResult};
+
+const HEADER: &str = "vixen-recording 1";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Entry {
+    device: usize,
+    tick: u64,
+    event: Event,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Event {
+    Read { port: u32, result: Result<u32, &'static str> },
+    Tick(&'static str),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordingError {
+    pub line: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Recorder {
+    log: Rc<RefCell<Vec<Entry>>>,
+    devices: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Replayer {
+    // By device index, a device's entries are handed to it when it is wrapped
+    entries: BTreeMap<usize, VecDeque<Entry>>,
+    devices: usize,
+    divergence: Rc<Cell<Option<u64>>>,
+}
+
+#[derive(Debug)]
+struct Recorded {
+    device: Box<dyn BusDevice>,
+    index: usize,
+    ticks: u64,
+    log: Rc<RefCell<Vec<Entry>>>,
+}
+
+#[derive(Debug)]
+struct Replayed {
+    device: Box<dyn BusDevice>,
+    ticks: u64,
+    entries: VecDeque<Entry>,
+    divergence: Rc<Cell<Option<u64>>>,
+}
+
+impl Recorder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn wrap(&mut self, device: Box<dyn BusDevice>) -> Box<dyn BusDevice> {
+        self.devices += 1;
+        Box::new(Recorded {
+            device,
+            index: self.devices - 1,
+            ticks: 0,
+            log: self.log.clone(),
+        })
+    }
+}
+
+impl Replayer {
+    pub fn parse(recording: &str) -> Result<Self, RecordingError> {
+        let mut lines = recording.lines().enumerate();
+        if lines.next().map(|(_, line)| line.trim()) != Some(HEADER) {
+            return Err(RecordingError { line: 1 });
+        }
+
+        let mut entries: BTreeMap<usize, VecDeque<Entry>> = BTreeMap::new();
+        for (index, line) in lines {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let entry = parse_entry(line).ok_or(RecordingError { line: index + 1 })?;
+            entries.entry(entry.device).or_default().push_back(entry);
+        }
+
+        Ok(Self {
+            entries,
+            devices: 0,
+            divergence: Rc::default(),
+        })
+    }
+
+    pub fn wrap(&mut self, device: Box<dyn BusDevice>) -> Box<dyn BusDevice> {
+        let entries = self.entries.remove(&self.devices).unwrap_or_default();
+        self.devices += 1;
+        Box::new(Replayed {
+            device,
+            ticks: 0,
+            entries,
+            divergence: self.divergence.clone(),
+        })
+    }
+
+    // The tick at which the run stopped matching the recording, from then on devices run live.
+    // Once every device is attached, entries left for devices that never were count from their
+    // first tick
+    #[must_use]
+    pub fn divergence(&self) -> Option<u64> {
+        let unattached = self.entries.values().filter_map(VecDeque::front).map(|entry| entry.tick);
+        self.divergence.get().into_iter().chain(unattached).min()
+    }
+}
+
+impl Display for Recorder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{HEADER}")?;
+        for entry in self.log.borrow().iter() {
+            write!(f, "{} {} ", entry.device, entry.tick)?;
+            match entry.event {
+                Event::Read { port, result: Ok(value) } => writeln!(f, "read {port} {value:0>8x}")?,
+                Event::Read { port, result: Err(error) } => writeln!(f, "read {port} {error}")?,
+                Event::Tick(error) => writeln!(f, "tick {error}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Recorded {
+    fn record(&self, event: Event) {
+        self.log.borrow_mut().push(Entry { device: self.index, tick: self.ticks, event });
+    }
+}
+
+impl BusDevice for Recorded {
+    fn get_port_count(&self) -> u32 {
+        self.device.get_port_count()
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.device.get_base_address()
+    }
+
+    fn read_port(&mut self, index: u32) -> BusResult<u32> {
+        let result = self.device.read_port(index);
+        let recorded = result.as_ref().map(|value| *value).map_err(error_name);
+        self.record(Event::Read { port: index, result: recorded });
+        result
+    }
+
+    fn write_port(&mut self, index: u32, data: u32) -> BusResult<()> {
+        self.device.write_port(index, data)
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        let result = self.device.tick();
+        if let Err(error) = &result {
+            self.record(Event::Tick(error_name(error)));
+        }
+        self.ticks += 1;
+        result
+    }
+}
+
+impl Replayed {
+    // The next entry if it belongs to this point of the run, anything else means the run went
+    // somewhere the recording didn't
+    fn next(&mut self, matches: impl Fn(Event) -> bool) -> Option<Event> {
+        if self.divergence.get().is_some() {
+            return None;
+        }
+
+        match self.entries.front() {
+            Some(entry) if entry.tick == self.ticks && matches(entry.event) => {
+                self.entries.pop_front().map(|entry| entry.event)
+            }
+            Some(entry) if entry.tick <= self.ticks => {
+                self.divergence.set(Some(self.ticks));
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+impl BusDevice for Replayed {
+    fn get_port_count(&self) -> u32 {
+        self.device.get_port_count()
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.device.get_base_address()
+    }
+
+    fn read_port(&mut self, index: u32) -> BusResult<u32> {
+        if let Some(Event::Read { result, .. }) = self.next(|event| matches!(event, Event::Read { port, .. } if port == index)) {
+            return result.map_err(parse_error);
+        }
+
+        // A read the recording doesn't have
+        if self.divergence.get().is_none() {
+            self.divergence.set(Some(self.ticks));
+        }
+        self.device.read_port(index)
+    }
+
+    fn write_port(&mut self, index: u32, data: u32) -> BusResult<()> {
+        self.device.write_port(index, data)
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        // Still ticked for what it does on its own, like writing out characters
+        let live = self.device.tick();
+        let replayed = if self.divergence.get().is_some() {
+            live
+        } else {
+            match self.next(|event| matches!(event, Event::Tick(_))) {
+                Some(Event::Tick(error)) => Err(parse_error(error)),
+                _ if self.divergence.get().is_some() => live,
+                _ => Ok(()),
+            }
+        };
+
+        self.ticks += 1;
+        replayed
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut parts = line.split_whitespace();
+    let device = parts.next()?.parse().ok()?;
+    let tick = parts.next()?.parse().ok()?;
+
+    let event = match parts.next()? {
+        "read" => {
+            let port = parts.next()?.parse().ok()?;
+            let value = parts.next()?;
+            let result = match u32::from_str_radix(value, 16) {
+                Ok(value) => Ok(value),
+                Err(_) => Err(error_name(&parse_error_name(value)?)),
+            };
+            Event::Read { port, result }
+        }
+        "tick" => Event::Tick(error_name(&parse_error_name(parts.next()?)?)),
+        _ => return None,
+    };
+
+    parts.next().is_none().then_some(Entry { device, tick, event })
+}
+
+fn error_name(error: &BusError) -> &'static str {
+    match error {
+        BusError::PortOutOfRange => "port-out-of-range",
+        BusError::ReadOnly => "read-only",
+        BusError::WriteOnly => "write-only",
+        BusError::DeviceEvent => "device-event",
+        BusError::EmptyBuffer => "empty-buffer",
+        BusError::InternalSystem => "internal-system",
+    }
+}
+
+fn parse_error_name(name: &str) -> Option<BusError> {
+    match name {
+        "port-out-of-range" => Some(BusError::PortOutOfRange),
+        "read-only" => Some(BusError::ReadOnly),
+        "write-only" => Some(BusError::WriteOnly),
+        "device-event" => Some(BusError::DeviceEvent),
+        "empty-buffer" => Some(BusError::EmptyBuffer),
+        "internal-system" => Some(BusError::InternalSystem),
+        _ => None,
+    }
+}
+
+// Names in entries always come from `error_name`
+fn parse_error(name: &'static str) -> BusError {
+    parse_error_name(name).unwrap_or(BusError::InternalSystem)
+}
+
+impl Display for RecordingError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid recording entry on line {}", self.line)
+    }
+}
+
+impl std::error::Error for RecordingError {}
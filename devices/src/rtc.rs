@@ -7,11 +7,17 @@ mod time;
 
 pub use time::{Time, Timer};
 
+pub const RTC_BASE_ADDRESS: u32 = 0x0400_020C;
+pub(crate) const RTC_PORT_COUNT: u32 = 5;
+
 #[derive(Debug)]
 pub struct RealTimeClock {
     time: Time,
     timer: Option<Timer>,
     last_tick_time: Instant,
+    // Set in virtual time, where every tick moves the clock on by this much
+    tick_duration: Option<Duration>,
+    base_address: u32,
 }
 
 #[allow(clippy::cast_possible_truncation)]
@@ -22,6 +28,8 @@ impl RealTimeClock {
             time,
             timer: None,
             last_tick_time: Instant::now(),
+            tick_duration: None,
+            base_address: RTC_BASE_ADDRESS,
         }
     }
 
@@ -39,9 +47,25 @@ impl RealTimeClock {
             time,
             timer: None,
             last_tick_time: Instant::now(),
+            tick_duration: None,
+            base_address: RTC_BASE_ADDRESS,
         }
     }
 
+    // Follows the instruction count instead of the host clock: the device ticks once per
+    // instruction, so two runs of the same program see exactly the same times
+    #[must_use]
+    pub fn with_virtual_time(mut self, tick_duration: Duration) -> Self {
+        self.tick_duration = Some(tick_duration);
+        self
+    }
+
+    #[must_use]
+    pub fn with_base_address(mut self, base_address: u32) -> Self {
+        self.base_address = base_address;
+        self
+    }
+
     #[inline]
     #[must_use]
     pub fn secs(&self) -> u32 {
@@ -118,6 +142,11 @@ impl RealTimeClock {
     }
 
     fn update(&mut self) {
+        if let Some(duration) = self.tick_duration {
+            self.add(duration);
+            return;
+        }
+
         let now = Instant::now();
         let elapsed = now - self.last_tick_time;
         self.last_tick_time = now;
@@ -139,11 +168,11 @@ impl RealTimeClock {
 
 impl BusDevice for RealTimeClock {
     fn get_port_count(&self) -> u32 {
-        5
+        RTC_PORT_COUNT
     }
 
     fn get_base_address(&self) -> u32 {
-        0x0400_020C
+        self.base_address
     }
 
     fn read_port(&mut self, index: u32) -> BusResult<u32> {
@@ -1,46 +1,89 @@
 use std::collections::VecDeque;
+use std::fmt::{Debug, Formatter};
 use std::io;
-use std::io::{Stdout, Write};
-use stdin::TerminalStdin;
+use std::io::Write;
 use vixen::BusDevice;
 use vixen::devices::errors::{BusError, BusResult};
 
+use crate::{PowerRequest, PowerSignal};
+
 mod stdin;
+mod schedule;
+
+pub use stdin::{StdinReader, TerminalStdin};
+pub use schedule::{ScheduleError, ScheduledInput};
 
-pub use stdin::StdinReader;
+pub const TERMINAL_BASE_ADDRESS: u32 = 0x0400_0200;
+pub(crate) const TERMINAL_PORT_COUNT: u32 = 3;
 
-#[derive(Debug)]
 pub struct Terminal<S: StdinReader> {
     stdin: S,
-    stdout: Stdout,
+    base_address: u32,
+    output: Box<dyn Write>,
+    // Told when the output fails, the terminal drops what it writes from then on
+    power: Option<PowerSignal>,
+    output_closed: bool,
     read_buffer: VecDeque<u8>,
     write_buffer: VecDeque<u8>,
 }
 
 impl Default for Terminal<TerminalStdin> {
     fn default() -> Self {
-        let (sender, receiver) = std::sync::mpsc::sync_channel(512);
-        let stdin = TerminalStdin::spawn(sender, receiver);
-
-        Self::new(stdin)
+        Self::new(TerminalStdin::new())
     }
 }
 
 impl<S: StdinReader> Terminal<S> {
     #[must_use]
     pub fn new(stdin: S) -> Self {
-        let stdout = io::stdout();
+        let output = Box::new(io::stdout());
         let read_buffer = VecDeque::new();
         let write_buffer = VecDeque::new();
 
         Self {
             stdin,
-            stdout,
+            base_address: TERMINAL_BASE_ADDRESS,
+            output,
+            power: None,
+            output_closed: false,
             read_buffer,
             write_buffer,
         }
     }
 
+    #[must_use]
+    pub fn with_base_address(mut self, base_address: u32) -> Self {
+        self.base_address = base_address;
+        self
+    }
+
+    // Where the guest's characters go instead of stdout, flushed one character at a time
+    #[must_use]
+    pub fn with_output(mut self, output: impl Write + 'static) -> Self {
+        self.output = Box::new(output);
+        self
+    }
+
+    // Where to ask for the machine to stop once the output can't be written to anymore
+    #[must_use]
+    pub fn with_power_signal(mut self, power: PowerSignal) -> Self {
+        self.power = Some(power);
+        self
+    }
+
+    fn flush_character(&mut self, ch: u8) {
+        if self.output_closed {
+            return;
+        }
+
+        if self.output.write_all(&[ch]).and_then(|()| self.output.flush()).is_err() {
+            self.output_closed = true;
+            if let Some(power) = &self.power {
+                power.request(PowerRequest::OutputClosed);
+            }
+        }
+    }
+
     fn read(&mut self) -> BusResult<u32> {
         match self.read_buffer.pop_front() {
             Some(ch) => Ok(u32::from(ch)),
@@ -56,13 +99,25 @@ impl<S: StdinReader> Terminal<S> {
     }
 }
 
+impl<S: StdinReader> Debug for Terminal<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Terminal")
+            .field("stdin", &self.stdin)
+            .field("base_address", &self.base_address)
+            .field("output_closed", &self.output_closed)
+            .field("read_buffer", &self.read_buffer)
+            .field("write_buffer", &self.write_buffer)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<S: StdinReader> BusDevice for Terminal<S> {
     fn get_port_count(&self) -> u32 {
-        3
+        TERMINAL_PORT_COUNT
     }
 
     fn get_base_address(&self) -> u32 {
-        0x0400_0200
+        self.base_address
     }
 
     fn read_port(&mut self, index: u32) -> BusResult<u32> {
@@ -84,8 +139,7 @@ impl<S: StdinReader> BusDevice for Terminal<S> {
 
     fn tick(&mut self) -> BusResult<()> {
         if let Some(ch) = self.write_buffer.pop_front() {
-            self.stdout.write_all(&[ch]).unwrap();
-            self.stdout.flush().unwrap();
+            self.flush_character(ch);
         }
 
         if let Some(char) = self.stdin.read() {
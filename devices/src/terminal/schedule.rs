@@ -0,0 +1,107 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+
+use super::StdinReader;
+
+// Terminal input typed at fixed points of the run instead of whenever the host delivers it, so
+// runs that read input can be repeated exactly. Each line of a schedule is an instruction count
+// and the text to type from then on:
+//
+//     1000 hello\n
+//     50000 \x03
+//
+// The terminal takes one character per tick and ticks once per instruction. Text supports the
+// escapes \n, \r, \t, \\ and \xNN.
+#[derive(Debug, Default)]
+pub struct ScheduledInput {
+    ticks: Cell<u64>,
+    pending: RefCell<VecDeque<(u64, u8)>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleError {
+    pub line: usize,
+}
+
+impl ScheduledInput {
+    #[must_use]
+    pub fn new(entries: &[(u64, Vec<u8>)]) -> Self {
+        let mut entries = entries.to_vec();
+        entries.sort_by_key(|(count, _)| *count);
+
+        let pending = entries.into_iter()
+            .flat_map(|(count, text)| text.into_iter().map(move |byte| (count, byte)))
+            .collect();
+
+        Self {
+            ticks: Cell::new(0),
+            pending: RefCell::new(pending),
+        }
+    }
+
+    pub fn parse(schedule: &str) -> Result<Self, ScheduleError> {
+        let mut entries = Vec::new();
+
+        for (index, line) in schedule.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let error = ScheduleError { line: index + 1 };
+            let (count, text) = line.split_once(' ').ok_or(error)?;
+            let count = count.parse().map_err(|_| error)?;
+            entries.push((count, unescape(text).ok_or(error)?));
+        }
+
+        Ok(Self::new(&entries))
+    }
+}
+
+impl StdinReader for ScheduledInput {
+    fn read(&self) -> Option<u8> {
+        let tick = self.ticks.get();
+        self.ticks.set(tick + 1);
+
+        let mut pending = self.pending.borrow_mut();
+        match pending.front() {
+            Some((count, _)) if *count <= tick => pending.pop_front().map(|(_, byte)| byte),
+            _ => None,
+        }
+    }
+}
+
+fn unescape(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut chars = text.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => bytes.push(b'\n'),
+            'r' => bytes.push(b'\r'),
+            't' => bytes.push(b'\t'),
+            '\\' => bytes.push(b'\\'),
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                bytes.push(u8::from_str_radix(&hex, 16).ok()?);
+            }
+            _ => return None,
+        }
+    }
+
+    Some(bytes)
+}
+
+impl Display for ScheduleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid input schedule entry on line {}", self.line)
+    }
+}
+
+impl std::error::Error for ScheduleError {}
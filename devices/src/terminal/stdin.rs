@@ -1,4 +1,5 @@
 use std::fmt::Debug;
+use std::io::{BufReader, Read};
 use std::sync::mpsc::{Receiver, SyncSender};
 use std::thread;
 use getch::Getch;
@@ -8,6 +9,12 @@ pub trait StdinReader: Debug {
     fn read(&self) -> Option<u8>;
 }
 
+impl<S: StdinReader + ?Sized> StdinReader for Box<S> {
+    fn read(&self) -> Option<u8> {
+        (**self).read()
+    }
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Debug)]
 pub struct TerminalStdin {
@@ -15,6 +22,13 @@ pub struct TerminalStdin {
 }
 
 impl TerminalStdin {
+    #[must_use]
+    pub fn new() -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(512);
+        Self::spawn(sender, receiver)
+    }
+
+    #[must_use]
     pub fn spawn(sender: SyncSender<u8>, receiver: Receiver<u8>) -> Self {
         let getch = Getch::new();
         thread::spawn(move || reader_thread(&getch, &sender));
@@ -23,9 +37,35 @@ impl TerminalStdin {
             receiver,
         }
     }
+
+    // Feeds the terminal from a file or pipe instead of the keyboard, input simply stops at the
+    // end of it
+    #[must_use]
+    pub fn from_reader(reader: impl Read + Send + 'static) -> Self {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(512);
+        thread::spawn(move || {
+            for byte in BufReader::new(reader).bytes() {
+                let Ok(byte) = byte else { break };
+                if sender.send(byte).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Self {
+            receiver,
+        }
+    }
+}
+
+impl Default for TerminalStdin {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl StdinReader for TerminalStdin {
+    #[allow(clippy::manual_ok_err)]
     fn read(&self) -> Option<u8> {
         if let Ok(char) = self.receiver.try_recv() {
             Some(char)
@@ -0,0 +1,215 @@
+// Checks machine description files are read and turned into a running CPU.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use vixen::core::Interrupt;
+use vixen::core::instruction::Operation;
+use vixen::devices::errors::BusResult;
+use vixen::models::JOHTO;
+use vixen::{BusDevice, MEMORY_1M, MEMORY_64M};
+use vixen_devices::board::{BoardError, DeviceDescription};
+use vixen_devices::{Board, PowerRequest, PowerSignal, StdinReader, Terminal};
+use vixen_devices::{POWER_BASE_ADDRESS, RTC_BASE_ADDRESS, TERMINAL_BASE_ADDRESS};
+
+const IMMEDIATE: u32 = 0x0;
+const DIRECT: u32 = 0x1;
+const ABSOLUTE: u32 = 0x3;
+const IMPLIED: u32 = 0x5;
+const R0: u32 = 0x0001;
+
+#[derive(Debug)]
+struct NoInput;
+
+impl StdinReader for NoInput {
+    fn read(&self) -> Option<u8> {
+        None
+    }
+}
+
+#[derive(Debug)]
+struct Ports(u32, u32);
+
+impl BusDevice for Ports {
+    fn get_port_count(&self) -> u32 {
+        self.1
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.0
+    }
+
+    fn read_port(&mut self, _index: u32) -> BusResult<u32> {
+        Ok(0)
+    }
+
+    fn write_port(&mut self, _index: u32, _data: u32) -> BusResult<()> {
+        Ok(())
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        Ok(())
+    }
+}
+
+fn emit(rom: &mut Vec<u8>, operation: Operation, operands: [(u32, u32); 3]) {
+    let modes = operands[0].0 | operands[1].0 << 4 | operands[2].0 << 8;
+    let opcode = (u32::from(u16::from(operation)) << 12 | modes).to_le_bytes();
+    rom.extend_from_slice(&opcode[..3]);
+    for (_, value) in operands {
+        rom.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+#[test]
+fn sample_describes_the_default_board() {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../samples/machine.toml");
+    let board = Board::load(&path).unwrap();
+    let default = Board::default();
+
+    assert_eq!(board.memory, default.memory);
+    assert_eq!(board.model.short_name, default.model.short_name);
+    assert_eq!(board.devices, [
+        DeviceDescription::Terminal { base_address: Some(TERMINAL_BASE_ADDRESS) },
+        DeviceDescription::Rtc { base_address: Some(RTC_BASE_ADDRESS), epoch: None },
+        DeviceDescription::Power { base_address: Some(POWER_BASE_ADDRESS) },
+    ]);
+}
+
+#[test]
+fn builds_the_described_machine() {
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    fs::write(directory.join("board-data.bin"), 0x1234_5678u32.to_le_bytes()).unwrap();
+
+    let board = Board::parse(r#"
+        memory = 1048576
+        model = "johto"
+
+        [[rom]]
+        path = "board-data.bin"
+        address = 0x0460_0000
+
+        [[device]]
+        type = "rtc"
+        base_address = 0x0400_0300
+        epoch = 1000
+    "#, &directory).unwrap();
+
+    assert_eq!(board.memory, MEMORY_1M);
+    assert_eq!(board.model.short_name, JOHTO.short_name);
+    assert_eq!(board.devices, [DeviceDescription::Rtc { base_address: Some(0x0400_0300), epoch: Some(1000) }]);
+
+    // mov r0, [$04600000]
+    // mov r1, [$04000300]
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (ABSOLUTE, 0x0460_0000), (IMPLIED, 0)]);
+    emit(&mut program, Operation::Mov, [(DIRECT, 0x0011), (ABSOLUTE, 0x0400_0300), (IMPLIED, 0)]);
+
+    let mut cpu = board.build(&program, || Terminal::new(NoInput), &PowerSignal::new()).unwrap();
+    for _ in 0..2 {
+        cpu.tick().unwrap();
+        cpu.program_counter += 15;
+    }
+
+    assert_eq!(cpu.registers.r0, 0x1234_5678);
+    assert!((1000..1010).contains(&cpu.registers.r1), "{}", cpu.registers.r1);
+}
+
+#[test]
+fn starts_at_the_entry_point() {
+    // mov r0, #7
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(DIRECT, R0), (IMMEDIATE, 7), (IMPLIED, 0)]);
+    let directory = PathBuf::from(env!("CARGO_TARGET_TMPDIR"));
+    fs::write(directory.join("board-entry.bin"), &program).unwrap();
+
+    let board = Board::parse(r#"
+        entry = 0x0001_0000
+
+        [[rom]]
+        path = "board-entry.bin"
+        address = 0x0001_0000
+    "#, &directory).unwrap();
+
+    let mut cpu = board.build(&[], || Terminal::new(NoInput), &PowerSignal::new()).unwrap();
+    assert_eq!(cpu.program_counter, 0x0001_0000);
+    cpu.tick().unwrap();
+    assert_eq!(cpu.registers.r0, 7);
+
+    board.reboot(&mut cpu, &[]).unwrap();
+    assert_eq!(cpu.program_counter, 0x0001_0000);
+}
+
+#[test]
+fn rejects_bad_descriptions() {
+    let cases = [
+        "memory = \"9G\"",
+        "model = \"sinnoh\"",
+        "[[device]]\ntype = \"printer\"",
+        "[[device]]\ntype = \"rtc\"\nspeed = 2",
+        "colour = \"red\"",
+        "[[device]]\ntype = \"power\"\nbase_address = 0x100",
+        "[[device]]\ntype = \"rtc\"\nbase_address = 0x0410_01f0",
+        "[[device]]\ntype = \"power\"\nbase_address = 0xffff_fffc",
+        "[[device]]\ntype = \"terminal\"\n[[device]]\ntype = \"power\"\nbase_address = 0x0400_0208",
+        "[[device]]\ntype = \"power\"\n[[device]]\ntype = \"power\"",
+    ];
+
+    for text in cases {
+        assert!(Board::parse(text, Path::new("")).is_err(), "{text}");
+    }
+
+    let board = Board::parse("[[rom]]\npath = \"missing.bin\"\naddress = 0", Path::new("")).unwrap();
+    assert!(matches!(board.build(&[], || Terminal::new(NoInput), &PowerSignal::new()), Err(BoardError::Io(..))));
+
+    let moved = "[[device]]\ntype = \"rtc\"\nbase_address = 0x0410_01ec\n[[device]]\ntype = \"power\"\nbase_address = 0x0400_0200";
+    assert!(Board::parse(moved, Path::new("")).is_ok());
+
+    let empty = Board::parse("", Path::new("")).unwrap();
+    assert_eq!(empty.memory, MEMORY_64M);
+    assert!(empty.devices.is_empty());
+}
+
+#[test]
+fn devices_do_not_overlap() {
+    let board = Board::parse("", Path::new("")).unwrap();
+    let mut cpu = board.build(&[], || Terminal::new(NoInput), &PowerSignal::new()).unwrap();
+
+    assert!(cpu.add_device(Box::new(Ports(0x0400_0300, 4))).is_ok());
+    for (base, ports) in [(0xffff_fff0, 8), (0x0400_0400, u32::MAX), (0x0400_02fc, 2), (0x0400_0304, 1)] {
+        let attached = cpu.add_device(Box::new(Ports(base, ports)));
+        assert!(matches!(attached, Err(Interrupt::Hardware)), "{base:#x} with {ports} ports");
+    }
+    assert!(cpu.add_device(Box::new(Ports(0x0400_02fc, 1))).is_ok());
+}
+
+#[test]
+fn guest_controls_power() {
+    // add r0, r0, #1
+    // mov [POWER + 4], #0      ; reboot
+    // mov [POWER], r0          ; shut down with the number of boots
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Add, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 1)]);
+    emit(&mut program, Operation::Mov, [(ABSOLUTE, POWER_BASE_ADDRESS + 4), (IMMEDIATE, 0), (IMPLIED, 0)]);
+    emit(&mut program, Operation::Mov, [(ABSOLUTE, POWER_BASE_ADDRESS), (DIRECT, R0), (IMPLIED, 0)]);
+
+    let board = Board::parse("[[device]]\ntype = \"power\"", Path::new("")).unwrap();
+    let power = PowerSignal::new();
+    let mut cpu = board.build(&program, || Terminal::new(NoInput), &power).unwrap();
+
+    for _ in 0..2 {
+        cpu.tick().unwrap();
+        cpu.program_counter += 15;
+    }
+    assert_eq!(power.take(), Some(PowerRequest::Reboot));
+    assert_eq!(power.take(), None);
+
+    // Registers start over, so the guest shuts down with 1 rather than 2
+    board.reboot(&mut cpu, &program).unwrap();
+    assert_eq!((cpu.program_counter, cpu.registers.r0), (0x200, 0));
+    cpu.tick().unwrap();
+    cpu.program_counter += 15;
+    cpu.program_counter += 15;
+    cpu.tick().unwrap();
+    assert_eq!(power.take(), Some(PowerRequest::Shutdown(1)));
+}
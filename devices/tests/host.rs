@@ -0,0 +1,64 @@
+// Checks symbols are found next to the images the front ends load, and only cover those images.
+
+use std::fs;
+use std::path::PathBuf;
+
+use vixen::core::{Executable, SymbolLookup, SymbolTable};
+use vixen_devices::host::{load_all_symbols, load_symbols};
+
+fn directory() -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+}
+
+#[test]
+fn raw_image_with_map() {
+    let image = directory().join("host-raw.bin");
+    fs::write(&image, [0u8; 30]).unwrap();
+    fs::write(image.with_extension("sym"), "00000200 main\n0000020f loop\n").unwrap();
+
+    let symbols = load_symbols(&image, 0x200).unwrap();
+    assert_eq!(symbols.lookup(0x21d).unwrap().to_string(), "loop+0xe");
+    assert_eq!(symbols.lookup(0x21e), None);
+
+    let missing = directory().join("host-missing.bin");
+    assert!(load_symbols(&missing, 0x200).is_none());
+}
+
+#[test]
+fn executable_symbols() {
+    let mut symbols = SymbolTable::new();
+    symbols.insert(0x0001_0000, "main");
+    let executable = Executable {
+        symbols: Some(symbols),
+        ..Executable::new(0x0001_0000, vec![0; 15])
+    };
+    let image = directory().join("host-executable.vx");
+    fs::write(&image, executable.to_bytes()).unwrap();
+    // Embedded symbols win over a stale map
+    fs::write(image.with_extension("sym"), "00000200 stale\n").unwrap();
+
+    let symbols = load_symbols(&image, 0x200).unwrap();
+    assert_eq!(symbols.lookup(0x0001_000e).unwrap().to_string(), "main+0xe");
+    assert_eq!(symbols.lookup(0x0001_000f), None);
+    assert_eq!(symbols.lookup(0x200), None);
+}
+
+#[test]
+fn bios_and_program() {
+    let bios = directory().join("host-bios.bin");
+    fs::write(&bios, [0u8; 15]).unwrap();
+    fs::write(bios.with_extension("sym"), "00000200 reset\n").unwrap();
+    let program = directory().join("host-program.bin");
+    fs::write(&program, [0u8; 15]).unwrap();
+    fs::write(program.with_extension("sym"), "00100000 main\n").unwrap();
+
+    let symbols = load_all_symbols(Some(&bios), Some(&program), 0x0010_0000).unwrap();
+    assert_eq!(symbols.lookup(0x20e).unwrap().to_string(), "reset+0xe");
+    assert_eq!(symbols.lookup(0x20f), None);
+    assert_eq!(symbols.lookup(0x0010_000e).unwrap().to_string(), "main+0xe");
+    assert_eq!(symbols.lookup(0x0010_000f), None);
+
+    // Without a BIOS the program runs from the entry point, wherever it was asked to go
+    let symbols = load_all_symbols(None, Some(&bios), 0x0010_0000).unwrap();
+    assert_eq!(symbols.lookup(0x20e).unwrap().to_string(), "reset+0xe");
+}
@@ -0,0 +1,105 @@
+// Checks a recorded run hands devices' answers back unchanged on replay.
+
+use vixen::BusDevice;
+use vixen::devices::errors::{BusError, BusResult};
+use vixen_devices::replay::{Recorder, RecordingError, Replayer};
+
+// Answers reads with a counter and raises an event every third tick, standing in for devices
+// driven by the host
+#[derive(Debug, Default)]
+struct Sensor {
+    reads: u32,
+    ticks: u32,
+    start: u32,
+}
+
+impl BusDevice for Sensor {
+    fn get_port_count(&self) -> u32 {
+        2
+    }
+
+    fn get_base_address(&self) -> u32 {
+        0x0400_0000
+    }
+
+    fn read_port(&mut self, index: u32) -> BusResult<u32> {
+        self.reads += 1;
+        match index {
+            0 => Ok(self.start + self.reads),
+            _ => Err(BusError::EmptyBuffer),
+        }
+    }
+
+    fn write_port(&mut self, _: u32, _: u32) -> BusResult<()> {
+        Ok(())
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        self.ticks += 1;
+        if self.ticks.is_multiple_of(3) { Err(BusError::DeviceEvent) } else { Ok(()) }
+    }
+}
+
+// Ticks the device four times, reading both ports after each tick
+fn run(device: &mut dyn BusDevice) -> Vec<String> {
+    let mut seen = Vec::new();
+    for _ in 0..4 {
+        seen.push(format!("{:?}", device.tick().is_err()));
+        seen.push(format!("{:?}", device.read_port(0).ok()));
+        seen.push(format!("{:?}", device.read_port(1).ok()));
+    }
+    seen
+}
+
+#[test]
+fn replays_what_was_recorded() {
+    let mut recorder = Recorder::new();
+    let mut device = recorder.wrap(Box::new(Sensor { start: 100, ..Sensor::default() }));
+    let seen = run(&mut *device);
+    let recording = recorder.to_string();
+
+    assert!(recording.starts_with("vixen-recording 1\n0 1 read 0 00000065\n0 1 read 1 empty-buffer\n"), "{recording}");
+    assert!(recording.contains("0 2 tick device-event\n"), "{recording}");
+
+    // A device that would answer differently, and raise events on other ticks
+    let mut replayer = Replayer::parse(&recording).unwrap();
+    let mut device = replayer.wrap(Box::new(Sensor { start: 500, ticks: 1, ..Sensor::default() }));
+    assert_eq!(run(&mut *device), seen);
+    assert_eq!(replayer.divergence(), None);
+}
+
+#[test]
+fn notices_divergence() {
+    let mut recorder = Recorder::new();
+    let mut device = recorder.wrap(Box::new(Sensor::default()));
+    let _ = device.tick();
+    let _ = device.read_port(0);
+
+    let mut replayer = Replayer::parse(&recorder.to_string()).unwrap();
+    let mut device = replayer.wrap(Box::new(Sensor { start: 7, ..Sensor::default() }));
+    let _ = device.tick();
+    let _ = device.tick();
+    assert_eq!(replayer.divergence(), Some(1));
+    assert_eq!(device.read_port(0).ok(), Some(8));
+}
+
+#[test]
+fn rejects_bad_recordings() {
+    assert_eq!(Replayer::parse("0 0 tick device-event\n").unwrap_err(), RecordingError { line: 1 });
+    assert_eq!(Replayer::parse("vixen-recording 1\n0 0 tick sneeze\n").unwrap_err(), RecordingError { line: 2 });
+    assert_eq!(Replayer::parse("vixen-recording 1\n\n0 x read 0 1\n").unwrap_err(), RecordingError { line: 3 });
+}
+
+#[test]
+fn notices_devices_that_were_never_attached() {
+    let recording = "vixen-recording 1\n0 1 read 0 00000001\n18446744073709551615 5 read 0 00000001\n100000000000000 3 tick device-event\n";
+    let mut replayer = Replayer::parse(recording).unwrap();
+    let mut device = replayer.wrap(Box::new(Sensor::default()));
+    let _ = device.tick();
+    assert_eq!(device.read_port(0).ok(), Some(1));
+    assert_eq!(replayer.divergence(), Some(3));
+
+    let mut replayer = Replayer::parse("vixen-recording 1\n1 4 tick device-event\n").unwrap();
+    let _device = replayer.wrap(Box::new(Sensor::default()));
+    assert_eq!(replayer.divergence(), Some(4));
+}
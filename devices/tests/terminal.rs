@@ -0,0 +1,73 @@
+// Checks the terminal gives up on output it can't write instead of taking the emulator down.
+
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use vixen::BusDevice;
+use vixen_devices::{PowerRequest, PowerSignal, StdinReader, Terminal};
+
+#[derive(Debug)]
+struct NoInput;
+
+impl StdinReader for NoInput {
+    fn read(&self) -> Option<u8> {
+        None
+    }
+}
+
+// Takes `room` bytes, then fails like a pipe whose reader went away
+#[derive(Clone)]
+struct Pipe {
+    written: Rc<RefCell<Vec<u8>>>,
+    room: usize,
+}
+
+impl Write for Pipe {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut written = self.written.borrow_mut();
+        if written.len() + buf.len() > self.room {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        written.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn type_out(terminal: &mut Terminal<NoInput>, text: &[u8]) {
+    for ch in text {
+        terminal.write_port(0, u32::from(*ch)).ok().unwrap();
+        terminal.tick().ok().unwrap();
+    }
+}
+
+#[test]
+fn closed_output_stops_the_machine() {
+    let pipe = Pipe { written: Rc::default(), room: 3 };
+    let power = PowerSignal::new();
+    let mut terminal = Terminal::new(NoInput).with_output(pipe.clone()).with_power_signal(power.clone());
+
+    type_out(&mut terminal, b"abc");
+    assert_eq!(power.take(), None);
+
+    type_out(&mut terminal, b"defg");
+    assert_eq!(power.take(), Some(PowerRequest::OutputClosed));
+    assert_eq!(*pipe.written.borrow(), b"abc");
+
+    // Asked once, later output is dropped quietly
+    type_out(&mut terminal, b"h");
+    assert_eq!(power.take(), None);
+}
+
+#[test]
+fn closed_output_without_power_signal() {
+    let pipe = Pipe { written: Rc::default(), room: 0 };
+    let mut terminal = Terminal::new(NoInput).with_output(pipe.clone());
+
+    type_out(&mut terminal, b"lost");
+    assert!(pipe.written.borrow().is_empty());
+}
@@ -0,0 +1,50 @@
+// Checks devices driven by the instruction count behave the same on every run.
+
+use std::path::Path;
+use std::time::Duration;
+
+use vixen::BusDevice;
+use vixen_devices::board::DeviceDescription;
+use vixen_devices::{Board, PowerSignal, RealTimeClock, ScheduleError, ScheduledInput, StdinReader, Terminal, Time};
+
+#[test]
+fn clock_follows_ticks() {
+    let mut rtc = RealTimeClock::new(Time::new(100, 0)).with_virtual_time(Duration::from_millis(300));
+    for _ in 0..5 {
+        assert!(rtc.tick().is_ok());
+    }
+
+    assert_eq!((rtc.secs(), rtc.nanos()), (101, 500_000_000));
+}
+
+#[test]
+fn input_arrives_on_schedule() {
+    let input = ScheduledInput::parse("3 hi\\n\n\n1 \\x41\n").unwrap();
+    let read: Vec<_> = (0..8).map(|_| input.read()).collect();
+
+    assert_eq!(read, [None, Some(b'A'), None, Some(b'h'), Some(b'i'), Some(b'\n'), None, None]);
+}
+
+#[test]
+fn rejects_bad_schedules() {
+    assert_eq!(ScheduledInput::parse("10 ok\nsoon text").unwrap_err(), ScheduleError { line: 2 });
+    assert_eq!(ScheduledInput::parse("10").unwrap_err(), ScheduleError { line: 1 });
+    assert_eq!(ScheduledInput::parse("10 \\q").unwrap_err(), ScheduleError { line: 1 });
+}
+
+#[test]
+fn board_runs_on_virtual_time() {
+    let board = Board::parse("[[device]]\ntype = \"rtc\"\n\n[virtual_time]\ninstruction_time = 250\n", Path::new("")).unwrap();
+    assert_eq!(board.virtual_time, Some(Duration::from_nanos(250)));
+
+    let mut terminal = || Terminal::new(ScheduledInput::default());
+    let mut rtc = DeviceDescription::Rtc { base_address: None, epoch: None }
+        .create(&mut terminal, &PowerSignal::new(), board.virtual_time);
+    for _ in 0..4 {
+        assert!(rtc.tick().is_ok());
+    }
+    assert_eq!((rtc.read_port(0).ok(), rtc.read_port(1).ok()), (Some(0), Some(1000)));
+
+    let board = Board::parse("[virtual_time]\n", Path::new("")).unwrap();
+    assert_eq!(board.virtual_time, Some(Duration::from_micros(1)));
+}
@@ -1,8 +1,11 @@
 use std::ffi::OsString;
+use std::fmt::Write;
 use std::process::exit;
 use std::{env, fs};
 use vixen::{CPU, MEMORY_NONE};
+use vixen::core::{Executable, SymbolLookup, SymbolTable};
 use vixen::cpu::Decoder;
+use vixen::models::DEFAULT_MODEL;
 
 fn main() {
     let path = get_rom_path().unwrap_or_else(|| {
@@ -24,6 +27,15 @@ fn main() {
         exit(2);
     }
 
+    if Executable::is_executable(&rom) {
+        let executable = Executable::parse(&rom).unwrap_or_else(|e| {
+            eprintln!("\u{1b}[33mFailed to read executable: {e}\u{1b}[0m");
+            exit(2);
+        });
+        println!("{}", disassemble_executable(&executable).trim_end());
+        return;
+    }
+
     let mut cpu = CPU::new(MEMORY_NONE);
     if let Err(e) = cpu.load_rom(&rom) {
         eprintln!("\u{1b}[33mFailed to load ROM into CPU: {e}\u{1b}[0m");
@@ -35,7 +47,7 @@ fn main() {
     println!("{disassembled}");
 }
 
-#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_possible_truncation, clippy::format_push_string)]
 fn disassemble_rom(mut cpu: CPU, rom: &[u8]) -> String {
     let mut disassembled = String::new();
 
@@ -52,6 +64,48 @@ fn disassemble_rom(mut cpu: CPU, rom: &[u8]) -> String {
     disassembled
 }
 
+// Every segment on its own, with labels from the executable's symbols
+fn disassemble_executable(executable: &Executable) -> String {
+    let mut disassembled = String::new();
+    let model = executable.model.unwrap_or(DEFAULT_MODEL);
+    let _ = writeln!(disassembled, "; executable for {} CPU, entry point {:0>8x}",
+                     executable.model.map_or("any", |model| model.short_name), executable.entry_point);
+    if let Some(debug) = &executable.debug {
+        let _ = writeln!(disassembled, "; {debug}");
+    }
+
+    let end = executable.segments.iter()
+        .map(|segment| segment.address as usize + segment.data.len())
+        .max()
+        .unwrap_or(0);
+    let mut cpu = CPU::new_with_model(MEMORY_NONE.max(end + 15), model);
+    if let Err(e) = cpu.load_segments(&executable.load_segments(), executable.entry_point) {
+        eprintln!("\u{1b}[33mFailed to load executable into CPU: {e}\u{1b}[0m");
+        exit(2);
+    }
+
+    let symbols = executable.symbols.clone().unwrap_or_default();
+    for segment in &executable.segments {
+        let _ = writeln!(disassembled, "\n; segment at {:0>8x}, {} bytes", segment.address, segment.data.len());
+        disassemble_segment(&cpu, segment.address, segment.data.len(), &symbols, &mut disassembled);
+    }
+
+    disassembled
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn disassemble_segment(cpu: &CPU, start: u32, length: usize, symbols: &SymbolTable, disassembled: &mut String) {
+    for position in (start..start + length as u32).step_by(15) {
+        if let Some(symbol) = symbols.lookup(position).filter(|symbol| symbol.offset == 0) {
+            let _ = writeln!(disassembled, "{}:", symbol.name);
+        }
+
+        let text = cpu.read_instruction_string(position);
+        let _ = writeln!(disassembled, "{text:<32} ; {position:0>8x}: {}",
+                         cpu.extract_instruction_infailible(position));
+    }
+}
+
 fn get_rom_path() -> Option<OsString> {
     // Skip binary path
     env::args_os().nth(1)
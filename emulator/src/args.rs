@@ -0,0 +1,214 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use clap::{Parser, ValueEnum};
+use vixen::core::CpuModel;
+use vixen::cpu::{CacheConfig, WritePolicy};
+use vixen::models::find_model;
+use vixen_devices::board::{parse_memory, DeviceDescription};
+
+/// An emulator for Vixen processors
+///
+/// Exits with the code the guest shuts down with, 1 on an unhandled interrupt, 2 when the machine
+/// can't be set up, 3 when the instruction limit is reached, 4 when the time limit is reached and 5
+/// when the terminal output can't be written to anymore, e.g. a pipe closed by the reader.
+///
+/// In batch mode nothing is read from the keyboard: the terminal takes its input from `--input`
+/// or whatever is piped in, and the machine state is written to stderr when it stops.
+#[derive(Parser, Debug)]
+#[allow(clippy::struct_excessive_bools)]
+#[command(about)]
+pub struct Args {
+    /// Program loaded at 0x200, or at `--program-address` when booting a BIOS, unless it is a Vixen
+    /// executable saying where it goes. Optional when the machine file loads ROM images itself
+    #[arg(required_unless_present_any = ["machine", "bios"])]
+    pub rom: Option<PathBuf>,
+    /// Firmware loaded at 0x200 and started instead of the program, which it jumps to once done
+    #[arg(long, value_name = "FILE")]
+    pub bios: Option<PathBuf>,
+    /// Hexadecimal address the program is loaded at when booting a BIOS
+    #[arg(long, value_name = "ADDRESS", requires = "bios", default_value = "100000", value_parser = parse_address)]
+    pub program_address: u32,
+    /// Machine description file setting up memory, CPU model, ROM images and devices
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["memory", "model", "devices", "epoch"])]
+    pub machine: Option<PathBuf>,
+    /// Installed memory, a preset such as `64M` or `MEMORY_64M`, or a number of bytes on top of the
+    /// base system (hexadecimal with a `0x` prefix)
+    #[arg(short, long, default_value = "64M", value_parser = parse_memory)]
+    pub memory: usize,
+    /// CPU model, e.g. `pallet`, `kanto` or `johto`
+    #[arg(long, default_value = "kanto", value_parser = parse_model)]
+    pub model: &'static CpuModel,
+    /// Devices to attach, comma-separated
+    #[arg(short, long, value_delimiter = ',', default_value = "terminal,rtc,power")]
+    pub devices: Vec<DeviceKind>,
+    /// Drive device time from the instruction count instead of the host clock, each instruction
+    /// taking this many nanoseconds, so runs are reproducible
+    #[arg(long, value_name = "NANOS", num_args = 0..=1, default_missing_value = "1000")]
+    pub virtual_time: Option<u64>,
+    /// Seconds since the Unix epoch the real-time clock starts at, 0 in virtual time and the host
+    /// time otherwise
+    #[arg(long, value_name = "SECONDS")]
+    pub epoch: Option<u32>,
+    /// Type terminal input at fixed instruction counts, one `COUNT TEXT` entry per line
+    #[arg(long, value_name = "FILE", conflicts_with = "input")]
+    pub input_schedule: Option<PathBuf>,
+    /// Log terminal input, clock readings and device interrupts to a file as they happen
+    #[arg(long, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Feed the devices from a file written by `--record` instead of the keyboard and host clock
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["input", "input_schedule"])]
+    pub replay: Option<PathBuf>,
+    /// Stop after executing this many instructions
+    #[arg(long, value_name = "COUNT")]
+    pub max_instructions: Option<u64>,
+    /// Stop after running for this many seconds
+    #[arg(long, value_name = "SECONDS", value_parser = parse_timeout)]
+    pub timeout: Option<Duration>,
+    /// Run at this many instructions per second instead of as fast as possible, e.g. `2M` or `500k`
+    #[arg(long, value_name = "RATE", value_parser = parse_rate)]
+    pub speed: Option<f64>,
+    /// What `--speed` counts, cycles include cache penalties
+    #[arg(long, value_enum, default_value = "instructions")]
+    pub pace: Pace,
+    /// Simulate an instruction and a data cache, counting hits and misses and adding miss
+    /// penalties to the cycle count
+    #[arg(long)]
+    pub cache: bool,
+    /// Size of each cache in bytes
+    #[arg(long, value_name = "BYTES", requires = "cache", default_value_t = CacheConfig::default().size)]
+    pub cache_size: usize,
+    /// Size of a cache line in bytes, a power of two
+    #[arg(long, value_name = "BYTES", requires = "cache", default_value_t = CacheConfig::default().line_size)]
+    pub cache_line_size: usize,
+    /// Lines per cache set
+    #[arg(long, value_name = "WAYS", requires = "cache", default_value_t = CacheConfig::default().associativity)]
+    pub cache_associativity: usize,
+    /// When data cache writes reach memory
+    #[arg(long, value_enum, requires = "cache", default_value = "write-back")]
+    pub cache_write_policy: CacheWritePolicy,
+    /// Cycles lost on each cache miss and each dirty line written back
+    #[arg(long, value_name = "CYCLES", requires = "cache", default_value_t = CacheConfig::default().miss_penalty)]
+    pub cache_miss_penalty: u64,
+    /// Write every executed instruction to a file, `-` writes to stderr
+    #[arg(long, value_name = "FILE")]
+    pub trace: Option<PathBuf>,
+    /// Write a JSON crash report on an unhandled interrupt, `-` prints it instead of the stack trace
+    #[arg(long, value_name = "FILE")]
+    pub crash_report: Option<PathBuf>,
+    /// Write the whole machine state to a core file on an unhandled interrupt, for `vdbg --core`
+    #[arg(long, value_name = "FILE")]
+    pub core_dump: Option<PathBuf>,
+    /// Run without a keyboard, e.g. in CI, and dump the registers to stderr on exit
+    #[arg(long)]
+    pub batch: bool,
+    /// File the terminal reads from in batch mode, stdin when left out
+    #[arg(long, value_name = "FILE", requires = "batch")]
+    pub input: Option<PathBuf>,
+    /// File the terminal writes to in batch mode, stdout when left out
+    #[arg(long, value_name = "FILE", requires = "batch")]
+    pub output: Option<PathBuf>,
+    /// Memory to include in the batch mode dump, as hexadecimal `START:LENGTH`, may be repeated
+    #[arg(long, value_name = "START:LENGTH", requires = "batch", value_parser = parse_range)]
+    pub dump_memory: Vec<(u32, u32)>,
+    /// Only print what the program itself writes
+    #[arg(short, long, conflicts_with = "verbose")]
+    pub quiet: bool,
+    /// Describe the machine on startup and print statistics when it stops
+    #[arg(short, long)]
+    pub verbose: bool,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceKind {
+    Terminal,
+    Rtc,
+    /// Lets the guest shut down with an exit code or reboot
+    Power,
+    /// Attach nothing, e.g. `--devices none`
+    None,
+}
+
+impl DeviceKind {
+    pub fn description(self, epoch: Option<u32>) -> Option<DeviceDescription> {
+        match self {
+            DeviceKind::Terminal => Some(DeviceDescription::Terminal { base_address: None }),
+            DeviceKind::Rtc => Some(DeviceDescription::Rtc { base_address: None, epoch }),
+            DeviceKind::Power => Some(DeviceDescription::Power { base_address: None }),
+            DeviceKind::None => None,
+        }
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheWritePolicy {
+    WriteBack,
+    WriteThrough,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    Instructions,
+    Cycles,
+}
+
+impl Args {
+    pub fn cache_config(&self) -> Option<CacheConfig> {
+        self.cache.then_some(CacheConfig {
+            size: self.cache_size,
+            line_size: self.cache_line_size,
+            associativity: self.cache_associativity,
+            write_policy: match self.cache_write_policy {
+                CacheWritePolicy::WriteBack => WritePolicy::WriteBack,
+                CacheWritePolicy::WriteThrough => WritePolicy::WriteThrough,
+            },
+            miss_penalty: self.cache_miss_penalty,
+        })
+    }
+}
+
+impl Pace {
+    pub fn name(self) -> &'static str {
+        match self {
+            Pace::Instructions => "instructions",
+            Pace::Cycles => "cycles",
+        }
+    }
+}
+
+// A number with an optional k, M or G suffix and an optional trailing `Hz`
+fn parse_rate(value: &str) -> Result<f64, String> {
+    let number = value.strip_suffix("Hz").unwrap_or(value);
+    let (number, scale) = match number.char_indices().last() {
+        Some((i, 'k' | 'K')) => (&number[..i], 1e3),
+        Some((i, 'M')) => (&number[..i], 1e6),
+        Some((i, 'G')) => (&number[..i], 1e9),
+        _ => (number, 1.0),
+    };
+
+    number.parse::<f64>().ok()
+        .map(|rate| rate * scale)
+        .filter(|rate| rate.is_finite() && *rate > 0.0)
+        .ok_or_else(|| format!("`{value}` is not a positive rate such as `2M` or `500k`"))
+}
+
+fn parse_timeout(value: &str) -> Result<Duration, String> {
+    value.parse::<f64>().ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .ok_or_else(|| format!("`{value}` is not a duration in seconds such as `2.5`"))
+}
+
+fn parse_model(value: &str) -> Result<&'static CpuModel, String> {
+    find_model(value).ok_or_else(|| format!("unknown CPU model `{value}`"))
+}
+
+fn parse_address(value: &str) -> Result<u32, String> {
+    u32::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|_| format!("`{value}` is not a hexadecimal address"))
+}
+
+fn parse_range(value: &str) -> Result<(u32, u32), String> {
+    let parse = |part: &str| u32::from_str_radix(part.trim_start_matches("0x"), 16).ok();
+    value.split_once(':')
+        .and_then(|(start, length)| Some((parse(start)?, parse(length)?)))
+        .ok_or_else(|| format!("`{value}` is not a hexadecimal START:LENGTH range"))
+}
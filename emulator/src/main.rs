@@ -1,66 +1,376 @@
-use std::ffi::OsString;
+mod args;
+mod pacing;
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
 use std::process::exit;
-use std::{env, fs};
+use std::time::{Duration, Instant};
 
-use vixen::core::Interrupt;
+use clap::Parser;
+use vixen::core::{CoreDump, CrashReport, Interrupt, SymbolLookup, SymbolTable};
 use vixen::core::StackTrace;
-use vixen::{BusDevice, CPU, MEMORY_64M};
-use vixen::CPUResult;
-use vixen_devices::{RealTimeClock, Terminal};
+use vixen::cpu::{Decoder, DEFAULT_ENTRY_POINT};
+use vixen::{CPU, DEVICE_SPACE_START};
+use vixen_devices::host;
+use vixen_devices::replay::{Recorder, Replayer};
+use vixen_devices::board::RomImage;
+use vixen_devices::{Board, PowerRequest, PowerSignal, ScheduledInput, StdinReader, Terminal, TerminalStdin};
 
-fn main() {
-    let path = get_rom_path().unwrap_or_else(|| {
-        eprintln!("\u{1b}[33mUsage: vxemu {{rom}}\u{1b}[0m");
-        eprintln!("\u{1b}[33mPlease provide path to ROM.\u{1b}[0m");
+use args::{Args, Pace};
+use pacing::Pacer;
 
-        exit(-1);
-    });
+// Checking the clock on every instruction is needlessly slow
+const TIMEOUT_CHECK_INTERVAL: u64 = 4096;
+
+enum Stop {
+    Shutdown(u32),
+    Interrupt(Interrupt),
+    InstructionLimit,
+    TimeLimit,
+    OutputClosed
+}
 
-    let rom = fs::read(path);
+struct Run {
+    stop: Stop,
+    executed: u64,
+    cycles: u64,
+    elapsed: Duration
+}
+
+fn main() {
+    let args = Args::parse();
+    let symbols = host::load_all_symbols(args.bios.as_deref(), args.rom.as_deref(), args.program_address);
+
+    // With a BIOS, the BIOS takes the program's place at 0x200 and the program becomes an image
+    let rom = args.bios.as_ref().or(args.rom.as_ref()).map_or(Ok(Vec::new()), fs::read);
     let rom = rom.unwrap_or_else(|e| {
         eprintln!("\u{1b}[33mFailed to read ROM file: {e}\u{1b}[0m");
-        exit(-1);
+        exit(2);
     });
 
-    if rom.len() > 67_108_864 {
+    let board = load_board(&args);
+
+    let rom_space = rom_space(board.memory);
+    if rom.len() > rom_space {
         eprintln!("\u{1b}[33mROM is too large ({} bytes) for the reserved memory space \
-        (67108864 bytes).\u{1b}[0m", rom.len());
+        ({rom_space} bytes).\u{1b}[0m", rom.len());
         exit(2);
     }
 
-    let mut cpu = CPU::new(MEMORY_64M);
-    if let Err(e) = cpu.load_rom(&rom) {
-        eprintln!("\u{1b}[33mFailed to load ROM into CPU: {e}\u{1b}[0m");
+    let power = PowerSignal::new();
+    let terminal = || open_terminal(&args).unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to open terminal input or output: {e}\u{1b}[0m");
+        exit(2);
+    });
+    let mut recorder = args.record.as_ref().map(|_| Recorder::new());
+    let mut replayer = args.replay.as_deref().map(open_replay).transpose().unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to read recording: {e}\u{1b}[0m");
+        exit(2);
+    });
+    let wrap = |device| match (&mut recorder, &mut replayer) {
+        (Some(recorder), _) => recorder.wrap(device),
+        (None, Some(replayer)) => replayer.wrap(device),
+        (None, None) => device,
+    };
+    let mut cpu = board.build_with(&rom, terminal, &power, wrap).unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to set up the machine: {e}\u{1b}[0m");
+        exit(2);
+    });
+
+    let cache = args.cache_config();
+    let caches = cpu.caches.set_instruction_cache(cache).and_then(|()| cpu.caches.set_data_cache(cache));
+    if let Err(e) = caches {
+        eprintln!("\u{1b}[33mFailed to set up the caches: {e}\u{1b}[0m");
         exit(2);
     }
 
-    let devices: Vec<Box<dyn BusDevice>> = vec![
-        Box::new(Terminal::default()),
-        Box::new(RealTimeClock::now()),
-    ];
-    if let Err(e) = cpu.register_devices(devices) {
-        eprintln!("\u{1b}[33mFailed to start up devices: {e}\u{1b}[0m");
+    let mut trace = open_trace(&args).unwrap_or_else(|e| {
+        eprintln!("\u{1b}[33mFailed to open trace output: {e}\u{1b}[0m");
         exit(2);
+    });
+
+    if args.verbose {
+        eprintln!("\u{1b}[33mLoaded {} bytes of ROM and {} images on a {} CPU with {} bytes of memory and devices: {:?}\u{1b}[0m",
+                  rom.len(), board.roms.len(), board.model.short_name, board.memory, board.devices);
+    }
+
+    let lookup = symbols.as_ref().map(|symbols| symbols as &dyn SymbolLookup);
+    let mut reboot = |cpu: &mut CPU| {
+        if let Err(e) = board.reboot(cpu, &rom) {
+            eprintln!("\u{1b}[33mFailed to reboot: {e}\u{1b}[0m");
+            exit(2);
+        }
+    };
+    let run = run_cpu(&mut cpu, &args, trace.as_mut(), lookup, &power, &mut reboot);
+    if let Some(trace) = &mut trace {
+        let _ = trace.flush();
+    }
+
+    if args.verbose {
+        print_statistics(&run, &cpu, &args);
+    }
+
+    if let (Some(recorder), Some(path)) = (&recorder, &args.record) {
+        if let Err(e) = fs::write(path, recorder.to_string()) {
+            eprintln!("\u{1b}[33mFailed to write recording: {e}\u{1b}[0m");
+        }
+    }
+    if let Some(tick) = replayer.as_ref().and_then(Replayer::divergence) {
+        if !args.quiet {
+            eprintln!("\u{1b}[33mRun left the recording after {tick} device ticks, devices ran live from there\u{1b}[0m");
+        }
     }
 
-    if let Err(interrupt) = run_cpu(&mut cpu) {
-        on_unhandled_interrupt(&cpu, interrupt);
-        exit(1);
+    if args.batch && !args.quiet {
+        dump_state(&cpu, &args.dump_memory);
+    }
+
+    match run.stop {
+        // Exit codes are truncated to what the host supports, the guest picks small ones
+        #[allow(clippy::cast_possible_wrap)]
+        Stop::Shutdown(code) => exit(code as i32),
+        Stop::Interrupt(interrupt) => {
+            on_unhandled_interrupt(&cpu, interrupt, symbols.as_ref(), &args);
+            exit(1);
+        }
+        Stop::InstructionLimit => {
+            if !args.quiet {
+                eprintln!("\u{1b}[33mStopped after {} instructions at {:0>8x}\u{1b}[0m", run.executed, cpu.program_counter);
+            }
+            exit(3);
+        }
+        Stop::TimeLimit => {
+            if !args.quiet {
+                eprintln!("\u{1b}[33mStopped after {:.3}s at {:0>8x}\u{1b}[0m", run.elapsed.as_secs_f64(), cpu.program_counter);
+            }
+            exit(4);
+        }
+        Stop::OutputClosed => {
+            if !args.quiet {
+                eprintln!("\u{1b}[33mStopped at {:0>8x}, the terminal output was closed\u{1b}[0m", cpu.program_counter);
+            }
+            exit(5);
+        }
     }
 }
 
-fn get_rom_path() -> Option<OsString> {
-    // Skip binary path
-    env::args_os().nth(1)
+// ROM is mapped from 0x200 up to the device space, or to the end of memory if that comes first
+fn rom_space(memory: usize) -> usize {
+    memory.min(DEVICE_SPACE_START).saturating_sub(DEFAULT_ENTRY_POINT as usize)
 }
 
-fn run_cpu(cpu: &mut CPU) -> CPUResult<()> {
-    loop {
-        cpu.tick()?;
-        cpu.program_counter += 15;
+fn load_board(args: &Args) -> Board {
+    let mut board = match &args.machine {
+        Some(path) => Board::load(path).unwrap_or_else(|e| {
+            eprintln!("\u{1b}[33mFailed to read machine description: {e}\u{1b}[0m");
+            exit(2);
+        }),
+        None => Board {
+            memory: args.memory,
+            model: args.model,
+            roms: Vec::new(),
+            entry_point: DEFAULT_ENTRY_POINT,
+            devices: args.devices.iter().filter_map(|kind| kind.description(args.epoch)).collect(),
+            virtual_time: None,
+        }
+    };
+    if let Some(nanos) = args.virtual_time {
+        board.virtual_time = Some(Duration::from_nanos(nanos));
+    }
+    if let (Some(_), Some(path)) = (&args.bios, &args.rom) {
+        board.roms.push(RomImage { path: path.clone(), address: args.program_address });
+    }
+    board
+}
+
+fn print_statistics(run: &Run, cpu: &CPU, args: &Args) {
+    let seconds = run.elapsed.as_secs_f64().max(f64::EPSILON);
+    // Precision loss only matters past 2^52 instructions
+    #[allow(clippy::cast_precision_loss)]
+    let (rate, cycle_rate) = (run.executed as f64 / seconds, run.cycles as f64 / seconds);
+    eprintln!("\u{1b}[33mExecuted {} instructions and {} cycles in {seconds:.3}s ({:.2} MIPS, {:.2} MHz)\u{1b}[0m",
+             run.executed, run.cycles, rate / 1_000_000.0, cycle_rate / 1_000_000.0);
+
+    if let Some(target) = args.speed {
+        let achieved = match args.pace {
+            Pace::Instructions => rate,
+            Pace::Cycles => cycle_rate,
+        };
+        eprintln!("\u{1b}[33mAchieved {:.1}% of the requested {target} {} per second\u{1b}[0m",
+                 achieved / target * 100.0, args.pace.name());
+    }
+
+    for (name, statistics) in [("Instruction", cpu.caches.instruction_statistics()), ("Data", cpu.caches.data_statistics())] {
+        if let Some(statistics) = statistics {
+            eprintln!("\u{1b}[33m{name} cache: {} hits, {} misses ({:.2}% hit rate), {} writebacks, {} memory writes\u{1b}[0m",
+                      statistics.hits, statistics.misses, statistics.hit_rate() * 100.0,
+                      statistics.writebacks, statistics.memory_writes);
+        }
     }
 }
 
-fn on_unhandled_interrupt(cpu: &CPU, interrupt: Interrupt) {
-    println!("\u{1b}[33m{}\u{1b}[0m", StackTrace::new(interrupt, cpu));
+// In batch mode the guest's input and output are plain files or pipes, with no keyboard involved
+fn open_terminal(args: &Args) -> io::Result<Terminal<Box<dyn StdinReader>>> {
+    // Replayed input comes from the recording, the terminal itself stays silent
+    if args.replay.is_some() {
+        let terminal = Terminal::new(Box::new(ScheduledInput::default()) as Box<dyn StdinReader>);
+        return Ok(match &args.output {
+            Some(path) => terminal.with_output(File::create(path)?),
+            None => terminal,
+        });
+    }
+
+    let stdin: Box<dyn StdinReader> = match (&args.input_schedule, &args.input) {
+        (Some(path), _) => {
+            let schedule = fs::read_to_string(path)?;
+            Box::new(ScheduledInput::parse(&schedule).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?)
+        }
+        (None, Some(path)) => Box::new(TerminalStdin::from_reader(File::open(path)?)),
+        (None, None) if args.batch => Box::new(TerminalStdin::from_reader(io::stdin())),
+        (None, None) => Box::new(TerminalStdin::new()),
+    };
+
+    let terminal = Terminal::new(stdin);
+    match &args.output {
+        Some(path) => Ok(terminal.with_output(File::create(path)?)),
+        None => Ok(terminal),
+    }
+}
+
+fn open_replay(path: &Path) -> Result<Replayer, String> {
+    let recording = fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    Replayer::parse(&recording).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn open_trace(args: &Args) -> io::Result<Option<Box<dyn Write>>> {
+    let Some(path) = &args.trace else {
+        return Ok(None);
+    };
+
+    if path == Path::new("-") {
+        return Ok(Some(Box::new(BufWriter::new(io::stderr()))));
+    }
+    Ok(Some(Box::new(BufWriter::new(File::create(path)?))))
+}
+
+fn run_cpu(cpu: &mut CPU, args: &Args, mut trace: Option<&mut Box<dyn Write>>, symbols: Option<&dyn SymbolLookup>,
+           power: &PowerSignal, reboot: &mut dyn FnMut(&mut CPU)) -> Run {
+    let start = Instant::now();
+    let timeout = args.timeout;
+    let mut pacer = args.speed.map(Pacer::new);
+    let start_cycles = cpu.cycles;
+    let mut executed = 0;
+
+    let stop = loop {
+        if args.max_instructions.is_some_and(|limit| executed >= limit) {
+            break Stop::InstructionLimit;
+        }
+        if executed % TIMEOUT_CHECK_INTERVAL == 0 && timeout.is_some_and(|timeout| start.elapsed() >= timeout) {
+            break Stop::TimeLimit;
+        }
+
+        if let Some(output) = &mut trace {
+            let pc = cpu.program_counter;
+            let _ = match symbols.and_then(|symbols| symbols.lookup(pc)) {
+                Some(symbol) => writeln!(output, "{pc:0>8x} <{symbol}>: {}", cpu.read_instruction_string(pc)),
+                None => writeln!(output, "{pc:0>8x}: {}", cpu.read_instruction_string(pc))
+            };
+        }
+
+        if let Err(interrupt) = cpu.tick() {
+            break Stop::Interrupt(interrupt);
+        }
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+        executed += 1;
+
+        if let Some(pacer) = &mut pacer {
+            pacer.pace(match args.pace {
+                Pace::Instructions => executed,
+                Pace::Cycles => cpu.cycles - start_cycles,
+            });
+        }
+
+        match power.take() {
+            Some(PowerRequest::Shutdown(code)) => break Stop::Shutdown(code),
+            Some(PowerRequest::Reboot) => reboot(cpu),
+            Some(PowerRequest::OutputClosed) => break Stop::OutputClosed,
+            None => {}
+        }
+    };
+
+    Run {
+        stop,
+        executed,
+        cycles: cpu.cycles - start_cycles,
+        elapsed: start.elapsed()
+    }
+}
+
+fn on_unhandled_interrupt(cpu: &CPU, interrupt: Interrupt, symbols: Option<&SymbolTable>, args: &Args) {
+    if let Some(path) = &args.core_dump {
+        if let Err(e) = fs::write(path, CoreDump::capture(interrupt, cpu).to_bytes()) {
+            eprintln!("\u{1b}[33mFailed to write core dump: {e}\u{1b}[0m");
+        }
+    }
+
+    if let Some(path) = &args.crash_report {
+        let mut report = CrashReport::new(interrupt, cpu);
+        if let Some(symbols) = symbols {
+            report = report.with_symbols(symbols);
+        }
+
+        if path == Path::new("-") {
+            println!("{report}");
+            return;
+        }
+        if let Err(e) = fs::write(path, format!("{report}\n")) {
+            eprintln!("\u{1b}[33mFailed to write crash report: {e}\u{1b}[0m");
+        }
+    }
+
+    if args.quiet {
+        return;
+    }
+
+    let mut trace = StackTrace::new(interrupt, cpu);
+    if let Some(symbols) = symbols {
+        trace = trace.with_symbols(symbols);
+    }
+    // Keep the guest's output clean when it is being captured
+    if args.batch {
+        eprintln!("{trace}");
+    } else {
+        println!("\u{1b}[33m{trace}\u{1b}[0m");
+    }
+}
+
+// Same layout as the debugger's `registers` and `memory` commands, without the colours
+fn dump_state(cpu: &CPU, ranges: &[(u32, u32)]) {
+    let registers = &cpu.registers;
+    eprintln!("sr  = {}, sp  = {:0>8x}, pc  = {:0>8x}", cpu.status_register, cpu.stack_pointer, cpu.program_counter);
+    let values = [
+        registers.r0, registers.r1, registers.r2, registers.r3, registers.r4,
+        registers.r5, registers.r6, registers.r7, registers.r8, registers.r9,
+        registers.r10, registers.r11, registers.r12, registers.r13, registers.r14,
+    ];
+    for (row, chunk) in values.chunks(3).enumerate() {
+        let line = chunk.iter().enumerate()
+            .map(|(column, value)| format!("{:<3} = {value:0>8x}", format!("r{}", row * 3 + column)))
+            .collect::<Vec<_>>();
+        eprintln!("{}", line.join(", "));
+    }
+
+    for &(start, length) in ranges {
+        let end = u64::from(start) + u64::from(length);
+        for line in (u64::from(start)..end).step_by(16) {
+            let bytes = (line..end.min(line + 16))
+                .map(|address| match usize::try_from(address).ok().and_then(|address| cpu.memory.get(address)) {
+                    Some(byte) => format!("{byte:0>2x}"),
+                    None => "??".to_string(),
+                })
+                .collect::<Vec<_>>();
+            eprintln!("{line:0>8x}:  {}", bytes.join(" "));
+        }
+    }
 }
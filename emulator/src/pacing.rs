@@ -0,0 +1,47 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+// How far ahead of schedule the guest may get before the host thread sleeps, shorter sleeps are
+// mostly overhead
+const SLEEP_THRESHOLD: Duration = Duration::from_millis(1);
+
+// Holds the guest to a fixed rate of instructions or cycles per second by sleeping whenever it
+// gets ahead of where it should be
+pub struct Pacer {
+    rate: f64,
+    start: Instant,
+    next_check: u64,
+    check_interval: u64,
+}
+
+impl Pacer {
+    pub fn new(rate: f64) -> Self {
+        // Checking roughly every millisecond of guest time keeps the pacing smooth without
+        // looking at the clock on every instruction
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let check_interval = (rate / 1000.0).max(1.0) as u64;
+
+        Self {
+            rate,
+            start: Instant::now(),
+            next_check: check_interval,
+            check_interval,
+        }
+    }
+
+    // `count` is how many instructions or cycles have run since the pacer was created
+    pub fn pace(&mut self, count: u64) {
+        if count < self.next_check {
+            return;
+        }
+        self.next_check = count + self.check_interval;
+
+        // Precision loss only matters past 2^52 instructions
+        #[allow(clippy::cast_precision_loss)]
+        let scheduled = Duration::from_secs_f64(count as f64 / self.rate);
+        let ahead = scheduled.saturating_sub(self.start.elapsed());
+        if ahead >= SLEEP_THRESHOLD {
+            thread::sleep(ahead);
+        }
+    }
+}
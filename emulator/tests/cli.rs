@@ -0,0 +1,341 @@
+// Runs the vemu binary on small hand-encoded ROMs and checks how it stops.
+
+use std::io::Read;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+use std::fs;
+use vixen::core::instruction::Operation;
+use vixen::core::{CoreDump, Executable};
+use vixen::models::PALLET;
+
+const IMMEDIATE: u32 = 0x0;
+const DIRECT: u32 = 0x1;
+const ABSOLUTE: u32 = 0x3;
+const IMPLIED: u32 = 0x5;
+const R0: u32 = 0x0001;
+const R1: u32 = 0x0011;
+
+fn emit(rom: &mut Vec<u8>, operation: Operation, operands: [(u32, u32); 3]) {
+    let modes = operands[0].0 | operands[1].0 << 4 | operands[2].0 << 8;
+    let opcode = (u32::from(u16::from(operation)) << 12 | modes).to_le_bytes();
+    rom.extend_from_slice(&opcode[..3]);
+    for (_, value) in operands {
+        rom.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn write_rom(name: &str, rom: &[u8]) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    fs::write(&path, rom).unwrap();
+    path
+}
+
+// add r0, r0, #1
+// jmpl $200
+fn spin() -> PathBuf {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Add, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 1)]);
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, 0x200), (IMPLIED, 0), (IMPLIED, 0)]);
+    write_rom("spin.bin", &rom)
+}
+
+// div r0, r0, #0, under a name of its own since tests run in parallel
+fn crash(name: &str) -> PathBuf {
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Div, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 0)]);
+    write_rom(name, &rom)
+}
+
+fn vemu(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_vemu")).args(args).output().unwrap()
+}
+
+#[test]
+fn instruction_limit() {
+    let rom = spin();
+    let trace = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("spin.trace");
+    let output = vemu(&[rom.to_str().unwrap(), "--devices", "none", "--max-instructions", "3",
+        "--trace", trace.to_str().unwrap()]);
+
+    assert_eq!(output.status.code(), Some(3));
+    let trace = fs::read_to_string(trace).unwrap();
+    assert_eq!(trace.lines().collect::<Vec<_>>(), [
+        "00000200: add r0, r0, #$1",
+        "0000020f: jmpl $00000200",
+        "00000200: add r0, r0, #$1"
+    ]);
+}
+
+#[test]
+fn time_limit() {
+    let output = vemu(&[spin().to_str().unwrap(), "-d", "none", "--timeout", "0.1", "--quiet"]);
+
+    assert_eq!(output.status.code(), Some(4));
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn unhandled_interrupt() {
+    let output = vemu(&[crash("interrupt.bin").to_str().unwrap(), "-d", "none", "--memory", "MEMORY_1M", "--model", "pallet",
+        "--crash-report", "-"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.contains(r#""code": 19"#), "{report}");
+}
+
+#[test]
+fn core_dump() {
+    let core = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("crash.core");
+    let output = vemu(&[crash("core.bin").to_str().unwrap(), "-d", "rtc", "--core-dump", core.to_str().unwrap(), "-q"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    let dump = CoreDump::parse(&fs::read(core).unwrap()).unwrap();
+    assert_eq!(u32::from(dump.interrupt), 0x13);
+    assert_eq!(dump.program_counter, 0x200);
+    assert_eq!(dump.devices.len(), 1);
+    assert!(dump.devices[0].state.starts_with("RealTimeClock"), "{}", dump.devices[0].state);
+}
+
+#[test]
+fn rejects_bad_configuration() {
+    let rom = crash("configuration.bin");
+    for args in [["--memory", "9G"], ["--model", "sinnoh"], ["--devices", "printer"]] {
+        let output = vemu(&[rom.to_str().unwrap(), args[0], args[1]]);
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+    }
+    for timeout in ["--timeout=-1", "--timeout=nan", "--timeout=inf", "--timeout=1e300"] {
+        let output = vemu(&[rom.to_str().unwrap(), timeout]);
+        assert_eq!(output.status.code(), Some(2), "{timeout}");
+    }
+}
+
+#[test]
+fn rom_must_fit_below_the_devices() {
+    // Everything from 0x200 up to the device space, spinning at the start
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, 0x200), (IMPLIED, 0), (IMPLIED, 0)]);
+    rom.resize(0x0400_0000, 0);
+    let rom = write_rom("largest.bin", &rom);
+    let output = vemu(&[rom.to_str().unwrap(), "--devices", "none", "--max-instructions", "1"]);
+    assert_eq!(output.status.code(), Some(3));
+
+    let rom = write_rom("too-large.bin", &vec![0; 0x0400_0001]);
+    let output = vemu(&[rom.to_str().unwrap(), "--devices", "none"]);
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("(67108864 bytes)"));
+}
+
+#[test]
+fn machine_file() {
+    let machine = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("crash.toml");
+    fs::write(&machine, "memory = \"1M\"\n\n[[rom]]\npath = \"machine.bin\"\naddress = 0x200\n").unwrap();
+    crash("machine.bin");
+
+    let output = vemu(&["--machine", machine.to_str().unwrap(), "--crash-report", "-"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stdout).unwrap().contains(r#""code": 19"#));
+
+    let output = vemu(&["--machine", machine.to_str().unwrap(), "--memory", "2M"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn guest_exit_code() {
+    // mov [$04000220], #42
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(ABSOLUTE, 0x0400_0220), (IMMEDIATE, 42), (IMPLIED, 0)]);
+    let rom = write_rom("shutdown.bin", &rom);
+
+    let output = vemu(&[rom.to_str().unwrap(), "--devices", "power"]);
+    assert_eq!(output.status.code(), Some(42));
+}
+
+#[test]
+fn boot_chain() {
+    // jmpl $00020000
+    let mut bios = Vec::new();
+    emit(&mut bios, Operation::Jmpl, [(ABSOLUTE, 0x0002_0000), (IMPLIED, 0), (IMPLIED, 0)]);
+    let bios = write_rom("bios.bin", &bios);
+
+    // mov [$04000220], #9
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(ABSOLUTE, 0x0400_0220), (IMMEDIATE, 9), (IMPLIED, 0)]);
+    let program = write_rom("program.bin", &program);
+
+    let output = vemu(&["--bios", bios.to_str().unwrap(), "--program-address", "20000", "--devices", "power",
+        program.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(9));
+
+    let output = vemu(&[program.to_str().unwrap(), "--program-address", "20000"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn executable() {
+    // mov [$04000220], #5
+    let mut program = Vec::new();
+    emit(&mut program, Operation::Mov, [(ABSOLUTE, 0x0400_0220), (IMMEDIATE, 5), (IMPLIED, 0)]);
+    let executable = Executable::new(0x0003_0000, program);
+    let path = write_rom("executable.vx", &executable.to_bytes());
+
+    let output = vemu(&[path.to_str().unwrap(), "--devices", "power"]);
+    assert_eq!(output.status.code(), Some(5));
+
+    let pallet = Executable { model: Some(&PALLET), ..executable };
+    let path = write_rom("pallet.vx", &pallet.to_bytes());
+    let output = vemu(&[path.to_str().unwrap(), "--devices", "power"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn batch_mode() {
+    // mov [$04000200], #'o'
+    // mov [$04000200], #'k'
+    // add r0, r0, #1 (x2, the terminal writes a character per instruction)
+    // mov [$04000220], #7
+    let mut rom = Vec::new();
+    for ch in *b"ok" {
+        emit(&mut rom, Operation::Mov, [(ABSOLUTE, 0x0400_0200), (IMMEDIATE, u32::from(ch)), (IMPLIED, 0)]);
+    }
+    for _ in 0..2 {
+        emit(&mut rom, Operation::Add, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 1)]);
+    }
+    emit(&mut rom, Operation::Mov, [(ABSOLUTE, 0x0400_0220), (IMMEDIATE, 7), (IMPLIED, 0)]);
+    let rom = write_rom("batch.bin", &rom);
+    let input = write_rom("batch.in", b"");
+
+    let output = vemu(&[rom.to_str().unwrap(), "--batch", "--input", input.to_str().unwrap(),
+        "--dump-memory", "200:10"]);
+    assert_eq!(output.status.code(), Some(7));
+    assert_eq!(output.stdout, b"ok");
+    let dump = String::from_utf8(output.stderr).unwrap();
+    assert!(dump.contains("r0  = 00000002, r1  = 00000000, r2  = 00000000"), "{dump}");
+    assert!(dump.contains("00000200:  03 05 05 00 02 00 04 6f 00 00 00 00 00 00 00 03"), "{dump}");
+
+    let captured = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("batch.out");
+    let output = vemu(&[rom.to_str().unwrap(), "--batch", "--input", input.to_str().unwrap(),
+        "--output", captured.to_str().unwrap(), "--quiet"]);
+    assert_eq!(output.status.code(), Some(7));
+    assert!(output.stdout.is_empty() && output.stderr.is_empty());
+    assert_eq!(fs::read(captured).unwrap(), b"ok");
+
+    let output = vemu(&[rom.to_str().unwrap(), "--output", "terminal.out"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn closed_output() {
+    // mov [$04000200], #'x'
+    // jmpl $200
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(ABSOLUTE, 0x0400_0200), (IMMEDIATE, u32::from(b'x')), (IMPLIED, 0)]);
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, 0x200), (IMPLIED, 0), (IMPLIED, 0)]);
+    let rom = write_rom("yes.bin", &rom);
+
+    // Like `vemu --batch yes.bin | head -c 3`
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vemu"))
+        .args([rom.to_str().unwrap(), "--batch", "-d", "terminal", "--timeout", "10"])
+        .stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped())
+        .spawn().unwrap();
+    let mut head = [0; 3];
+    child.stdout.take().unwrap().read_exact(&mut head).unwrap();
+    assert_eq!(&head, b"xxx");
+
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert_eq!(output.status.code(), Some(5), "{stderr}");
+    assert!(stderr.contains("terminal output was closed"), "{stderr}");
+}
+
+#[test]
+fn speed_limit() {
+    let rom = spin();
+    let output = vemu(&[rom.to_str().unwrap(), "-d", "none", "--speed", "20k", "--max-instructions", "4000", "-v"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    let stats = String::from_utf8(output.stderr).unwrap();
+    let seconds: f64 = stats.split(" in ").nth(1).and_then(|rest| rest.split('s').next()).unwrap().parse().unwrap();
+    assert!(seconds >= 0.19, "{stats}");
+    assert!(stats.contains("of the requested 20000 instructions per second"), "{stats}");
+
+    let output = vemu(&[rom.to_str().unwrap(), "--speed", "fast"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn cache_statistics() {
+    let rom = spin();
+    let output = vemu(&[rom.to_str().unwrap(), "-d", "none", "--max-instructions", "100", "--cache", "-v"]);
+
+    assert_eq!(output.status.code(), Some(3));
+    let stats = String::from_utf8(output.stderr).unwrap();
+    // Both instructions fit in one line
+    assert!(stats.contains("Instruction cache: 99 hits, 1 misses"), "{stats}");
+    assert!(stats.contains("Data cache: "), "{stats}");
+
+    let output = vemu(&[rom.to_str().unwrap(), "-d", "none", "--max-instructions", "100", "-v"]);
+    assert!(!String::from_utf8(output.stderr).unwrap().contains("cache:"));
+
+    let output = vemu(&[rom.to_str().unwrap(), "--cache", "--cache-line-size", "24"]);
+    assert_eq!(output.status.code(), Some(2));
+    let output = vemu(&[rom.to_str().unwrap(), "--cache-size", "1024"]);
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn virtual_time() {
+    // add r0, r0, #1 (x2)
+    // mov r1, [$04000210]
+    // jmpl $0000021e
+    let mut rom = Vec::new();
+    for _ in 0..2 {
+        emit(&mut rom, Operation::Add, [(DIRECT, R0), (DIRECT, R0), (IMMEDIATE, 1)]);
+    }
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (ABSOLUTE, 0x0400_0210), (IMPLIED, 0)]);
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, 0x21e), (IMPLIED, 0), (IMPLIED, 0)]);
+    let rom = write_rom("virtual.bin", &rom);
+
+    let run = |extra: &[&str]| {
+        let mut args = vec![rom.to_str().unwrap(), "--batch", "--virtual-time", "--max-instructions", "10"];
+        args.extend_from_slice(extra);
+        vemu(&args)
+    };
+
+    // The clock is last read on the 9th instruction, 9000ns in
+    let first = run(&[]);
+    assert_eq!(first.status.code(), Some(3));
+    let dump = String::from_utf8(first.stderr.clone()).unwrap();
+    assert!(dump.contains("r0  = 00000002, r1  = 00002328"), "{dump}");
+    assert_eq!(run(&[]).stderr, first.stderr);
+
+    // Input typed on the 7th instruction, which has no handler to take it
+    let schedule = write_rom("virtual.schedule", b"6 x\n");
+    let output = run(&["--input-schedule", schedule.to_str().unwrap(), "--crash-report", "-"]);
+    assert_eq!(output.status.code(), Some(1));
+    let report = String::from_utf8(output.stdout).unwrap();
+    assert!(report.contains(r#""code": 1"#) && report.contains(r#""pc": 542,"#) && report.contains(r#""r1": 5000,"#), "{report}");
+}
+
+#[test]
+fn record_and_replay() {
+    // mov r1, [$04000210] until typed input arrives, with no handler to take it
+    let mut rom = Vec::new();
+    emit(&mut rom, Operation::Mov, [(DIRECT, R1), (ABSOLUTE, 0x0400_0210), (IMPLIED, 0)]);
+    emit(&mut rom, Operation::Jmpl, [(ABSOLUTE, 0x200), (IMPLIED, 0), (IMPLIED, 0)]);
+    let rom = write_rom("record.bin", &rom);
+    let schedule = write_rom("record.schedule", b"25 x\n");
+    let recording = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("record.log");
+
+    // The clock follows the host, so only the recording can bring the same readings back
+    let recorded = vemu(&[rom.to_str().unwrap(), "--batch", "--input-schedule", schedule.to_str().unwrap(),
+        "--record", recording.to_str().unwrap(), "--crash-report", "-"]);
+    assert_eq!(recorded.status.code(), Some(1));
+    assert!(String::from_utf8(recorded.stdout.clone()).unwrap().contains(r#""code": 1"#));
+
+    let replayed = vemu(&[rom.to_str().unwrap(), "--batch", "--replay", recording.to_str().unwrap(),
+        "--crash-report", "-"]);
+    assert_eq!(replayed.status.code(), Some(1));
+    assert_eq!(replayed.stdout, recorded.stdout);
+    assert_eq!(replayed.stderr, recorded.stderr);
+}
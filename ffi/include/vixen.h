@@ -0,0 +1,124 @@
+/*
+ * C interface to the Vixen CPU, built from the vixen-ffi crate (libvixen_ffi).
+ *
+ * Every function taking a VixenCpu pointer accepts NULL and reports VIXEN_ERROR_ARGUMENT for it.
+ * A VixenCpu is not thread-safe; use it from one thread at a time.
+ */
+
+#ifndef VIXEN_H
+#define VIXEN_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Memory sizes, the system area is always included */
+#define VIXEN_MEMORY_NONE 72352256u
+#define VIXEN_MEMORY_1M (VIXEN_MEMORY_NONE + 1048576u)
+#define VIXEN_MEMORY_16M (VIXEN_MEMORY_NONE + 16777216u)
+#define VIXEN_MEMORY_64M (VIXEN_MEMORY_NONE + 67108864u)
+
+/* Register identifiers, as encoded in instruction operands */
+#define VIXEN_REG_R0 0x0001u
+#define VIXEN_REG_R1 0x0011u
+#define VIXEN_REG_R2 0x0012u
+#define VIXEN_REG_R3 0x1000u
+#define VIXEN_REG_R4 0x1001u
+#define VIXEN_REG_R5 0x1002u
+#define VIXEN_REG_R6 0x1003u
+#define VIXEN_REG_R7 0x1004u
+#define VIXEN_REG_R8 0x1005u
+#define VIXEN_REG_R9 0x1006u
+#define VIXEN_REG_R10 0x1007u
+#define VIXEN_REG_R11 0x1008u
+#define VIXEN_REG_R12 0x1009u
+#define VIXEN_REG_R13 0x100Au
+#define VIXEN_REG_R14 0x100Bu
+#define VIXEN_REG_SP 0x2000u
+#define VIXEN_REG_PC 0x2001u
+#define VIXEN_REG_SR 0x2002u
+#define VIXEN_REG_PTBR 0x2003u
+#define VIXEN_REG_PFAR 0x2004u
+#define VIXEN_REG_CID 0x2005u
+
+typedef enum VixenStatus {
+    VIXEN_OK = 0,
+    /* The CPU raised an interrupt nobody handled, see vixen_cpu_interrupt() */
+    VIXEN_INTERRUPT = 1,
+    /* NULL pointer, unknown register or read-only register */
+    VIXEN_ERROR_ARGUMENT = -1,
+    /* Memory range outside of the CPU memory */
+    VIXEN_ERROR_RANGE = -2,
+    /* A device already occupies the requested addresses */
+    VIXEN_ERROR_CONFLICT = -3
+} VixenStatus;
+
+/* Results for device callbacks, mirroring the bus errors of the Rust API.
+ * Callbacks return these as int32_t, unknown values count as VIXEN_BUS_INTERNAL_SYSTEM. */
+typedef enum VixenBusStatus {
+    VIXEN_BUS_OK = 0,
+    VIXEN_BUS_PORT_OUT_OF_RANGE = 1,
+    VIXEN_BUS_READ_ONLY = 2,
+    VIXEN_BUS_WRITE_ONLY = 3,
+    /* Raises the asynchronous I/O interrupt */
+    VIXEN_BUS_DEVICE_EVENT = 4,
+    VIXEN_BUS_EMPTY_BUFFER = 5,
+    VIXEN_BUS_INTERNAL_SYSTEM = 6
+} VixenBusStatus;
+
+/*
+ * A bus device implemented by the host. Ports are 32-bit words mapped from base_address onwards.
+ * Any callback may be NULL: reads then fail as write-only, writes as read-only and ticks do nothing.
+ * destroy is called with context when the CPU is freed.
+ */
+typedef struct VixenDevice {
+    void *context;
+    uint32_t base_address;
+    uint32_t port_count;
+    int32_t (*read_port)(void *context, uint32_t index, uint32_t *value);
+    int32_t (*write_port)(void *context, uint32_t index, uint32_t value);
+    int32_t (*tick)(void *context);
+    void (*destroy)(void *context);
+} VixenDevice;
+
+typedef struct VixenCpu VixenCpu;
+
+/* model is a short name such as "kanto" or "johto", NULL picks the default model.
+ * Returns NULL for an unknown model. */
+VixenCpu *vixen_cpu_new(size_t memory_size, const char *model);
+void vixen_cpu_free(VixenCpu *cpu);
+
+/* Copies a ROM image to 0x200, writes the CPU specification and resets the stacks */
+VixenStatus vixen_cpu_load_rom(VixenCpu *cpu, const uint8_t *rom, size_t length);
+
+/* Executes one instruction */
+VixenStatus vixen_cpu_step(VixenCpu *cpu);
+/* Executes up to max_instructions instructions, stopping early on an unhandled interrupt.
+ * executed may be NULL. */
+VixenStatus vixen_cpu_run(VixenCpu *cpu, uint64_t max_instructions, uint64_t *executed);
+
+/* Interrupt code of the last unhandled interrupt, or -1 if the CPU has not stopped on one */
+int32_t vixen_cpu_interrupt(const VixenCpu *cpu);
+/* Writes the stack trace of the last unhandled interrupt as a NUL-terminated string, truncated to
+ * size bytes. Returns the full length without the terminator, 0 if there is no interrupt. */
+size_t vixen_cpu_stack_trace(const VixenCpu *cpu, char *buffer, size_t size);
+
+VixenStatus vixen_cpu_read_register(const VixenCpu *cpu, uint32_t reg, uint32_t *value);
+/* Writing PC moves execution there; CID is read-only */
+VixenStatus vixen_cpu_write_register(VixenCpu *cpu, uint32_t reg, uint32_t value);
+
+/* Physical memory access, bypassing the MMU, caches and devices */
+VixenStatus vixen_cpu_read_memory(const VixenCpu *cpu, uint32_t address, uint8_t *buffer, size_t length);
+VixenStatus vixen_cpu_write_memory(VixenCpu *cpu, uint32_t address, const uint8_t *buffer, size_t length);
+
+/* Takes ownership of device->context, even when registration fails */
+VixenStatus vixen_cpu_add_device(VixenCpu *cpu, const VixenDevice *device);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,97 @@
+use std::ffi::c_void;
+use std::fmt::{Debug, Formatter};
+
+use vixen::BusDevice;
+use vixen::devices::errors::{BusError, BusResult};
+
+// Mirrors VixenDevice in include/vixen.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DeviceCallbacks {
+    pub context: *mut c_void,
+    pub base_address: u32,
+    pub port_count: u32,
+    pub read_port: Option<unsafe extern "C" fn(*mut c_void, u32, *mut u32) -> i32>,
+    pub write_port: Option<unsafe extern "C" fn(*mut c_void, u32, u32) -> i32>,
+    pub tick: Option<unsafe extern "C" fn(*mut c_void) -> i32>,
+    pub destroy: Option<unsafe extern "C" fn(*mut c_void)>
+}
+
+// Status codes are VixenBusStatus values, anything unknown is treated as an internal failure
+fn bus_result(status: i32) -> BusResult<()> {
+    Err(match status {
+        0 => return Ok(()),
+        1 => BusError::PortOutOfRange,
+        2 => BusError::ReadOnly,
+        3 => BusError::WriteOnly,
+        4 => BusError::DeviceEvent,
+        5 => BusError::EmptyBuffer,
+        _ => BusError::InternalSystem
+    })
+}
+
+// A bus device whose ports are served by host callbacks. The context is owned by the device and
+// handed back to `destroy` when the device is dropped.
+pub struct HostDevice {
+    callbacks: DeviceCallbacks
+}
+
+impl HostDevice {
+    /// # Safety
+    /// The callbacks must be safe to call with the context until destroy is called
+    pub unsafe fn new(callbacks: DeviceCallbacks) -> Self {
+        Self {
+            callbacks
+        }
+    }
+}
+
+impl Debug for HostDevice {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostDevice")
+            .field("base_address", &self.callbacks.base_address)
+            .field("port_count", &self.callbacks.port_count)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BusDevice for HostDevice {
+    fn get_port_count(&self) -> u32 {
+        self.callbacks.port_count
+    }
+
+    fn get_base_address(&self) -> u32 {
+        self.callbacks.base_address
+    }
+
+    fn read_port(&mut self, index: u32) -> BusResult<u32> {
+        let read_port = self.callbacks.read_port.ok_or(BusError::WriteOnly)?;
+        let mut value = 0;
+        // SAFETY: upheld by the caller of HostDevice::new
+        bus_result(unsafe { read_port(self.callbacks.context, index, &raw mut value) })?;
+        Ok(value)
+    }
+
+    fn write_port(&mut self, index: u32, data: u32) -> BusResult<()> {
+        let write_port = self.callbacks.write_port.ok_or(BusError::ReadOnly)?;
+        // SAFETY: upheld by the caller of HostDevice::new
+        bus_result(unsafe { write_port(self.callbacks.context, index, data) })
+    }
+
+    fn tick(&mut self) -> BusResult<()> {
+        match self.callbacks.tick {
+            // SAFETY: upheld by the caller of HostDevice::new
+            Some(tick) => bus_result(unsafe { tick(self.callbacks.context) }),
+            None => Ok(())
+        }
+    }
+}
+
+impl Drop for HostDevice {
+    fn drop(&mut self) {
+        if let Some(destroy) = self.callbacks.destroy {
+            // SAFETY: upheld by the caller of HostDevice::new, this is the last use of the context
+            unsafe { destroy(self.callbacks.context) }
+        }
+    }
+}
@@ -0,0 +1,289 @@
+// C interface to the Vixen CPU. include/vixen.h is the reference for every function in here, keep
+// both in sync. Functions never unwind into C: they report failures through VixenStatus codes.
+
+mod device;
+
+use std::ffi::{c_char, CStr};
+use std::slice;
+
+use vixen::core::{Interrupt, MemoryCell, Operand, StackTrace};
+use vixen::core::registers::RegisterId;
+use vixen::models::{find_model, DEFAULT_MODEL};
+use vixen::CPU;
+
+use crate::device::{DeviceCallbacks, HostDevice};
+
+// Mirrors VixenStatus in include/vixen.h
+const VIXEN_OK: i32 = 0;
+const VIXEN_INTERRUPT: i32 = 1;
+const VIXEN_ERROR_ARGUMENT: i32 = -1;
+const VIXEN_ERROR_RANGE: i32 = -2;
+const VIXEN_ERROR_CONFLICT: i32 = -3;
+
+pub struct VixenCpu {
+    cpu: CPU<'static>,
+    // Unhandled interrupt the last step or run stopped on
+    interrupt: Option<Interrupt>
+}
+
+impl VixenCpu {
+    fn step(&mut self) -> i32 {
+        self.interrupt = None;
+
+        match self.cpu.tick() {
+            Ok(()) => {
+                self.cpu.program_counter = self.cpu.program_counter.wrapping_add(15);
+                VIXEN_OK
+            },
+            Err(interrupt) => {
+                self.interrupt = Some(interrupt);
+                VIXEN_INTERRUPT
+            }
+        }
+    }
+}
+
+/// # Safety
+/// `model` must be NULL or a valid NUL-terminated string
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_new(memory_size: usize, model: *const c_char) -> *mut VixenCpu {
+    let model = if model.is_null() {
+        DEFAULT_MODEL
+    } else {
+        // SAFETY: checked for NULL above, validity is up to the caller
+        let name = unsafe { CStr::from_ptr(model) };
+        match name.to_str().ok().and_then(find_model) {
+            Some(model) => model,
+            None => return std::ptr::null_mut()
+        }
+    };
+
+    Box::into_raw(Box::new(VixenCpu {
+        cpu: CPU::new_with_model(memory_size, model),
+        interrupt: None
+    }))
+}
+
+/// # Safety
+/// `cpu` must be NULL or a pointer returned by `vixen_cpu_new` that was not freed yet
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_free(cpu: *mut VixenCpu) {
+    if !cpu.is_null() {
+        // SAFETY: the pointer came from Box::into_raw in vixen_cpu_new
+        drop(unsafe { Box::from_raw(cpu) });
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `rom` must point to length readable bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_load_rom(cpu: *mut VixenCpu, rom: *const u8, length: usize) -> i32 {
+    // SAFETY: upheld by the caller
+    let (Some(cpu), Some(rom)) = (unsafe { cpu.as_mut() }, unsafe { bytes(rom, length) }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    cpu.interrupt = None;
+    match cpu.cpu.load_rom(rom) {
+        Ok(()) => VIXEN_OK,
+        Err(_) => VIXEN_ERROR_RANGE
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_step(cpu: *mut VixenCpu) -> i32 {
+    // SAFETY: upheld by the caller
+    match unsafe { cpu.as_mut() } {
+        Some(cpu) => cpu.step(),
+        None => VIXEN_ERROR_ARGUMENT
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `executed` must be NULL or writable
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_run(cpu: *mut VixenCpu, max_instructions: u64, executed: *mut u64) -> i32 {
+    // SAFETY: upheld by the caller
+    let Some(cpu) = (unsafe { cpu.as_mut() }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    let mut count = 0;
+    let mut status = VIXEN_OK;
+    while count < max_instructions {
+        status = cpu.step();
+        if status != VIXEN_OK {
+            break;
+        }
+        count += 1;
+    }
+
+    // SAFETY: upheld by the caller
+    if let Some(executed) = unsafe { executed.as_mut() } {
+        *executed = count;
+    }
+    status
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_interrupt(cpu: *const VixenCpu) -> i32 {
+    // SAFETY: upheld by the caller
+    match unsafe { cpu.as_ref() }.and_then(|cpu| cpu.interrupt) {
+        // Interrupt codes are a single byte
+        Some(interrupt) => i32::try_from(u32::from(interrupt)).unwrap_or(-1),
+        None => -1
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `buffer` must be NULL or point to size writable bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_stack_trace(cpu: *const VixenCpu, buffer: *mut c_char, size: usize) -> usize {
+    // SAFETY: upheld by the caller
+    let trace = unsafe { cpu.as_ref() }
+        .and_then(|cpu| cpu.interrupt.map(|interrupt| StackTrace::new(interrupt, &cpu.cpu).to_string()))
+        .unwrap_or_default();
+
+    if !buffer.is_null() && size > 0 {
+        let copied = trace.len().min(size - 1);
+        // SAFETY: buffer holds at least size bytes, copied + 1 <= size
+        unsafe {
+            std::ptr::copy_nonoverlapping(trace.as_ptr().cast::<c_char>(), buffer, copied);
+            *buffer.add(copied) = 0;
+        }
+    }
+
+    trace.len()
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `value` must be writable
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_read_register(cpu: *const VixenCpu, register: u32, value: *mut u32) -> i32 {
+    // SAFETY: upheld by the caller
+    let (Some(cpu), Some(value)) = (unsafe { cpu.as_ref() }, unsafe { value.as_mut() }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+    let Ok(register) = RegisterId::try_from(register) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    *value = cpu.cpu.get_register(register);
+    VIXEN_OK
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_write_register(cpu: *mut VixenCpu, register: u32, value: u32) -> i32 {
+    // SAFETY: upheld by the caller
+    let Some(cpu) = (unsafe { cpu.as_mut() }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    match RegisterId::try_from(register) {
+        // Guest writes to PC jump past the current instruction, the host sets it directly
+        Ok(RegisterId::Pc) => {
+            cpu.cpu.program_counter = value;
+            VIXEN_OK
+        },
+        Ok(register) => match Operand::Register(register, 0).write_word(&mut cpu.cpu, value) {
+            Ok(()) => VIXEN_OK,
+            Err(_) => VIXEN_ERROR_ARGUMENT
+        },
+        Err(_) => VIXEN_ERROR_ARGUMENT
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `buffer` must point to length writable bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_read_memory(cpu: *const VixenCpu, address: u32, buffer: *mut u8, length: usize) -> i32 {
+    // SAFETY: upheld by the caller
+    let (Some(cpu), Some(buffer)) = (unsafe { cpu.as_ref() }, unsafe { bytes_mut(buffer, length) }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    match memory_range(&cpu.cpu, address, length) {
+        Some(range) => {
+            buffer.copy_from_slice(&cpu.cpu.memory[range]);
+            VIXEN_OK
+        },
+        None => VIXEN_ERROR_RANGE
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `buffer` must point to length readable bytes
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_write_memory(cpu: *mut VixenCpu, address: u32, buffer: *const u8, length: usize) -> i32 {
+    // SAFETY: upheld by the caller
+    let (Some(cpu), Some(buffer)) = (unsafe { cpu.as_mut() }, unsafe { bytes(buffer, length) }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    match memory_range(&cpu.cpu, address, length) {
+        Some(range) => {
+            cpu.cpu.memory[range].copy_from_slice(buffer);
+            VIXEN_OK
+        },
+        None => VIXEN_ERROR_RANGE
+    }
+}
+
+/// # Safety
+/// `cpu` as for `vixen_cpu_free`, `device` must be NULL or readable and its callbacks must be safe
+/// to call with its context until destroy is called
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn vixen_cpu_add_device(cpu: *mut VixenCpu, device: *const DeviceCallbacks) -> i32 {
+    // SAFETY: upheld by the caller
+    let Some(device) = (unsafe { device.as_ref() }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+    // The context is ours from here on, dropping the device on failure destroys it
+    // SAFETY: upheld by the caller
+    let device = unsafe { HostDevice::new(*device) };
+
+    // SAFETY: upheld by the caller
+    let Some(cpu) = (unsafe { cpu.as_mut() }) else {
+        return VIXEN_ERROR_ARGUMENT;
+    };
+
+    match cpu.cpu.register_devices(vec![Box::new(device)]) {
+        Ok(()) => VIXEN_OK,
+        Err(_) => VIXEN_ERROR_CONFLICT
+    }
+}
+
+fn memory_range(cpu: &CPU, address: u32, length: usize) -> Option<std::ops::Range<usize>> {
+    let start = address as usize;
+    let end = start.checked_add(length)?;
+    (end <= cpu.memory.len()).then_some(start..end)
+}
+
+// Empty buffers may come with a NULL pointer
+unsafe fn bytes<'a>(pointer: *const u8, length: usize) -> Option<&'a [u8]> {
+    if length == 0 {
+        Some(&[])
+    } else if pointer.is_null() {
+        None
+    } else {
+        // SAFETY: upheld by the caller
+        Some(unsafe { slice::from_raw_parts(pointer, length) })
+    }
+}
+
+unsafe fn bytes_mut<'a>(pointer: *mut u8, length: usize) -> Option<&'a mut [u8]> {
+    if length == 0 {
+        Some(&mut [])
+    } else if pointer.is_null() {
+        None
+    } else {
+        // SAFETY: upheld by the caller
+        Some(unsafe { slice::from_raw_parts_mut(pointer, length) })
+    }
+}
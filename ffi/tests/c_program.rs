@@ -0,0 +1,41 @@
+// Builds tests/ffi_test.c against the shared library and runs it. Skipped when no C compiler is
+// available, set CC to pick a specific one.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+fn library_directory() -> PathBuf {
+    // Test binaries live in target/<profile>/deps, next to the cdylib
+    env::current_exe().unwrap().parent().unwrap().to_path_buf()
+}
+
+#[test]
+fn c_test_program_passes() {
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let libraries = library_directory();
+    let binary = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ffi_test");
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc"));
+
+    let status = Command::new(&cc)
+        .arg(manifest.join("tests/ffi_test.c"))
+        .arg("-I").arg(manifest.join("include"))
+        .arg("-L").arg(&libraries)
+        .arg("-lvixen_ffi")
+        .arg("-o").arg(&binary)
+        .status();
+
+    let Ok(status) = status else {
+        eprintln!("skipping: no C compiler ({cc}) found");
+        return;
+    };
+    assert!(status.success(), "ffi_test.c failed to compile");
+
+    let output = Command::new(&binary)
+        .env("LD_LIBRARY_PATH", &libraries)
+        .env("DYLD_LIBRARY_PATH", &libraries)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success(), "ffi_test failed:\n{}", String::from_utf8_lossy(&output.stderr));
+}
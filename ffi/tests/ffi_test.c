@@ -0,0 +1,185 @@
+/*
+ * Exercises the C interface end to end: a host device feeds a value to a small guest program,
+ * which writes a result back to the device and then traps on a checked overflow.
+ *
+ * Built and run by tests/c_program.rs, or by hand:
+ *   cargo build -p vixen-ffi
+ *   cc ffi/tests/ffi_test.c -Iffi/include -Ltarget/debug -lvixen_ffi -o ffi_test
+ *   LD_LIBRARY_PATH=target/debug ./ffi_test
+ */
+
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "vixen.h"
+
+#define CHECK(condition) do { \
+    if (!(condition)) { \
+        fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #condition); \
+        exit(1); \
+    } \
+} while (0)
+
+#define DEVICE_BASE 0x04000300u
+
+#define IMMEDIATE 0x0u
+#define DIRECT 0x1u
+#define ABSOLUTE 0x3u
+#define IMPLIED 0x5u
+
+#define OP_ADD 0x010u
+#define OP_MOV 0x050u
+#define OP_ADDV 0x0B0u
+
+typedef struct Mailbox {
+    uint32_t input;
+    uint32_t output;
+    int reads;
+    int *destroyed;
+} Mailbox;
+
+static int32_t mailbox_read(void *context, uint32_t index, uint32_t *value) {
+    Mailbox *mailbox = context;
+    if (index != 0) {
+        return VIXEN_BUS_WRITE_ONLY;
+    }
+    mailbox->reads++;
+    *value = mailbox->input;
+    return VIXEN_BUS_OK;
+}
+
+static int32_t mailbox_write(void *context, uint32_t index, uint32_t value) {
+    Mailbox *mailbox = context;
+    if (index != 1) {
+        return VIXEN_BUS_READ_ONLY;
+    }
+    mailbox->output = value;
+    return VIXEN_BUS_OK;
+}
+
+static void mailbox_destroy(void *context) {
+    Mailbox *mailbox = context;
+    (*mailbox->destroyed)++;
+}
+
+static size_t emit(uint8_t *rom, size_t at, uint32_t opcode, const uint32_t modes[3], const uint32_t operands[3]) {
+    uint32_t word = opcode << 12 | modes[0] | modes[1] << 4 | modes[2] << 8;
+    rom[at++] = word & 0xff;
+    rom[at++] = (word >> 8) & 0xff;
+    rom[at++] = (word >> 16) & 0xff;
+    for (int i = 0; i < 3; i++) {
+        for (int byte = 0; byte < 4; byte++) {
+            rom[at++] = (operands[i] >> (byte * 8)) & 0xff;
+        }
+    }
+    return at;
+}
+
+int main(void) {
+    CHECK(vixen_cpu_new(VIXEN_MEMORY_1M, "no-such-model") == NULL);
+    CHECK(vixen_cpu_step(NULL) == VIXEN_ERROR_ARGUMENT);
+
+    VixenCpu *cpu = vixen_cpu_new(VIXEN_MEMORY_1M, "johto");
+    CHECK(cpu != NULL);
+
+    /*
+     * mov r1, [DEVICE_BASE]
+     * add r2, r1, #5
+     * mov [DEVICE_BASE + 4], r2
+     * mov r3, #$7fffffff
+     * addv r4, r3, #1
+     */
+    uint8_t rom[5 * 15];
+    size_t size = 0;
+    size = emit(rom, size, OP_MOV, (uint32_t[]) { DIRECT, ABSOLUTE, IMPLIED }, (uint32_t[]) { VIXEN_REG_R1, DEVICE_BASE, 0 });
+    size = emit(rom, size, OP_ADD, (uint32_t[]) { DIRECT, DIRECT, IMMEDIATE }, (uint32_t[]) { VIXEN_REG_R2, VIXEN_REG_R1, 5 });
+    size = emit(rom, size, OP_MOV, (uint32_t[]) { ABSOLUTE, DIRECT, IMPLIED }, (uint32_t[]) { DEVICE_BASE + 4, VIXEN_REG_R2, 0 });
+    size = emit(rom, size, OP_MOV, (uint32_t[]) { DIRECT, IMMEDIATE, IMPLIED }, (uint32_t[]) { VIXEN_REG_R3, 0x7fffffff, 0 });
+    size = emit(rom, size, OP_ADDV, (uint32_t[]) { DIRECT, DIRECT, IMMEDIATE }, (uint32_t[]) { VIXEN_REG_R4, VIXEN_REG_R3, 1 });
+    CHECK(vixen_cpu_load_rom(cpu, rom, size) == VIXEN_OK);
+
+    int destroyed = 0;
+    Mailbox *mailbox = calloc(1, sizeof(Mailbox));
+    mailbox->input = 37;
+    mailbox->destroyed = &destroyed;
+    VixenDevice device = {
+        .context = mailbox,
+        .base_address = DEVICE_BASE,
+        .port_count = 2,
+        .read_port = mailbox_read,
+        .write_port = mailbox_write,
+        .tick = NULL,
+        .destroy = mailbox_destroy
+    };
+    CHECK(vixen_cpu_add_device(cpu, &device) == VIXEN_OK);
+
+    /* Overlapping devices are rejected, and the rejected one is destroyed right away */
+    Mailbox *duplicate = calloc(1, sizeof(Mailbox));
+    duplicate->destroyed = &destroyed;
+    device.context = duplicate;
+    CHECK(vixen_cpu_add_device(cpu, &device) == VIXEN_ERROR_CONFLICT);
+    CHECK(destroyed == 1);
+    free(duplicate);
+
+    /* Run until the checked add traps */
+    CHECK(vixen_cpu_interrupt(cpu) == -1);
+    CHECK(vixen_cpu_stack_trace(cpu, NULL, 0) == 0);
+
+    uint64_t executed = 0;
+    CHECK(vixen_cpu_run(cpu, 100, &executed) == VIXEN_INTERRUPT);
+    CHECK(executed == 4);
+    CHECK(vixen_cpu_interrupt(cpu) == 0x15);
+    CHECK(mailbox->reads == 1);
+    CHECK(mailbox->output == 42);
+
+    uint32_t value = 0;
+    CHECK(vixen_cpu_read_register(cpu, VIXEN_REG_PC, &value) == VIXEN_OK);
+    CHECK(value == 0x200 + 4 * 15);
+    CHECK(vixen_cpu_read_register(cpu, VIXEN_REG_R4, &value) == VIXEN_OK);
+    CHECK(value == 0);
+
+    size_t length = vixen_cpu_stack_trace(cpu, NULL, 0);
+    CHECK(length > 0);
+    char *trace = malloc(length + 1);
+    CHECK(vixen_cpu_stack_trace(cpu, trace, length + 1) == length);
+    CHECK(strlen(trace) == length);
+    CHECK(strstr(trace, "15 (Arithmetic overflow)") != NULL);
+    CHECK(strstr(trace, "addv r4, r3, #$1") != NULL);
+
+    /* Truncated copies stay NUL-terminated */
+    char small[8];
+    CHECK(vixen_cpu_stack_trace(cpu, small, sizeof small) == length);
+    CHECK(strlen(small) == sizeof small - 1);
+    free(trace);
+
+    /* Registers */
+    CHECK(vixen_cpu_write_register(cpu, VIXEN_REG_R5, 0xdeadbeef) == VIXEN_OK);
+    CHECK(vixen_cpu_read_register(cpu, VIXEN_REG_R5, &value) == VIXEN_OK);
+    CHECK(value == 0xdeadbeef);
+    CHECK(vixen_cpu_write_register(cpu, VIXEN_REG_CID, 1) == VIXEN_ERROR_ARGUMENT);
+    CHECK(vixen_cpu_read_register(cpu, 0x9999, &value) == VIXEN_ERROR_ARGUMENT);
+
+    /* Memory */
+    const uint8_t pattern[4] = { 1, 2, 3, 4 };
+    uint8_t readback[4] = { 0 };
+    CHECK(vixen_cpu_write_memory(cpu, 0x04600000, pattern, sizeof pattern) == VIXEN_OK);
+    CHECK(vixen_cpu_read_memory(cpu, 0x04600000, readback, sizeof readback) == VIXEN_OK);
+    CHECK(memcmp(pattern, readback, sizeof pattern) == 0);
+    CHECK(vixen_cpu_read_memory(cpu, VIXEN_MEMORY_1M - 2, readback, sizeof readback) == VIXEN_ERROR_RANGE);
+
+    /* Jump back to the start and single-step through the device read again */
+    CHECK(vixen_cpu_write_register(cpu, VIXEN_REG_PC, 0x200) == VIXEN_OK);
+    mailbox->input = 7;
+    CHECK(vixen_cpu_step(cpu) == VIXEN_OK);
+    CHECK(vixen_cpu_interrupt(cpu) == -1);
+    CHECK(vixen_cpu_read_register(cpu, VIXEN_REG_R1, &value) == VIXEN_OK);
+    CHECK(value == 7);
+
+    vixen_cpu_free(cpu);
+    CHECK(destroyed == 2);
+    free(mailbox);
+
+    puts("ffi_test: ok");
+    return 0;
+}
@@ -0,0 +1,38 @@
+#![no_main]
+
+// Run with `cargo +nightly fuzz run rom` from the repository root.
+// Any Rust panic is a bug: guest programs may only ever fault with an interrupt.
+
+use libfuzzer_sys::fuzz_target;
+use vixen::core::instruction::Operation;
+use vixen::cpu::Decoder;
+use vixen::{CPU, MEMORY_1M, MEMORY_NONE};
+
+const MAX_TICKS: usize = 4096;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((selector, rom)) = data.split_first() else {
+        return;
+    };
+
+    let memory_size = match selector % 3 {
+        0 => 0x1000,
+        1 => MEMORY_NONE,
+        _ => MEMORY_1M
+    };
+
+    let mut cpu = CPU::new(memory_size);
+    if cpu.load_rom(rom).is_err() {
+        return;
+    }
+
+    for _ in 0..MAX_TICKS {
+        // hlt spins forever by design, stop before executing it
+        let halted = cpu.decode_instruction(cpu.program_counter)
+            .is_ok_and(|i| i.operation == u32::from(u16::from(Operation::Hlt)));
+        if halted || cpu.tick().is_err() {
+            break;
+        }
+        cpu.program_counter = cpu.program_counter.wrapping_add(15);
+    }
+});
@@ -6,7 +6,7 @@ strcopy:
     ; Check whether the string length is zero
     ; If so return
     cmp r1, #0
-    bne #copy
+    bne copy
     ret
 
     copy:
@@ -19,4 +19,4 @@ strcopy:
         dec r1
 
         ; Move next
-        jmp #strcopy
+        jmpl strcopy